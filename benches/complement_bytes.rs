@@ -0,0 +1,47 @@
+//! Throughput of writing bitwise complement of a 1 MiB byte field through a buffered writer,
+//! as done for `Order::Descending`: one `write()` call per byte, compared with
+//! `write_complement_bytes()`, which inverts the input in stack chunks.
+//! Run with `cargo bench --bench complement_bytes`.
+
+use ordcode::{
+    buf::{IoWriter, WriteBytes},
+    bytes_esc::write_complement_bytes,
+};
+use std::hint::black_box;
+use std::io::{sink, BufWriter};
+use std::time::{Duration, Instant};
+
+const ROUNDS: u32 = 100;
+const LEN: usize = 1024 * 1024;
+
+fn write_bytewise(mut writer: impl WriteBytes, input: &[u8]) -> ordcode::Result {
+    for v in input {
+        writer.write(&[!*v])?;
+    }
+    Ok(())
+}
+
+fn bench(
+    name: &str,
+    input: &[u8],
+    mut f: impl FnMut(&mut IoWriter<BufWriter<std::io::Sink>>, &[u8]),
+) {
+    let mut time = Duration::default();
+    for _ in 0..ROUNDS {
+        let mut writer = IoWriter::new(BufWriter::new(sink()));
+        let start = Instant::now();
+        f(&mut writer, black_box(input));
+        black_box(writer.finalize().unwrap());
+        time += start.elapsed();
+    }
+    let mb = f64::from(ROUNDS) * input.len() as f64 / 1e6;
+    println!("{:<10} write {:>8.1} MB/s", name, mb / time.as_secs_f64());
+}
+
+fn main() {
+    let input: Vec<u8> = (0..LEN).map(|i| (i * 31) as u8).collect();
+    bench("bytewise", &input, |w, v| write_bytewise(w, v).unwrap());
+    bench("chunked", &input, |w, v| {
+        write_complement_bytes(w, v).unwrap()
+    });
+}
@@ -0,0 +1,34 @@
+//! Throughput of `invert_buffer()`, which inverts 64-bit words, compared with inverting
+//! byte by byte, on a 64 KiB buffer.
+//! Run with `cargo bench --bench invert_buffer`.
+
+use ordcode::primitives::invert_buffer;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const ROUNDS: u32 = 2_000;
+const LEN: usize = 64 * 1024;
+
+fn invert_bytewise(buf: &mut [u8]) {
+    for b in buf {
+        *b = !*b;
+    }
+}
+
+fn bench(name: &str, mut f: impl FnMut(&mut [u8])) {
+    let mut buf: Vec<u8> = (0..LEN).map(|i| (i * 31) as u8).collect();
+    let mut time = Duration::default();
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        f(black_box(&mut buf));
+        time += start.elapsed();
+        black_box(&buf);
+    }
+    let mb = f64::from(ROUNDS) * LEN as f64 / 1e6;
+    println!("{:<10} invert {:>9.1} MB/s", name, mb / time.as_secs_f64());
+}
+
+fn main() {
+    bench("bytewise", invert_bytewise);
+    bench("words", invert_buffer);
+}
@@ -0,0 +1,56 @@
+//! Throughput of serialization with `NativeBinaryRaw` parameters, where integers are written
+//! as is, compared with `NativeBinary` and `PortableBinary`. Run with `cargo bench --bench native_binary`.
+
+use ordcode::{
+    params::{NativeBinary, NativeBinaryRaw, PortableBinary, SerializerParams},
+    DeBytesReader, DeBytesWriter, Deserializer, Serializer,
+};
+use serde::{Deserialize, Serialize};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const ROUNDS: u32 = 20;
+
+fn bench<P: SerializerParams>(name: &str, value: &(Vec<i64>, Vec<i32>, Vec<u64>), params: P) {
+    let size = ordcode::calc_size(value, params).unwrap();
+    let mut buf = vec![0_u8; size];
+    let mut ser_time = Duration::default();
+    let mut de_time = Duration::default();
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        let mut writer = DeBytesWriter::new(&mut buf);
+        black_box(value)
+            .serialize(&mut Serializer::new(&mut writer, params))
+            .unwrap();
+        ser_time += start.elapsed();
+
+        let start = Instant::now();
+        let mut reader = DeBytesReader::new(black_box(&buf));
+        let rt = <(Vec<i64>, Vec<i32>, Vec<u64>)>::deserialize(&mut Deserializer::new(
+            &mut reader,
+            params,
+        ))
+        .unwrap();
+        de_time += start.elapsed();
+        assert_eq!(&rt, value);
+    }
+    let mb = f64::from(ROUNDS) * size as f64 / 1e6;
+    println!(
+        "{:<16} serialize {:>8.1} MB/s, deserialize {:>8.1} MB/s",
+        name,
+        mb / ser_time.as_secs_f64(),
+        mb / de_time.as_secs_f64()
+    );
+}
+
+fn main() {
+    let n = 1_000_000_i64;
+    let value = (
+        (-n..n).collect::<Vec<i64>>(),
+        (-n..n).map(|v| v as i32 * 1000).collect::<Vec<i32>>(),
+        (0..n as u64).collect::<Vec<u64>>(),
+    );
+    bench("NativeBinaryRaw", &value, NativeBinaryRaw);
+    bench("NativeBinary", &value, NativeBinary);
+    bench("PortableBinary", &value, PortableBinary);
+}
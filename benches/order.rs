@@ -0,0 +1,57 @@
+//! Throughput of primitive serialization with `DescendingOrder` parameters, compared with
+//! `AscendingOrder`, and with ascending serialization followed by inverting the buffer.
+//! Order is resolved at compile time, so the first two should run at the same speed.
+//! Run with `cargo bench --bench order`.
+
+use ordcode::{
+    params::{AscendingOrder, DescendingOrder, EncodingParams},
+    primitives::{invert_buffer, SerializableValue},
+    DeBytesWriter,
+};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const ROUNDS: u32 = 200;
+const COUNT: usize = 10_000;
+
+fn encode<P: EncodingParams>(values: &[(u64, i32, f64)], buf: &mut [u8], params: P) {
+    let mut writer = DeBytesWriter::new(buf);
+    for (a, b, c) in values {
+        a.to_writer(&mut writer, params).unwrap();
+        b.to_writer(&mut writer, params).unwrap();
+        c.to_writer(&mut writer, params).unwrap();
+    }
+}
+
+fn bench(name: &str, values: &[(u64, i32, f64)], mut f: impl FnMut(&[(u64, i32, f64)], &mut [u8])) {
+    let mut buf = vec![0_u8; values.len() * 20];
+    let mut time = Duration::default();
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        f(black_box(values), &mut buf);
+        time += start.elapsed();
+        black_box(&buf);
+    }
+    let mb = f64::from(ROUNDS) * buf.len() as f64 / 1e6;
+    println!(
+        "{:<16} serialize {:>8.1} MB/s",
+        name,
+        mb / time.as_secs_f64()
+    );
+}
+
+fn main() {
+    let values: Vec<(u64, i32, f64)> = (0..COUNT)
+        .map(|i| (i as u64 * 7919, i as i32 - 5000, i as f64 / 3.0))
+        .collect();
+    bench("Ascending", &values, |v, buf| {
+        encode(v, buf, AscendingOrder)
+    });
+    bench("Descending", &values, |v, buf| {
+        encode(v, buf, DescendingOrder)
+    });
+    bench("Ascending+invert", &values, |v, buf| {
+        encode(v, buf, AscendingOrder);
+        invert_buffer(buf);
+    });
+}
@@ -0,0 +1,62 @@
+//! Throughput of deserialization of short sequences with single-byte sequence lengths
+//! ([`ByteTailLenEncoder`]), compared with varint lengths. Run with `cargo bench --bench seq_len`.
+
+use ordcode::{
+    params::{AscendingOrder, ByteTailLenEncoder, EncodingParams, Endianness, SerializerParams},
+    varint::VarIntDiscrEncoder,
+    DeBytesReader, DeBytesWriter, Deserializer, Order, Serializer,
+};
+use serde::{Deserialize, Serialize};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const ROUNDS: u32 = 20_000;
+
+#[derive(Copy, Clone, Default)]
+struct ByteLen;
+
+impl EncodingParams for ByteLen {
+    const ORDER: Order = Order::Ascending;
+    const ENDIANNESS: Endianness = Endianness::Big;
+}
+
+impl SerializerParams for ByteLen {
+    type SeqLenEncoder = ByteTailLenEncoder;
+    type DiscriminantEncoder = VarIntDiscrEncoder;
+}
+
+type Value = Vec<(String, Vec<u8>)>;
+
+fn bench<P: SerializerParams>(name: &str, value: &Value, params: P) {
+    let size = ordcode::calc_size(value, params).unwrap();
+    let mut buf = vec![0_u8; size];
+    let mut writer = DeBytesWriter::new(&mut buf);
+    value
+        .serialize(&mut Serializer::new(&mut writer, params))
+        .unwrap();
+    writer.is_complete().unwrap();
+
+    let mut de_time = Duration::default();
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        let mut reader = DeBytesReader::new(black_box(&buf));
+        let rt = Value::deserialize(&mut Deserializer::new(&mut reader, params)).unwrap();
+        de_time += start.elapsed();
+        assert_eq!(&rt, value);
+    }
+    let mb = f64::from(ROUNDS) * size as f64 / 1e6;
+    println!(
+        "{:<16} deserialize {:>8.1} MB/s",
+        name,
+        mb / de_time.as_secs_f64()
+    );
+}
+
+fn main() {
+    // all lengths, including the outer one, must fit into a single byte
+    let value: Value = (0..200_u8)
+        .map(|i| (format!("key{}", i), vec![i; usize::from(i) % 16]))
+        .collect();
+    bench("ByteTailLen", &value, ByteLen);
+    bench("VarIntTailLen", &value, AscendingOrder);
+}
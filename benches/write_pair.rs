@@ -0,0 +1,60 @@
+//! Throughput of writing strings with single-byte lengths to `DeBytesWriter`, with separate
+//! `write()` and `write_tail()` calls compared with fused `write_pair()`, and of serializing
+//! a string map, whose sorted entries are written with `write_pair()`.
+//! Run with `cargo bench --bench write_pair`.
+
+use ordcode::{
+    buf::{TailWriteBytes, WriteBytes},
+    new_ser_asc, DeBytesWriter,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const ROUNDS: u32 = 200;
+const COUNT: usize = 10_000;
+
+fn bench(name: &str, size: usize, mut f: impl FnMut(&mut [u8])) {
+    let mut buf = vec![0_u8; size];
+    let mut time = Duration::default();
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        f(&mut buf);
+        time += start.elapsed();
+        black_box(&buf);
+    }
+    let mb = f64::from(ROUNDS) * size as f64 / 1e6;
+    println!(
+        "{:<16} serialize {:>8.1} MB/s",
+        name,
+        mb / time.as_secs_f64()
+    );
+}
+
+fn main() {
+    let strings: Vec<String> = (0..COUNT).map(|i| format!("key-{}", i)).collect();
+    let size = strings.iter().map(|s| s.len() + 1).sum();
+    bench("separate", size, |buf| {
+        let mut writer = DeBytesWriter::new(buf);
+        for s in black_box(&strings) {
+            writer.write(s.as_bytes()).unwrap();
+            writer.write_tail(&[s.len() as u8]).unwrap();
+        }
+    });
+    bench("write_pair", size, |buf| {
+        let mut writer = DeBytesWriter::new(buf);
+        for s in black_box(&strings) {
+            writer.write_pair(s.as_bytes(), &[s.len() as u8]).unwrap();
+        }
+    });
+
+    let map: HashMap<&str, &str> = strings.iter().map(|s| (s.as_str(), s.as_str())).collect();
+    let size = ordcode::calc_size_asc(&map).unwrap();
+    bench("string map", size, |buf| {
+        let mut writer = DeBytesWriter::new(buf);
+        black_box(&map)
+            .serialize(&mut new_ser_asc(&mut writer))
+            .unwrap();
+    });
+}
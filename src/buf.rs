@@ -1,6 +1,7 @@
 //! Types and traits for working with serialization and deserialization buffers
 //!
-use crate::{Error, Result};
+use crate::{params::LengthEncoder, varint::VarIntTailLenEncoder, Error, Result};
+use core::ops::Range;
 
 /// Simple byte reader from buffer
 ///
@@ -28,14 +29,22 @@ pub trait ReadBytes {
 
     /// Check if buffer is fully consumed (empty)
     fn is_complete(&mut self) -> Result {
-        if self.remaining_buffer().is_empty() {
-            Ok(())
-        } else {
-            Err(Error::BufferUnderflow)
+        match self.remaining_buffer().len() {
+            0 => Ok(()),
+            remaining => Err(Error::BufferUnderflow { remaining }),
         }
     }
 }
 
+/// Reader which can lend bytes of the underlying input for its whole lifetime `'de`
+///
+/// Allows deserializer created with [`Deserializer::new_borrowed()`](crate::Deserializer::new_borrowed)
+/// to deserialize `&'de str` and `&'de [u8]` without copying.
+pub trait BorrowReadBytes<'de>: ReadBytes {
+    /// Get `n` bytes borrowed from the underlying input, advance buffer head by `n` bytes
+    fn read_borrowed(&mut self, n: usize) -> Result<&'de [u8]>;
+}
+
 /// Trait for reading from the tail of byte buffer
 pub trait TailReadBytes: ReadBytes {
     fn peek_tail<F, R>(&mut self, n: usize, f: F) -> Result<R>
@@ -75,6 +84,16 @@ where
     }
 }
 
+// forwarding for being able to use `&mut BorrowReadBytes` in place of `BorrowReadBytes`
+impl<'de, T> BorrowReadBytes<'de> for &mut T
+where
+    T: BorrowReadBytes<'de>,
+{
+    fn read_borrowed(&mut self, n: usize) -> Result<&'de [u8]> {
+        (*self).read_borrowed(n)
+    }
+}
+
 // forwarding for being able to use `&mut ReadBytes` in place of `ReadBytes`
 impl<'a, T> TailReadBytes for &'a mut T
 where
@@ -93,16 +112,137 @@ where
 
 /// Adapter type which implements double-ended read buffer over byte slice
 ///
-/// Implements [`ReadBytes`], [`TailReadBytes`] traits and intended to be used as input to [`Deserializer`](crate::Deserializer).
+/// Implements [`ReadBytes`], [`TailReadBytes`] traits and intended to be used as
+/// input to [`Deserializer`](crate::Deserializer).
+#[derive(Clone)]
 pub struct DeBytesReader<'a> {
     buf: &'a [u8],
+    head: usize,
+    // offset of the first error returned by the reader
+    error_pos: Option<usize>,
+}
+
+impl core::fmt::Debug for DeBytesReader<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("DeBytesReader")
+            .field("head_pos", &self.head_pos())
+            .field("tail_pos", &self.tail_pos())
+            .finish()
+    }
 }
 
 impl<'a> DeBytesReader<'a> {
     /// Constructs reader from provided byte slice
     #[must_use]
     pub fn new(buf: &'a [u8]) -> Self {
-        Self { buf }
+        Self {
+            buf,
+            head: 0,
+            error_pos: None,
+        }
+    }
+    /// Constructs reader from provided byte slice, for zero-copy deserialization
+    ///
+    /// Same as [`DeBytesReader::new()`]; with deserializer created by
+    /// [`new_de_asc_borrowed()`](crate::new_de_asc_borrowed), `&str` and `&[u8]` values deserialized
+    /// from this reader (including map keys and values) borrow from `buf` for its whole lifetime `'a`.
+    /// ```
+    /// # use ordcode::{ DeBytesReader, new_de_asc_borrowed, ser_to_vec_ordered, Order };
+    /// # use serde::de::Deserialize;
+    /// let buf = ser_to_vec_ordered(&("key", 1_u8), Order::Ascending).unwrap();
+    /// let mut reader = DeBytesReader::from_slice(&buf);
+    /// let (k, _) = <(&str, u8)>::deserialize(&mut new_de_asc_borrowed(&mut reader)).unwrap();
+    /// assert!(buf.as_ptr_range().contains(&k.as_ptr()));
+    /// ```
+    #[must_use]
+    pub fn from_slice(buf: &'a [u8]) -> Self {
+        Self::new(buf)
+    }
+    /// Offset of the head cursor in the underlying slice (number of bytes read from head)
+    #[must_use]
+    pub fn head_pos(&self) -> usize {
+        self.head
+    }
+    /// Offset of the tail cursor in the underlying slice
+    ///
+    /// Number of bytes read from tail is the difference between slice length and `tail_pos()`.
+    #[must_use]
+    pub fn tail_pos(&self) -> usize {
+        self.head + self.buf.len()
+    }
+    /// Number of bytes remaining between head and tail cursors
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.buf.len()
+    }
+    /// Unconsumed part of the underlying slice, between head and tail cursors
+    ///
+    /// When several values are serialized one after another into the same double-ended buffer,
+    /// their head sections are stored in order and their tail sections in reverse order, so
+    /// after deserializing the first value, remaining slice holds the rest of values and can
+    /// be used to construct a new reader. Same applies to values concatenated with
+    /// [`PortableBinary`](crate::params::PortableBinary) parameters, which write no tail section.
+    /// ```
+    /// # use ordcode::{ DeBytesReader, DeBytesWriter, new_de_asc_borrowed, new_ser_asc };
+    /// # use serde::{ Deserialize, Serialize };
+    /// let mut buf = [0_u8; 10];
+    /// let mut writer = DeBytesWriter::new(&mut buf);
+    /// "ab".serialize(&mut new_ser_asc(&mut writer)).unwrap();
+    /// "cde".serialize(&mut new_ser_asc(&mut writer)).unwrap();
+    /// let len = writer.finalize().unwrap();
+    ///
+    /// let mut reader = DeBytesReader::new(&buf[..len]);
+    /// assert_eq!(<&str>::deserialize(&mut new_de_asc_borrowed(&mut reader)).unwrap(), "ab");
+    /// let mut reader = DeBytesReader::new(reader.remaining_slice());
+    /// assert_eq!(<&str>::deserialize(&mut new_de_asc_borrowed(&mut reader)).unwrap(), "cde");
+    /// assert_eq!(reader.remaining(), 0);
+    /// ```
+    #[must_use]
+    pub fn remaining_slice(&self) -> &'a [u8] {
+        self.buf
+    }
+    /// Read `n` bytes from the head as a slice borrowed from the underlying buffer
+    ///
+    /// Unlike [`ReadBytes::read()`], returned slice is not limited to the closure scope,
+    /// and lives as long as the underlying buffer.
+    /// ```
+    /// # use ordcode::DeBytesReader;
+    /// let mut reader = DeBytesReader::new(b"abcde");
+    /// assert_eq!(reader.read_slice(2).unwrap(), b"ab");
+    /// assert_eq!(reader.read_slice(3).unwrap(), b"cde");
+    /// assert!(reader.read_slice(1).is_err());
+    /// ```
+    pub fn read_slice(&mut self, n: usize) -> Result<&'a [u8]> {
+        if n <= self.buf.len() {
+            let (head, rest) = self.buf.split_at(n);
+            self.buf = rest;
+            self.head += n;
+            Ok(head)
+        } else {
+            Err(self.error_at(self.head, Error::PrematureEndOfInput))
+        }
+    }
+    /// Offset of the first error returned by this reader, if any
+    ///
+    /// Offset is the head cursor position for errors in the head section, and the position
+    /// of bytes being read from the tail for errors in the tail section. Errors returned by
+    /// closures passed to [`ReadBytes::read()`] and [`TailReadBytes::read_tail()`] are
+    /// reported at the position of bytes passed to the closure.
+    /// ```
+    /// # use ordcode::{ DeBytesReader, new_de_asc };
+    /// # use serde::de::Deserialize;
+    /// let buf = [0_u8, 7, b'a', 0xFF, 5];
+    /// let mut de = new_de_asc(DeBytesReader::new(&buf));
+    /// assert!(<(u16, String)>::deserialize(&mut de).is_err()); // invalid UTF-8
+    /// assert_eq!(de.into_reader().error_offset(), Some(2));
+    /// ```
+    #[must_use]
+    pub fn error_offset(&self) -> Option<usize> {
+        self.error_pos
+    }
+    fn error_at(&mut self, offset: usize, e: Error) -> Error {
+        self.error_pos.get_or_insert(offset);
+        e
     }
 }
 
@@ -111,14 +251,16 @@ impl<'a> ReadBytes for DeBytesReader<'a> {
     where
         F: FnOnce(&[u8]) -> Result<R>,
     {
+        let head = self.head;
         if n <= self.buf.len() {
-            f(&self.buf[..n])
+            f(&self.buf[..n]).map_err(|e| self.error_at(head, e))
         } else {
-            Err(Error::PrematureEndOfInput)
+            Err(self.error_at(head, Error::PrematureEndOfInput))
         }
     }
     fn advance(&mut self, n: usize) {
         self.buf = &self.buf[n..];
+        self.head += n;
         //println!("after advance {} len={}", n, self.buf.len());
     }
     fn remaining_buffer(&mut self) -> &'_ [u8] {
@@ -126,15 +268,22 @@ impl<'a> ReadBytes for DeBytesReader<'a> {
     }
 }
 
+impl<'a> BorrowReadBytes<'a> for DeBytesReader<'a> {
+    fn read_borrowed(&mut self, n: usize) -> Result<&'a [u8]> {
+        self.read_slice(n)
+    }
+}
+
 impl<'a> TailReadBytes for DeBytesReader<'a> {
     fn peek_tail<F, R>(&mut self, n: usize, f: F) -> Result<R>
     where
         F: FnOnce(&[u8]) -> Result<R>,
     {
+        let tail = self.tail_pos();
         if n <= self.buf.len() {
-            f(&self.buf[(self.buf.len() - n)..])
+            f(&self.buf[(self.buf.len() - n)..]).map_err(|e| self.error_at(tail - n, e))
         } else {
-            Err(Error::PrematureEndOfInput)
+            Err(self.error_at(tail, Error::PrematureEndOfInput))
         }
     }
     fn advance_tail(&mut self, n: usize) {
@@ -183,12 +332,30 @@ impl std::io::Read for DeBytesReader<'_> {
 pub trait WriteBytes {
     /// Write to the byte buffer
     fn write(&mut self, value: &[u8]) -> Result;
+
+    /// Notification that a top-level value is completely written
+    ///
+    /// Called by [`Serializer::finish()`](crate::Serializer::finish); writers may commit
+    /// written data here. Default implementation does nothing.
+    fn flush(&mut self) -> Result {
+        Ok(())
+    }
 }
 
 /// Trait for writer to the tail of byte buffer
 pub trait TailWriteBytes: WriteBytes {
     /// Write to the tail of byte buffer
     fn write_tail(&mut self, value: &[u8]) -> Result;
+
+    /// Write `head` to the head and `tail` to the tail of byte buffer
+    ///
+    /// Same as `write()` followed by `write_tail()`; implementations may override it to write
+    /// both with a single bounds check.
+    #[inline]
+    fn write_pair(&mut self, head: &[u8], tail: &[u8]) -> Result {
+        self.write(head)?;
+        self.write_tail(tail)
+    }
 }
 
 /// Adapter type which implements double-ended write byte buffer over mutable byte slice
@@ -200,6 +367,16 @@ pub struct DeBytesWriter<'a> {
     tail: usize,
 }
 
+impl core::fmt::Debug for DeBytesWriter<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("DeBytesWriter")
+            .field("len", &self.buf.len())
+            .field("head_pos", &self.head)
+            .field("tail_pos", &self.tail)
+            .finish()
+    }
+}
+
 impl<'a> DeBytesWriter<'a> {
     /// Use provided byte slice as buffer
     pub fn new(buf: &'a mut [u8]) -> Self {
@@ -219,12 +396,82 @@ impl<'a> DeBytesWriter<'a> {
             Ok(len)
         }
     }
+    /// Finalize same as [`finalize()`](DeBytesWriter::finalize), and zero-fill the gap
+    /// past data length
+    ///
+    /// Use it when the buffer is reused, so previous buffer contents do not remain in the gap.
+    /// ```
+    /// # use ordcode::{ DeBytesWriter, buf::{ TailWriteBytes, WriteBytes } };
+    /// let mut buf = [0xff_u8; 6];
+    /// let mut writer = DeBytesWriter::new(&mut buf);
+    /// writer.write(b"ab").unwrap();
+    /// writer.write_tail(b"c").unwrap();
+    /// assert_eq!(writer.finalize_zeroed().unwrap(), 3);
+    /// assert_eq!(buf, *b"abc\0\0\0");
+    /// ```
+    pub fn finalize_zeroed(&mut self) -> Result<usize> {
+        let len = self.finalize()?;
+        self.buf[len..].fill(0);
+        Ok(len)
+    }
+    /// Offset of the head cursor in the underlying slice (number of bytes written to head)
+    #[must_use]
+    pub fn head_pos(&self) -> usize {
+        self.head
+    }
+    /// Offset of the tail cursor in the underlying slice
+    ///
+    /// Number of bytes written to tail is the difference between slice length and `tail_pos()`.
+    #[must_use]
+    pub fn tail_pos(&self) -> usize {
+        self.tail
+    }
+    /// Number of bytes available between head and tail cursors
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.tail - self.head
+    }
+    /// Call `f` with this writer, return range of the head section which `f` has written to
+    ///
+    /// Range stays valid after [`finalize()`](DeBytesWriter::finalize), so it can be used to
+    /// invert bytes of a single field with [`invert_range()`](crate::primitives::invert_range).
+    /// Bytes written to the tail section (e.g. sequence lengths) are not included.
+    /// ```
+    /// # use ordcode::{ DeBytesWriter, new_ser_asc, primitives::invert_range };
+    /// # use serde::Serialize;
+    /// let mut buf = [0_u8; 4];
+    /// let mut writer = DeBytesWriter::new(&mut buf);
+    /// 1_u8.serialize(&mut new_ser_asc(&mut writer)).unwrap();
+    /// let range = writer.head_range(|w| 2_u16.serialize(&mut new_ser_asc(w))).unwrap();
+    /// 3_u8.serialize(&mut new_ser_asc(&mut writer)).unwrap();
+    /// writer.is_complete().unwrap();
+    /// assert_eq!(range, 1..3);
+    /// invert_range(&mut buf, range);
+    /// assert_eq!(buf, [1, 0xff, 0xfd, 3]);
+    /// ```
+    pub fn head_range<F>(&mut self, f: F) -> Result<Range<usize>>
+    where
+        F: FnOnce(&mut Self) -> Result,
+    {
+        let start = self.head;
+        f(self)?;
+        Ok(start..self.head)
+    }
+    // error for failed write of `len` bytes
+    fn overflow(&self, len: usize) -> Error {
+        Error::BufferOverflow {
+            needed: self.buf.len() - self.remaining() + len,
+            available: self.buf.len(),
+        }
+    }
     /// Checks if buffer completely filled
     pub fn is_complete(&self) -> Result {
         if self.head == self.tail {
             Ok(())
         } else {
-            Err(Error::BufferUnderflow)
+            Err(Error::BufferUnderflow {
+                remaining: self.remaining(),
+            })
         }
     }
 }
@@ -232,7 +479,7 @@ impl<'a> DeBytesWriter<'a> {
 impl<'a> WriteBytes for DeBytesWriter<'a> {
     fn write(&mut self, value: &[u8]) -> Result {
         if (self.head + value.len()) > self.tail {
-            Err(Error::BufferOverflow)
+            Err(self.overflow(value.len()))
         } else {
             self.buf[self.head..(self.head + value.len())].copy_from_slice(value);
             self.head += value.len();
@@ -244,7 +491,7 @@ impl<'a> WriteBytes for DeBytesWriter<'a> {
 impl<'a> TailWriteBytes for DeBytesWriter<'a> {
     fn write_tail(&mut self, value: &[u8]) -> Result {
         if (self.head + value.len()) > self.tail {
-            Err(Error::BufferOverflow)
+            Err(self.overflow(value.len()))
         } else {
             let end_offs = self.tail - value.len();
             self.buf[end_offs..self.tail].copy_from_slice(value);
@@ -252,6 +499,21 @@ impl<'a> TailWriteBytes for DeBytesWriter<'a> {
             Ok(())
         }
     }
+    // nothing is written if both parts do not fit
+    fn write_pair(&mut self, head: &[u8], tail: &[u8]) -> Result {
+        let len = head.len() + tail.len();
+        if (self.head + len) > self.tail {
+            Err(self.overflow(len))
+        } else {
+            let head_end = self.head + head.len();
+            let tail_start = self.tail - tail.len();
+            self.buf[self.head..head_end].copy_from_slice(head);
+            self.buf[tail_start..self.tail].copy_from_slice(tail);
+            self.head = head_end;
+            self.tail = tail_start;
+            Ok(())
+        }
+    }
 }
 
 /// Adapter which implements `WriteBytes` for writing to the end of double-ended buffer
@@ -275,6 +537,85 @@ where
     }
 }
 
+/// Cursor for pushing and popping varint-encoded lengths in the tail of a byte slice
+///
+/// Implements the convention used by [`Serializer`](crate::Serializer) with
+/// [`params::AscendingOrder`](crate::params::AscendingOrder): lengths are written to the tail
+/// of the buffer, each one before previously written, and are read from the end of the buffer,
+/// so they are popped in the same order as they were pushed. Encoding of lengths is the same as
+/// of [`VarIntTailLenEncoder`](crate::varint::VarIntTailLenEncoder).
+///
+/// Pushing requires a mutable slice; popping works with any slice, e.g. serialized data.
+/// ```
+/// # use ordcode::{ buf::TailCursor, ser_to_vec_ordered, Order };
+/// let mut buf = [0_u8; 8];
+/// let mut cursor = TailCursor::new(&mut buf[..]);
+/// cursor.push_varint_len(1).unwrap();
+/// cursor.push_varint_len(1000).unwrap();
+/// assert_eq!(cursor.tail_len(), 3);
+/// assert_eq!(cursor.pop_varint_len().unwrap(), 1);
+/// assert_eq!(cursor.pop_varint_len().unwrap(), 1000);
+///
+/// let buf = ser_to_vec_ordered(&("ab", "cde"), Order::Ascending).unwrap();
+/// let mut cursor = TailCursor::new(&buf[..]);
+/// assert_eq!(cursor.pop_varint_len().unwrap(), 2);
+/// assert_eq!(cursor.pop_varint_len().unwrap(), 3);
+/// ```
+pub struct TailCursor<B> {
+    buf: B,
+    // start of the pushed tail section
+    write_pos: usize,
+    // end of the remaining tail section, not popped yet
+    read_pos: usize,
+}
+
+impl<B: AsRef<[u8]>> TailCursor<B> {
+    /// Create cursor with empty tail section at the end of the buffer
+    pub fn new(buf: B) -> Self {
+        let len = buf.as_ref().len();
+        Self {
+            buf,
+            write_pos: len,
+            read_pos: len,
+        }
+    }
+    /// Read length from the end of the tail section, which was not popped yet
+    ///
+    /// Pushed lengths are popped in the same order as they were pushed; popping more than was
+    /// pushed reads data preceding the pushed tail section.
+    pub fn pop_varint_len(&mut self) -> Result<usize> {
+        let mut reader = DeBytesReader::new(&self.buf.as_ref()[..self.read_pos]);
+        let len = VarIntTailLenEncoder::read(&mut reader)?;
+        self.read_pos = reader.remaining();
+        Ok(len)
+    }
+    /// Number of bytes pushed to the tail section
+    pub fn tail_len(&self) -> usize {
+        self.buf.as_ref().len() - self.write_pos
+    }
+    /// Returns underlying buffer
+    pub fn into_inner(self) -> B {
+        self.buf
+    }
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> TailCursor<B> {
+    /// Write length to the tail section, before previously pushed lengths
+    pub fn push_varint_len(&mut self, len: usize) -> Result {
+        let tail_len = self.tail_len();
+        let mut writer = DeBytesWriter::new(&mut self.buf.as_mut()[..self.write_pos]);
+        VarIntTailLenEncoder::write(&mut writer, len).map_err(|e| match e {
+            Error::BufferOverflow { needed, available } => Error::BufferOverflow {
+                needed: needed + tail_len,
+                available: available + tail_len,
+            },
+            e => e,
+        })?;
+        self.write_pos = writer.tail_pos();
+        Ok(())
+    }
+}
+
 // forwarding for being able to use `&mut WriteBytes` in place of `WriteBytes`
 impl<T> WriteBytes for &mut T
 where
@@ -283,6 +624,9 @@ where
     fn write(&mut self, buf: &[u8]) -> Result {
         (*self).write(buf)
     }
+    fn flush(&mut self) -> Result {
+        (*self).flush()
+    }
 }
 
 impl<T> TailWriteBytes for &mut T
@@ -292,6 +636,279 @@ where
     fn write_tail(&mut self, buf: &[u8]) -> Result {
         (*self).write_tail(buf)
     }
+    fn write_pair(&mut self, head: &[u8], tail: &[u8]) -> Result {
+        (*self).write_pair(head, tail)
+    }
+}
+
+/// Trait for byte-at-a-time output, e.g. ring buffers or serial ports
+///
+/// Serialized data can be written to a [`ByteSink`] with [`SinkWriter`] adapter.
+pub trait ByteSink {
+    /// Push a single byte to the output
+    fn push(&mut self, byte: u8) -> Result;
+}
+
+impl<T> ByteSink for &mut T
+where
+    T: ByteSink,
+{
+    fn push(&mut self, byte: u8) -> Result {
+        (*self).push(byte)
+    }
+}
+
+/// Adapter which implements [`WriteBytes`] and [`TailWriteBytes`] over [`ByteSink`]
+///
+/// Data written to head is pushed to the sink immediately. Tail section can't be pushed
+/// until all the data is written, so it is collected in the caller-provided buffer
+/// `tail_buf` and is pushed to the sink by [`SinkWriter::finalize()`]. Buffer should be large
+/// enough to hold all sequence lengths, otherwise [`Error::BufferOverflow`] is returned.
+/// ```
+/// # use ordcode::{ Result, Error, new_ser_asc, buf::{ ByteSink, SinkWriter } };
+/// # use serde::ser::Serialize;
+/// struct ArraySink<const N: usize> {
+///     buf: [u8; N],
+///     len: usize,
+/// }
+///
+/// impl<const N: usize> ByteSink for ArraySink<N> {
+///     fn push(&mut self, byte: u8) -> Result {
+///         *self.buf.get_mut(self.len).ok_or(Error::BufferOverflow { needed: self.len + 1, available: N })? = byte;
+///         self.len += 1;
+///         Ok(())
+///     }
+/// }
+///
+/// let mut tail_buf = [0_u8; 4];
+/// let mut writer = SinkWriter::new(ArraySink { buf: [0_u8; 16], len: 0 }, &mut tail_buf);
+/// ("abc", 1_u8).serialize(&mut new_ser_asc(&mut writer)).unwrap();
+/// let sink = writer.finalize().unwrap();
+/// assert_eq!(sink.buf[..sink.len], [b'a', b'b', b'c', 1, 7]);
+/// ```
+pub struct SinkWriter<'a, S> {
+    sink: S,
+    tail_buf: &'a mut [u8],
+    tail: usize,
+}
+
+impl<'a, S> SinkWriter<'a, S>
+where
+    S: ByteSink,
+{
+    /// Create adapter over `sink`, with `tail_buf` for buffering tail section
+    pub fn new(sink: S, tail_buf: &'a mut [u8]) -> Self {
+        let tail = tail_buf.len();
+        Self {
+            sink,
+            tail_buf,
+            tail,
+        }
+    }
+    /// Push buffered tail section to the sink and return it
+    pub fn finalize(mut self) -> Result<S> {
+        for b in &self.tail_buf[self.tail..] {
+            self.sink.push(*b)?;
+        }
+        Ok(self.sink)
+    }
+}
+
+impl<S> WriteBytes for SinkWriter<'_, S>
+where
+    S: ByteSink,
+{
+    fn write(&mut self, value: &[u8]) -> Result {
+        for b in value {
+            self.sink.push(*b)?;
+        }
+        Ok(())
+    }
+}
+
+impl<S> TailWriteBytes for SinkWriter<'_, S>
+where
+    S: ByteSink,
+{
+    fn write_tail(&mut self, value: &[u8]) -> Result {
+        if value.len() > self.tail {
+            Err(Error::BufferOverflow {
+                needed: self.tail_buf.len() - self.tail + value.len(),
+                available: self.tail_buf.len(),
+            })
+        } else {
+            let end_offs = self.tail - value.len();
+            self.tail_buf[end_offs..self.tail].copy_from_slice(value);
+            self.tail = end_offs;
+            Ok(())
+        }
+    }
+}
+
+/// Writer which counts written bytes instead of storing them
+///
+/// Implements [`WriteBytes`] and [`TailWriteBytes`], so serializing into it yields the exact size
+/// of serialized data, as produced by the actual primitive writes. Unlike [`SizeCalc`](crate::SizeCalc),
+/// it goes through the same code path as real serialization.
+/// ```
+/// # use ordcode::{ buf::CountWriter, new_ser_asc, calc_size_asc };
+/// # use serde::ser::Serialize;
+/// let mut counter = CountWriter::new();
+/// ("abc", 1_u32).serialize(&mut new_ser_asc(&mut counter)).unwrap();
+/// assert_eq!(counter.count(), 8);
+/// assert_eq!(counter.count(), calc_size_asc(&("abc", 1_u32)).unwrap());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CountWriter {
+    count: usize,
+}
+
+impl CountWriter {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Total number of bytes written to the head and to the tail
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl WriteBytes for CountWriter {
+    fn write(&mut self, value: &[u8]) -> Result {
+        self.count += value.len();
+        Ok(())
+    }
+}
+
+impl TailWriteBytes for CountWriter {
+    fn write_tail(&mut self, value: &[u8]) -> Result {
+        self.count += value.len();
+        Ok(())
+    }
+}
+
+/// Writer adapter which bitwise inverts all bytes written to the head and to the tail
+///
+/// Serializing with ascending order parameters into `InvertWriter` produces the same bytes as
+/// serializing into a buffer and inverting it afterwards with
+/// [`invert_buffer()`](crate::primitives::invert_buffer), i.e. descending order encoding,
+/// without the extra pass over the data. Please see [`new_ser_desc()`](crate::new_ser_desc).
+#[derive(Clone, Debug)]
+pub struct InvertWriter<W>(pub W);
+
+impl<W> InvertWriter<W> {
+    /// Unwrap underlying writer
+    pub fn into_inner(self) -> W {
+        self.0
+    }
+}
+
+impl<W> WriteBytes for InvertWriter<W>
+where
+    W: WriteBytes,
+{
+    fn write(&mut self, value: &[u8]) -> Result {
+        crate::bytes_esc::write_complement_bytes(&mut self.0, value)
+    }
+    fn flush(&mut self) -> Result {
+        self.0.flush()
+    }
+}
+
+impl<W> TailWriteBytes for InvertWriter<W>
+where
+    W: TailWriteBytes,
+{
+    fn write_tail(&mut self, value: &[u8]) -> Result {
+        // each tail write is placed before the previous one, so write chunks from the last
+        let mut chunk = [0_u8; 64];
+        for v in value.rchunks(chunk.len()) {
+            let chunk = &mut chunk[..v.len()];
+            chunk.copy_from_slice(v);
+            crate::primitives::invert_buffer(chunk);
+            self.0.write_tail(chunk)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reader adapter which bitwise inverts all bytes read from the head and from the tail
+///
+/// Counterpart of [`InvertWriter`]: deserializing with ascending order parameters from
+/// `InvertReader` reads descending order encoding, without inverting input buffer in place.
+/// Bytes are inverted into internal scratch buffer, so `&str` and `&[u8]` values can't be
+/// borrowed from the input. Please see [`new_de_desc()`](crate::new_de_desc).
+#[cfg(feature = "std")]
+pub struct InvertReader<R> {
+    reader: R,
+    scratch: Vec<u8>,
+}
+
+#[cfg(feature = "std")]
+impl<R> InvertReader<R> {
+    #[must_use]
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            scratch: Vec::new(),
+        }
+    }
+    /// Unwrap underlying reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+#[cfg(feature = "std")]
+fn invert_into(scratch: &mut Vec<u8>, buf: &[u8]) {
+    scratch.clear();
+    scratch.extend(buf.iter().map(|b| !*b));
+}
+
+#[cfg(feature = "std")]
+impl<R> ReadBytes for InvertReader<R>
+where
+    R: ReadBytes,
+{
+    fn peek<F, T>(&mut self, n: usize, f: F) -> Result<T>
+    where
+        F: FnOnce(&[u8]) -> Result<T>,
+    {
+        let scratch = &mut self.scratch;
+        self.reader.peek(n, |buf| {
+            invert_into(scratch, buf);
+            f(scratch)
+        })
+    }
+    fn advance(&mut self, n: usize) {
+        self.reader.advance(n);
+    }
+    fn remaining_buffer(&mut self) -> &'_ [u8] {
+        invert_into(&mut self.scratch, self.reader.remaining_buffer());
+        &self.scratch
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R> TailReadBytes for InvertReader<R>
+where
+    R: TailReadBytes,
+{
+    fn peek_tail<F, T>(&mut self, n: usize, f: F) -> Result<T>
+    where
+        F: FnOnce(&[u8]) -> Result<T>,
+    {
+        let scratch = &mut self.scratch;
+        self.reader.peek_tail(n, |buf| {
+            invert_into(scratch, buf);
+            f(scratch)
+        })
+    }
+    fn advance_tail(&mut self, n: usize) {
+        self.reader.advance_tail(n);
+    }
 }
 
 /// Pushes data to the vector
@@ -315,6 +932,261 @@ impl TailWriteBytes for Vec<u8> {
     }
 }
 
+/// Growable double-ended write buffer
+///
+/// Implements [`WriteBytes`] and [`TailWriteBytes`] like [`DeBytesWriter`], but does not need
+/// pre-allocated buffer of exact size: head and tail sections grow independently as needed,
+/// and are joined together by [`VecWriter::finalize()`].
+/// ```
+/// # use ordcode::{ buf::{ VecWriter, WriteBytes, TailWriteBytes } };
+/// let mut writer = VecWriter::new();
+/// writer.write(b"ab").unwrap();
+/// writer.write_tail(b"1").unwrap();
+/// writer.write(b"c").unwrap();
+/// writer.write_tail(b"23").unwrap();
+/// assert_eq!(writer.finalize(), b"abc231");
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Default)]
+pub struct VecWriter {
+    head: Vec<u8>,
+    // tail section in reverse byte order
+    tail: Vec<u8>,
+}
+
+#[cfg(feature = "std")]
+impl VecWriter {
+    /// Create new empty buffer
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Create new buffer with pre-allocated capacity for the head section
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            head: Vec::with_capacity(capacity),
+            tail: Vec::new(),
+        }
+    }
+    // head and tail sections, tail in its final byte order
+    pub(crate) fn into_sections(self) -> (Vec<u8>, Vec<u8>) {
+        let mut tail = self.tail;
+        tail.reverse();
+        (self.head, tail)
+    }
+    /// Join head and tail sections, returns serialized data
+    #[must_use]
+    pub fn finalize(self) -> Vec<u8> {
+        let mut buf = self.head;
+        buf.extend(self.tail.iter().rev());
+        buf
+    }
+}
+
+#[cfg(feature = "std")]
+impl WriteBytes for VecWriter {
+    fn write(&mut self, value: &[u8]) -> Result {
+        self.head.extend_from_slice(value);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl TailWriteBytes for VecWriter {
+    fn write_tail(&mut self, value: &[u8]) -> Result {
+        self.tail.extend(value.iter().rev());
+        Ok(())
+    }
+}
+
+/// Adapter which implements [`WriteBytes`] and [`TailWriteBytes`] over [`std::io::Write`]
+///
+/// Data written to head is passed to the underlying writer immediately. Since it is not possible
+/// to write backwards into a stream, data written to tail is buffered in memory until
+/// [`IoWriter::finalize()`] is called, so memory usage is proportional to the size of tail
+/// section (sequence lengths), not to the size of serialized data.
+/// ```
+/// # use ordcode::{ buf::IoWriter, new_ser_asc };
+/// # use serde::ser::Serialize;
+/// let mut writer = IoWriter::new(Vec::new());
+/// ("abc", 1u8).serialize(&mut new_ser_asc(&mut writer)).unwrap();
+/// assert_eq!(writer.finalize().unwrap(), [b'a', b'b', b'c', 1, 7]);
+/// ```
+#[cfg(feature = "std")]
+pub struct IoWriter<W> {
+    writer: W,
+    // tail section in reverse byte order
+    tail: Vec<u8>,
+}
+
+#[cfg(feature = "std")]
+impl<W> IoWriter<W>
+where
+    W: std::io::Write,
+{
+    /// Create adapter over `writer`
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            tail: Vec::new(),
+        }
+    }
+    /// Write buffered tail section to the underlying writer, flush it and return it
+    pub fn finalize(mut self) -> Result<W> {
+        self.tail.reverse();
+        self.writer
+            .write_all(&self.tail)
+            .map_err(|e| Error::Io(e.kind()))?;
+        self.writer.flush().map_err(|e| Error::Io(e.kind()))?;
+        Ok(self.writer)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W> WriteBytes for IoWriter<W>
+where
+    W: std::io::Write,
+{
+    fn write(&mut self, value: &[u8]) -> Result {
+        self.writer
+            .write_all(value)
+            .map_err(|e| Error::Io(e.kind()))
+    }
+    // flushes head section written so far, tail section is written on `finalize()`
+    fn flush(&mut self) -> Result {
+        self.writer.flush().map_err(|e| Error::Io(e.kind()))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W> TailWriteBytes for IoWriter<W>
+where
+    W: std::io::Write,
+{
+    fn write_tail(&mut self, value: &[u8]) -> Result {
+        self.tail.extend(value.iter().rev());
+        Ok(())
+    }
+}
+
+/// Adapter which implements [`ReadBytes`] and [`TailReadBytes`] over [`std::io::Read`]
+///
+/// Reads a single frame of serialized data of known length `frame_len` from a stream.
+/// Data is read from the stream as it is requested from the head and kept in an internal buffer.
+/// Since sequence lengths are stored at the end of the frame, the whole frame is read
+/// into the buffer on the first read from tail, so memory usage is up to `frame_len` bytes.
+/// Reading fixed-size values which have no tail section does not buffer more data than needed.
+/// ```
+/// # use ordcode::{ buf::IoReader, new_de_asc };
+/// # use serde::de::Deserialize;
+/// let data = [b'a', b'b', b'c', 1, 7, 0xFF];
+/// let mut cursor = std::io::Cursor::new(&data[..]);
+/// let mut reader = IoReader::new(&mut cursor, 5);
+/// let v = <(String, u8)>::deserialize(&mut new_de_asc(&mut reader)).unwrap();
+/// assert_eq!(v, ("abc".to_string(), 1));
+/// assert_eq!(cursor.position(), 5);
+/// ```
+#[cfg(feature = "std")]
+pub struct IoReader<R> {
+    reader: R,
+    frame_len: usize,
+    // data read from the frame so far
+    buf: Vec<u8>,
+    head: usize,
+    tail: usize,
+}
+
+#[cfg(feature = "std")]
+impl<R> IoReader<R>
+where
+    R: std::io::Read,
+{
+    /// Create adapter for reading frame of `frame_len` bytes from `reader`
+    pub fn new(reader: R, frame_len: usize) -> Self {
+        Self {
+            reader,
+            frame_len,
+            buf: Vec::new(),
+            head: 0,
+            tail: frame_len,
+        }
+    }
+    /// Number of frame bytes remaining between head and tail cursors
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.tail - self.head
+    }
+    /// Read the rest of the frame, if any, and return the underlying reader
+    pub fn into_inner(mut self) -> Result<R> {
+        self.fill(self.frame_len)?;
+        Ok(self.reader)
+    }
+    // make sure that the first `len` bytes of the frame are in the buffer
+    fn fill(&mut self, len: usize) -> Result {
+        let filled = self.buf.len();
+        if filled < len {
+            self.buf.resize(len, 0);
+            if let Err(e) = self.reader.read_exact(&mut self.buf[filled..]) {
+                // drop padding, so it is not taken for frame data
+                self.buf.truncate(filled);
+                return Err(if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                    Error::PrematureEndOfInput
+                } else {
+                    Error::Io(e.kind())
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R> ReadBytes for IoReader<R>
+where
+    R: std::io::Read,
+{
+    fn peek<F, T>(&mut self, n: usize, f: F) -> Result<T>
+    where
+        F: FnOnce(&[u8]) -> Result<T>,
+    {
+        if n > self.remaining() {
+            return Err(Error::PrematureEndOfInput);
+        }
+        self.fill(self.head + n)?;
+        f(&self.buf[self.head..self.head + n])
+    }
+    fn advance(&mut self, n: usize) {
+        self.head += n;
+    }
+    /// Returns view into remaining frame data, reading it from the stream if needed.
+    /// If reading fails, returns only the data which is already buffered.
+    fn remaining_buffer(&mut self) -> &'_ [u8] {
+        let _ = self.fill(self.tail);
+        &self.buf[self.head.min(self.buf.len())..self.tail.min(self.buf.len())]
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R> TailReadBytes for IoReader<R>
+where
+    R: std::io::Read,
+{
+    fn peek_tail<F, T>(&mut self, n: usize, f: F) -> Result<T>
+    where
+        F: FnOnce(&[u8]) -> Result<T>,
+    {
+        if n > self.remaining() {
+            return Err(Error::PrematureEndOfInput);
+        }
+        self.fill(self.frame_len)?;
+        f(&self.buf[self.tail - n..self.tail])
+    }
+    fn advance_tail(&mut self, n: usize) {
+        self.tail -= n;
+    }
+}
+
 /// Adapter for always writing to buffer head, even for `write_tail()`
 ///
 /// Useful e.g. for appending serialized suffix to the buffer
@@ -329,6 +1201,9 @@ where
     fn write(&mut self, value: &[u8]) -> Result {
         self.0.write(value)
     }
+    fn flush(&mut self) -> Result {
+        self.0.flush()
+    }
 }
 
 impl<'a, W> TailWriteBytes for WriteToHead<'a, W>
@@ -358,3 +1233,21 @@ fn test_debuffer() {
     assert_eq!(rb.read(2, |b| Ok(b == b"bd")).unwrap(), true);
     rb.is_complete().unwrap();
 }
+
+#[test]
+fn test_debuffer_positions() {
+    let mut byte_buf = [0_u8; 10];
+    let mut w = DeBytesWriter::new(byte_buf.as_mut());
+    assert_eq!((w.head_pos(), w.tail_pos(), w.remaining()), (0, 10, 10));
+    w.write(&[1, 2, 3, 4]).unwrap();
+    w.write_tail(&[5]).unwrap();
+    assert_eq!((w.head_pos(), w.tail_pos(), w.remaining()), (4, 9, 5));
+
+    let mut r = DeBytesReader::new(byte_buf.as_ref());
+    assert_eq!((r.head_pos(), r.tail_pos(), r.remaining()), (0, 10, 10));
+    r.read(4, |_| Ok(())).unwrap();
+    r.read_tail(1, |_| Ok(())).unwrap();
+    assert_eq!((r.head_pos(), r.tail_pos(), r.remaining()), (4, 9, 5));
+    r.read_slice(2).unwrap();
+    assert_eq!((r.head_pos(), r.tail_pos(), r.remaining()), (6, 9, 3));
+}
@@ -5,11 +5,20 @@
 //! `{ 0xF8, 0x01 }` for ascending order, `{ 0x07, 0xFE }` for descending order. Escaped byte
 //!   value `0xF8` is chosen because it does not appear in valid UTF-8, and escaping zero
 //!   is impractical (it is too common)
+//!
+//! Note that this encoding does not preserve lexicographic ordering of sequences where one is
+//! a prefix of another (e.g. `"a"` is encoded greater than `"a\0"`). For order-preserving
+//! escaped encoding, use [`serialize_escaped()`] and [`deserialize_escaped()`], which escape
+//! zero byte as `{ 0x00, 0xFF }` and terminate sequence with `{ 0x00, 0x01 }`, so that terminator
+//! is ordered before any continuation. This is useful for embedding variable-length values
+//! into composite keys, where length in the tail can't be used.
+//! Such sequences can be compared without decoding with [`compare()`].
 use crate::{
     buf::{ReadBytes, WriteBytes},
     params::EncodingParams,
     Error, Order, Result,
 };
+use core::cmp::Ordering;
 
 fn apply_over_esc<R, F>(rb: &mut R, esc: u8, advance: bool, f: &mut F) -> Result
 where
@@ -17,6 +26,7 @@ where
     R: ReadBytes,
 {
     let mut b = &rb.remaining_buffer()[..];
+    let total = b.len();
     let r = loop {
         if let Some(pos) = b.iter().position(|v| *v == esc) {
             if pos + 1 >= b.len() {
@@ -31,9 +41,9 @@ where
             break Err(Error::PrematureEndOfInput);
         }
     };
-    let len = b.len();
+    let consumed = total - b.len();
     if advance {
-        rb.advance(len);
+        rb.advance(consumed);
     }
     r
 }
@@ -54,6 +64,17 @@ const BSTR_ESCAPE_DESC: ByteStrEscapes = ByteStrEscapes {
     esc: !BSTR_ESCAPE_ASC.esc,
     term: !BSTR_ESCAPE_ASC.term,
 };
+// Escape and terminator sequences for order-preserving byte array encoding.
+const ZERO_ESCAPE_ASC: ByteStrEscapes = ByteStrEscapes {
+    start: 0x00,
+    esc: 0xFF,
+    term: 0x01,
+};
+const ZERO_ESCAPE_DESC: ByteStrEscapes = ByteStrEscapes {
+    start: !ZERO_ESCAPE_ASC.start,
+    esc: !ZERO_ESCAPE_ASC.esc,
+    term: !ZERO_ESCAPE_ASC.term,
+};
 
 // Calculates unescaped length of escaped sequence, does not advance reader
 #[inline]
@@ -83,43 +104,46 @@ pub fn bytes_length<P: EncodingParams>(mut reader: impl ReadBytes, _param: P) ->
     )
 }
 
+// Write escaped byte sequence, `asc` is escapes for ascending order
+fn write_escaped<P: EncodingParams>(
+    mut writer: impl WriteBytes,
+    value: &[u8],
+    param: P,
+    asc: &ByteStrEscapes,
+) -> Result {
+    let esc_start = ord_cond!(P, !asc.start, asc.start);
+    let esc_esc = ord_cond!(P, !asc.esc, asc.esc);
+    let esc_term = ord_cond!(P, !asc.term, asc.term);
+    let mut rest = value;
+    // write runs of bytes between escaped values with a single call
+    while let Some(pos) = rest.iter().position(|b| *b == asc.start) {
+        serialize_bytes_noesc(&mut writer, &rest[..pos], param)?;
+        writer.write(&[esc_start, esc_esc])?;
+        rest = &rest[pos + 1..];
+    }
+    serialize_bytes_noesc(&mut writer, rest, param)?;
+    writer.write(&[esc_start, esc_term])
+}
+
 /// Serialize byte sequence to escaped representation
 pub fn serialize_bytes<P: EncodingParams>(
-    mut writer: impl WriteBytes,
+    writer: impl WriteBytes,
     value: &[u8],
-    _param: P,
+    param: P,
 ) -> Result {
-    ord_cond!(
-        P,
-        {
-            for b in value {
-                if BSTR_ESCAPE_ASC.start == *b {
-                    writer.write(&[BSTR_ESCAPE_DESC.start, BSTR_ESCAPE_DESC.esc])?;
-                } else {
-                    writer.write(&[!*b])?;
-                }
-            }
-            writer.write(&[BSTR_ESCAPE_DESC.start, BSTR_ESCAPE_DESC.term])
-        },
-        {
-            for b in value {
-                if BSTR_ESCAPE_ASC.start == *b {
-                    writer.write(&[BSTR_ESCAPE_ASC.start, BSTR_ESCAPE_ASC.esc])?;
-                } else {
-                    writer.write(&[*b])?;
-                }
-            }
-            writer.write(&[BSTR_ESCAPE_ASC.start, BSTR_ESCAPE_ASC.term])
-        }
-    )
+    write_escaped(writer, value, param, &BSTR_ESCAPE_ASC)
 }
 
-fn read_escaped_bytes_asc(mut rb: impl ReadBytes, mut out: impl WriteBytes) -> Result {
-    apply_over_esc(&mut rb, BSTR_ESCAPE_ASC.start, true, &mut |buf, c| {
-        if c == BSTR_ESCAPE_ASC.esc {
+fn read_escaped_bytes_asc(
+    mut rb: impl ReadBytes,
+    mut out: impl WriteBytes,
+    esc: &ByteStrEscapes,
+) -> Result {
+    apply_over_esc(&mut rb, esc.start, true, &mut |buf, c| {
+        if c == esc.esc {
             out.write(&buf[..buf.len()])?;
             Ok(true)
-        } else if c == BSTR_ESCAPE_ASC.term {
+        } else if c == esc.term {
             out.write(&buf[..buf.len() - 1])?;
             Ok(false)
         } else {
@@ -128,12 +152,16 @@ fn read_escaped_bytes_asc(mut rb: impl ReadBytes, mut out: impl WriteBytes) -> R
     })
 }
 
-fn read_escaped_bytes_desc(mut rb: impl ReadBytes, mut out: impl WriteBytes) -> Result {
-    apply_over_esc(&mut rb, BSTR_ESCAPE_DESC.start, true, &mut |buf, c| {
-        if c == BSTR_ESCAPE_DESC.esc {
+fn read_escaped_bytes_desc(
+    mut rb: impl ReadBytes,
+    mut out: impl WriteBytes,
+    esc: &ByteStrEscapes,
+) -> Result {
+    apply_over_esc(&mut rb, esc.start, true, &mut |buf, c| {
+        if c == esc.esc {
             write_complement_bytes(&mut out, &buf[..buf.len()])?;
             Ok(true)
-        } else if c == BSTR_ESCAPE_DESC.term {
+        } else if c == esc.term {
             write_complement_bytes(&mut out, &buf[..buf.len() - 1])?;
             Ok(false)
         } else {
@@ -151,8 +179,8 @@ pub fn deserialize_bytes_to_writer<P: EncodingParams>(
 ) -> Result {
     ord_cond!(
         P,
-        read_escaped_bytes_desc(reader, out),
-        read_escaped_bytes_asc(reader, out)
+        read_escaped_bytes_desc(reader, out, &BSTR_ESCAPE_DESC),
+        read_escaped_bytes_asc(reader, out, &BSTR_ESCAPE_ASC)
     )
 }
 
@@ -168,11 +196,119 @@ pub fn deserialize_bytes_to_vec<P: EncodingParams>(
     Ok(v)
 }
 
+/// Serialize byte sequence to order-preserving escaped representation
+///
+/// Zero byte is escaped as `{ 0x00, 0xFF }`, sequence is terminated by `{ 0x00, 0x01 }`
+/// (bitwise inverted for [`Order::Descending`]). Encoded sequences compare in the same order
+/// as original sequences, and remain so when followed by other data, so they can be
+/// concatenated into composite keys without length prefix or tail.
+/// ```
+/// # use ordcode::{ bytes_esc::serialize_escaped, params::AscendingOrder };
+/// let mut a = Vec::new();
+/// let mut b = Vec::new();
+/// serialize_escaped(&mut a, b"a", AscendingOrder).unwrap();
+/// serialize_escaped(&mut b, b"a\0", AscendingOrder).unwrap();
+/// assert_eq!(a, [b'a', 0x00, 0x01]);
+/// assert_eq!(b, [b'a', 0x00, 0xFF, 0x00, 0x01]);
+/// assert!(a < b);
+/// ```
+pub fn serialize_escaped<P: EncodingParams>(
+    writer: impl WriteBytes,
+    value: &[u8],
+    param: P,
+) -> Result {
+    write_escaped(writer, value, param, &ZERO_ESCAPE_ASC)
+}
+
+/// Calculate unescaped length of pending sequence serialized with [`serialize_escaped()`]
+///
+/// Does not advance the reader.
+#[inline]
+pub fn escaped_length<P: EncodingParams>(mut reader: impl ReadBytes, _param: P) -> Result<usize> {
+    ord_cond!(
+        P,
+        unescaped_length(&mut reader, &ZERO_ESCAPE_DESC),
+        unescaped_length(&mut reader, &ZERO_ESCAPE_ASC)
+    )
+}
+
+/// Deserialize sequence serialized with [`serialize_escaped()`] and write result to `WriteBytes`
+#[inline]
+pub fn deserialize_escaped_to_writer<P: EncodingParams>(
+    reader: impl ReadBytes,
+    out: impl WriteBytes,
+    _param: P,
+) -> Result {
+    ord_cond!(
+        P,
+        read_escaped_bytes_desc(reader, out, &ZERO_ESCAPE_DESC),
+        read_escaped_bytes_asc(reader, out, &ZERO_ESCAPE_ASC)
+    )
+}
+
+/// Deserialize sequence serialized with [`serialize_escaped()`]
+///
+/// Reader is advanced past the terminator, so following data can be read from it.
+#[cfg(feature = "std")]
+pub fn deserialize_escaped<P: EncodingParams>(
+    mut reader: impl ReadBytes,
+    param: P,
+) -> Result<Vec<u8>> {
+    let len = escaped_length(&mut reader, param)?;
+    let mut v = Vec::with_capacity(len);
+    deserialize_escaped_to_writer(&mut reader, &mut v, param)?;
+    Ok(v)
+}
+
+// Length of escaped sequence serialized with `serialize_escaped()` for ascending order, including
+// terminator, or length of the whole buffer if it is not terminated
+fn escaped_end(buf: &[u8]) -> usize {
+    let esc = &ZERO_ESCAPE_ASC;
+    let mut pos = 0;
+    while let Some(offs) = buf[pos..].iter().position(|b| *b == esc.start) {
+        pos += offs + 2;
+        if buf.get(pos - 1) != Some(&esc.esc) {
+            break;
+        }
+    }
+    pos.min(buf.len())
+}
+
+/// Compare sequences serialized with [`serialize_escaped()`] for ascending order
+///
+/// Result is the same as comparison of original unescaped sequences. Only the first escaped
+/// sequence of each buffer is compared, data following its terminator is ignored, so the
+/// function can be applied to composite keys which start with escaped value. Buffer without
+/// terminator is compared as a whole.
+/// ```
+/// # use ordcode::{ bytes_esc::{ compare, serialize_escaped }, params::AscendingOrder };
+/// # use std::cmp::Ordering;
+/// let mut a = Vec::new();
+/// let mut b = Vec::new();
+/// serialize_escaped(&mut a, b"a\0", AscendingOrder).unwrap();
+/// serialize_escaped(&mut b, b"a", AscendingOrder).unwrap();
+/// b.push(0xFF); // trailing data
+/// assert_eq!(compare(&a, &b), Ordering::Greater);
+/// ```
+#[must_use]
+pub fn compare(a: &[u8], b: &[u8]) -> Ordering {
+    // escaped encoding is prefix-free and preserves ordering, so terminated sequences
+    // can be compared directly
+    a[..escaped_end(a)].cmp(&b[..escaped_end(b)])
+}
+
 /// Write 0xFF bitwise complement of input
+///
+/// Input is inverted in fixed-size chunks on stack, so the writer is called once per chunk
+/// rather than once per byte.
 #[inline]
 pub fn write_complement_bytes(mut writer: impl WriteBytes, input: &[u8]) -> Result {
-    for v in input {
-        writer.write(&[!*v])?;
+    let mut chunk = [0_u8; 64];
+    for v in input.chunks(chunk.len()) {
+        let chunk = &mut chunk[..v.len()];
+        chunk.copy_from_slice(v);
+        crate::primitives::invert_buffer(chunk);
+        writer.write(chunk)?;
     }
     Ok(())
 }
@@ -5,8 +5,16 @@ pub enum Error {
     #[doc(hidden)]
     Serde(String), // not used, but need to satisfy serde Error traits
     SerializeSequenceMustHaveLength,
-    BufferOverflow,
-    BufferUnderflow,
+    /// Buffer is too small: `needed` bytes are required to complete a write,
+    /// counting data written so far, but only `available` bytes fit into the buffer
+    BufferOverflow {
+        needed: usize,
+        available: usize,
+    },
+    /// Buffer is not completely filled or consumed, `remaining` bytes are left
+    BufferUnderflow {
+        remaining: usize,
+    },
     PrematureEndOfInput,
     InvalidByteSequenceEscape,
     DeserializeAnyNotSupported,
@@ -15,8 +23,42 @@ pub enum Error {
     InvalidUtf8Encoding,
     InvalidTagEncoding,
     InvalidVarintEncoding,
+    TrailingBytes,
+    UnexpectedZero,
+    InvalidDuration,
+    IntegerOverflow,
+    InvalidCharCodePoint(u32),
+    /// Enum discriminant is out of range of declared variants, and enum has no
+    /// `#[serde(other)]` variant
+    InvalidVariantIndex(u32),
+    SequenceTooLong,
+    /// String or byte sequence is longer than [`SerializerParams::MAX_STR_LEN`](crate::params::SerializerParams::MAX_STR_LEN)
+    StringTooLong,
+    RecursionLimitExceeded,
+    InvalidHexEncoding,
+    PrecisionLoss,
+    PrefixOfNonCompoundValue,
+    /// Map of unknown length can't be serialized, in particular struct with
+    /// `#[serde(flatten)]` fields, which _serde_ serializes this way
+    FlattenNotSupported,
+    ChecksumMismatch,
+    /// NaN or infinite float value with [`EncodingParams::FINITE_FLOATS`](crate::params::EncodingParams::FINITE_FLOATS)
+    /// or NaN value of [`NonNanF64`](crate::primitives::NonNanF64)
+    NonFiniteFloat,
+    VersionMismatch {
+        expected: u32,
+        found: u32,
+    },
+    #[cfg(feature = "std")]
+    Io(std::io::ErrorKind),
     #[cfg(not(feature = "std"))]
     CannotSerializeDisplayInNoStdContext,
+    /// Map serialized with [`SerializerParams::SORT_MAP_ENTRIES`](crate::params::SerializerParams::SORT_MAP_ENTRIES)
+    #[cfg(not(feature = "std"))]
+    CannotSortMapInNoStdContext,
+    /// Sequence deserialized with [`SerializerParams::ESCAPED_BYTES`](crate::params::SerializerParams::ESCAPED_BYTES)
+    #[cfg(not(feature = "std"))]
+    CannotUnescapeInNoStdContext,
 }
 
 impl Error {
@@ -25,8 +67,8 @@ impl Error {
         match self {
             Error::Serde(_) => "serde custom error", // not used
             Error::SerializeSequenceMustHaveLength => "serialized sequence must have length",
-            Error::BufferOverflow => "serialized data buffer overflow",
-            Error::BufferUnderflow => "serialized data buffer underflow",
+            Error::BufferOverflow { .. } => "serialized data buffer overflow",
+            Error::BufferUnderflow { .. } => "serialized data buffer underflow",
             Error::PrematureEndOfInput => "premature end of input",
             Error::InvalidByteSequenceEscape => "invalid byte sequence escaping",
             Error::DeserializeAnyNotSupported => "deserialize to any type not supported",
@@ -35,8 +77,31 @@ impl Error {
             Error::InvalidUtf8Encoding => "invalid UTF-8 encoding",
             Error::InvalidTagEncoding => "invalid encoding for enum tag",
             Error::InvalidVarintEncoding => "invalid varint encoding",
+            Error::TrailingBytes => "trailing bytes remain after deserialization",
+            Error::UnexpectedZero => "zero value for non-zero integer type",
+            Error::InvalidDuration => "invalid duration encoding",
+            Error::IntegerOverflow => "integer value does not fit into target type",
+            Error::InvalidCharCodePoint(_) => "invalid char code point",
+            Error::InvalidVariantIndex(_) => "invalid enum variant index",
+            Error::SequenceTooLong => "sequence length exceeds maximum",
+            Error::StringTooLong => "string or byte sequence length exceeds maximum",
+            Error::RecursionLimitExceeded => "recursion depth limit exceeded",
+            Error::InvalidHexEncoding => "invalid hex encoding",
+            Error::PrecisionLoss => {
+                "value can't be represented in target type without precision loss"
+            }
+            Error::PrefixOfNonCompoundValue => "key prefix requires tuple or struct value",
+            Error::FlattenNotSupported => "flattened structs and unsized maps not supported",
+            Error::ChecksumMismatch => "checksum mismatch, data is corrupted",
+            Error::NonFiniteFloat => "NaN or infinite float value not allowed",
+            Error::VersionMismatch { .. } => "serialized data format version mismatch",
+            Error::Io(_) => "I/O error",
             #[cfg(not(feature = "std"))]
             Error::CannotSerializeDisplayInNoStdContext => "", // kill ide warning
+            #[cfg(not(feature = "std"))]
+            Error::CannotSortMapInNoStdContext => "sorting map entries requires std",
+            #[cfg(not(feature = "std"))]
+            Error::CannotUnescapeInNoStdContext => "deserializing escaped sequence requires std",
         }
     }
     #[cfg(not(feature = "std"))]
@@ -48,6 +113,14 @@ impl Error {
 impl core::fmt::Display for Error {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.write_str(self.descr())?;
+        match self {
+            Error::BufferOverflow { needed, available } => {
+                write!(f, ": needed {needed} bytes, available {available}")?;
+            }
+            Error::BufferUnderflow { remaining } => write!(f, ": {remaining} bytes remain")?,
+            Error::InvalidVariantIndex(index) => write!(f, " {index}")?,
+            _ => (),
+        }
         Ok(())
     }
 }
@@ -55,6 +128,48 @@ impl core::fmt::Display for Error {
 #[cfg(feature = "std")]
 impl std::error::Error for Error {}
 
+#[cfg(not(feature = "std"))]
+impl core::error::Error for Error {}
+
+/// Deserialization error with offset in the input buffer at which it occurred
+///
+/// Returned by [`de_from_bytes_asc_at()`](crate::de_from_bytes_asc_at). Offset of the error
+/// is the position of head cursor, or of tail cursor for errors in the tail section, please
+/// see [`DeBytesReader::error_offset()`](crate::DeBytesReader::error_offset).
+#[derive(Debug, Clone)]
+pub struct ErrorAt {
+    /// Offset from the beginning of input buffer
+    pub offset: usize,
+    /// Deserialization error
+    pub error: Error,
+}
+
+impl core::fmt::Display for ErrorAt {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} at offset {}", self.error, self.offset)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ErrorAt {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl core::error::Error for ErrorAt {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl From<ErrorAt> for Error {
+    fn from(e: ErrorAt) -> Self {
+        e.error
+    }
+}
+
 #[cfg(feature = "serde")]
 const _: () = {
     impl serde::ser::Error for Error {
@@ -38,11 +38,23 @@
 //!    If you need only primitives, you can opt out.
 //! * `std` (on by default): opt out for `#[no-std]` use, you will lose some convenience methods
 //!   which use `Vec<u8>`
+//! * `testing`: include `testing` module with helpers for checking that custom serializer
+//!   parameters preserve ordering of serialized data
+//! * `collation`: include functions in `primitives` module for serializing strings with
+//!   user-provided collation keys, for locale-aware ordering
 //!
 //! ## Stability guarantees
 //! The underlying encoding format is simple and unlikely to change.
 //! As a safeguard, `Serializer` and `Deserializer` implement `FormatVersion` trait for all serializer parameter
-//! pre-sets (`params::AscendingOrder`, `params::PortableBinary`, `params::NativeBinary`).
+//! pre-sets (`params::AscendingOrder`, `params::SelfDescribing`, `params::PortableBinary`,
+//! `params::NativeBinary`, `params::NativeBinaryRaw`). Use [`ser_to_vec_versioned()`] and
+//! [`de_from_bytes_versioned()`] to store this version along with serialized data and check it
+//! on deserialization.
+//! To detect corruption of long-lived stored values, use [`ser_to_vec_checksummed()`] and
+//! [`de_from_bytes_checksummed()`], which append and verify CRC-32 checksum.
+//!
+//! For prefix scans over composite keys, use [`ser_prefix()`] to serialize leading fields
+//! of a tuple or struct key.
 //!
 //! Note: serializing with descending lexicographic order is particularly useful for key-value
 //! databases like _rocksdb_, where reverse iteration is slower than forward iteration.
@@ -59,7 +71,7 @@ extern crate serde;
 mod errors;
 
 #[doc(inline)]
-pub use errors::Error;
+pub use errors::{Error, ErrorAt};
 
 /// A convenient Result type
 pub type Result<T = (), E = errors::Error> = core::result::Result<T, E>;
@@ -76,22 +88,39 @@ pub use buf::{DeBytesReader, DeBytesWriter, ReadFromTail, WriteToTail};
 #[doc(inline)]
 pub use params::Order;
 
+#[cfg(all(feature = "std", feature = "serde"))]
+mod checksum;
 #[cfg(feature = "serde")]
 mod ord_de;
 #[cfg(feature = "serde")]
 mod ord_ser;
+#[cfg(all(feature = "std", feature = "serde"))]
+mod prefix_ser;
+#[cfg(all(feature = "std", feature = "serde"))]
+mod scan;
 #[cfg(feature = "serde")]
 mod size_calc;
+#[cfg(feature = "serde")]
+mod type_tag;
+
+#[cfg(feature = "serde")]
+pub mod with;
+
+#[cfg(feature = "testing")]
+pub mod testing;
 
 #[doc(inline)]
 #[cfg(feature = "serde")]
-pub use ord_de::Deserializer;
+pub use ord_de::{Borrowed, Copied, Deserializer, Lend};
 #[doc(inline)]
 #[cfg(feature = "serde")]
 pub use ord_ser::Serializer;
 #[doc(inline)]
+#[cfg(all(feature = "std", feature = "serde"))]
+pub use scan::{KeyRange, ScanBounds};
+#[doc(inline)]
 #[cfg(feature = "serde")]
-pub use size_calc::SizeCalc;
+pub use size_calc::{ConstSize, SizeCalc};
 
 /// Current version of data encoding format for [`Serializer`] parametrized with
 /// some [`params::SerializerParams`].
@@ -105,6 +134,9 @@ pub trait FormatVersion<P: params::SerializerParams> {
 /// Calculation process is inexpensive, for fixed-size objects it evaluates to compile-time
 /// constant, or a few `len()` method calls for variable-size objects.
 ///
+/// Sequences which do not report their length to _serde_ can't be serialized, for them
+/// [`Error::SerializeSequenceMustHaveLength`] is returned at sizing time.
+///
 /// ```
 /// # use ordcode::{ calc_size, params };
 /// # use serde::ser::Serialize;
@@ -136,6 +168,56 @@ where
     calc_size(value, params::AscendingOrder)
 }
 
+/// Calculate sizes of head and tail sections of serialized data, returns `(head_len, tail_len)`
+///
+/// Head section holds serialized data, tail section holds sequence lengths for
+/// [`SerializerParams`](params::SerializerParams) which write them to the end of the
+/// double-ended buffer. Sum of the sizes is equal to [`calc_size()`].
+///
+/// ```
+/// # use ordcode::{ calc_size_split, params };
+/// #[derive(serde_derive::Serialize)]
+/// struct Foo(u16, String);
+/// let foo = Foo(1, "abc".to_string());
+///
+/// let (head, tail) = calc_size_split(&foo, params::AscendingOrder).unwrap();
+/// assert_eq!((head, tail), (5, 1));
+/// ```
+#[cfg(feature = "serde")]
+pub fn calc_size_split<T, P>(value: &T, _params: P) -> Result<(usize, usize)>
+where
+    T: ?Sized + serde::ser::Serialize,
+    P: params::SerializerParams,
+{
+    let mut sc = size_calc::SizeCalc::<P>::new();
+    value.serialize(&mut sc)?;
+    Ok((sc.head_size(), sc.tail_size()))
+}
+
+/// Calculate size of serialized data for a [`ConstSize`] value, without traversing it
+///
+/// Returns [`ConstSize::SIZE`], unless serializer parameters require self-describing
+/// serialization, in which case falls back to [`calc_size()`].
+///
+/// ```
+/// # use ordcode::{ calc_size, calc_size_const, params::AscendingOrder };
+/// let value = (1u32, 2u64, [3u8; 4]);
+/// assert_eq!(calc_size_const(&value, AscendingOrder).unwrap(), 16);
+/// assert_eq!(calc_size(&value, AscendingOrder).unwrap(), 16);
+/// ```
+#[cfg(feature = "serde")]
+pub fn calc_size_const<T, P>(value: &T, params: P) -> Result<usize>
+where
+    T: ConstSize,
+    P: params::SerializerParams,
+{
+    if P::SELF_DESCRIBING {
+        calc_size(value, params)
+    } else {
+        Ok(T::SIZE)
+    }
+}
+
 /// Serialize `value` into pre-allocated byte buffer.
 ///
 /// Buffer is supposed to be large enough to hold serialized data. You can use [`calc_size()`]
@@ -173,6 +255,39 @@ where
     Ok(len)
 }
 
+/// Serialize `value` into pre-allocated byte buffer, zero-filling unused part of the buffer
+///
+/// Same as [`ser_to_buf_ordered()`], but bytes past serialized data are set to zero, so that
+/// previous contents of reused buffer do not remain there. Returns range of the zeroed gap,
+/// serialized data length is the range start.
+///
+/// *Example*
+/// ```
+/// # use ordcode::{ Order, ser_to_buf_ordered_zeroed };
+/// let mut buf = [0xff_u8; 8];
+/// let gap = ser_to_buf_ordered_zeroed(&mut buf, &(1_u16, "abc"), Order::Descending).unwrap();
+/// assert_eq!(gap, 6..8);
+/// assert_eq!(buf[gap], [0, 0]);
+/// ```
+#[cfg(feature = "serde")]
+pub fn ser_to_buf_ordered_zeroed<T>(
+    buf: &mut [u8],
+    value: &T,
+    order: Order,
+) -> Result<core::ops::Range<usize>>
+where
+    T: ?Sized + serde::ser::Serialize,
+{
+    let mut de_buf = DeBytesWriter::new(buf);
+    let mut ser = new_ser_asc(&mut de_buf);
+    value.serialize(&mut ser)?;
+    let len = de_buf.finalize_zeroed()?;
+    if matches!(order, Order::Descending) {
+        primitives::invert_buffer(&mut buf[..len]);
+    }
+    Ok(len..buf.len())
+}
+
 /// Serialize `value` into pre-allocated, exact size byte buffer
 ///
 /// Buffer is expected to be of exact size to hold serialized data. You can use [`calc_size()`]
@@ -237,6 +352,401 @@ where
     Ok(byte_buf)
 }
 
+/// Serialize prefix of a tuple or struct key, consisting of its first `fields` fields
+///
+/// Returned bytes are a genuine prefix of the serialized full key (with the same `order`),
+/// so they can be used for prefix scans over keys which have the same leading fields.
+/// Serializing a separate tuple of leading fields does not give a prefix, because serialized
+/// value consists of the head section, with fields in order, followed by the tail section, with
+/// lengths of variable-size fields in reverse order; prefix contains only head section of
+/// the leading fields.
+///
+/// Note that since lengths of the leading fields are omitted, prefix of a variable-length field
+/// (e.g. `"ab"`) also matches keys where this field only starts with the same bytes (e.g. `"abc"`).
+/// If this matters, check results of the scan, or use fixed-size leading fields.
+///
+/// Value must be a tuple, tuple struct or struct (possibly wrapped into a newtype struct),
+/// otherwise [`Error::PrefixOfNonCompoundValue`] is returned. If `fields` is larger than
+/// number of fields, head sections of all fields are serialized.
+///
+/// *Example*
+/// ```
+/// # use ordcode::{ Order, ser_prefix, ser_to_vec_ordered };
+/// let key = ser_to_vec_ordered(&(1_u16, "abc", 2_u8), Order::Ascending).unwrap();
+/// let prefix = ser_prefix(&(1_u16, "abc", 2_u8), 2, Order::Ascending).unwrap();
+/// assert_eq!(prefix, [0, 1, b'a', b'b', b'c']);
+/// assert!(key.starts_with(&prefix));
+/// ```
+#[cfg(all(feature = "std", feature = "serde"))]
+pub fn ser_prefix<T>(value: &T, fields: usize, order: Order) -> Result<Vec<u8>>
+where
+    T: ?Sized + serde::ser::Serialize,
+{
+    let mut buf = prefix_ser::ser_prefix_asc(value, fields)?;
+    if matches!(order, Order::Descending) {
+        primitives::invert_buffer(&mut buf);
+    }
+    Ok(buf)
+}
+
+/// Serialize each of `values` into a single byte vector
+///
+/// Returns the byte vector and a vector of `values.len() + 1` offsets, so that serialized
+/// value `values[i]` is `buf[offsets[i]..offsets[i + 1]]`. Each value is serialized
+/// independently, as if it was serialized with [`ser_to_vec_ordered()`], but with only two
+/// allocations in total. Buffer size is calculated in advance with [`calc_size()`].
+///
+/// *Example*
+/// ```
+/// # use ordcode::{ Order, ser_many_to_vec };
+/// let (buf, offsets) = ser_many_to_vec(&[1_u16, 2, 3], Order::Ascending).unwrap();
+/// assert_eq!(offsets, [0, 2, 4, 6]);
+/// assert_eq!(&buf[offsets[1]..offsets[2]], &[0, 2]);
+/// ```
+#[cfg(all(feature = "std", feature = "serde"))]
+pub fn ser_many_to_vec<T>(values: &[T], order: Order) -> Result<(Vec<u8>, Vec<usize>)>
+where
+    T: serde::ser::Serialize,
+{
+    let mut offsets = Vec::with_capacity(values.len() + 1);
+    let mut total = 0;
+    offsets.push(total);
+    for value in values {
+        total += calc_size(value, params::AscendingOrder)?;
+        offsets.push(total);
+    }
+    let mut byte_buf = vec![0_u8; total];
+    for (value, w) in values.iter().zip(offsets.windows(2)) {
+        let mut de_buf = DeBytesWriter::new(&mut byte_buf[w[0]..w[1]]);
+        let mut ser = new_ser_asc(&mut de_buf);
+        value.serialize(&mut ser)?;
+        de_buf.is_complete()?;
+    }
+    if matches!(order, Order::Descending) {
+        primitives::invert_buffer(&mut byte_buf);
+    }
+    Ok((byte_buf, offsets))
+}
+
+/// Reusable buffer for serialization of many values, one at a time
+///
+/// Keeps its allocation between calls to [`ReusableBuffer::ser_into()`], so serializing
+/// a large number of keys in a loop allocates only when a value larger than all previous ones
+/// is encountered.
+///
+/// *Example*
+/// ```
+/// # use ordcode::{ Order, ReusableBuffer };
+/// let mut buf = ReusableBuffer::new();
+/// for i in 0..3_u8 {
+///     let key = buf.ser_into(&(i, "abc"), Order::Ascending).unwrap();
+///     assert_eq!(key, [i, b'a', b'b', b'c', 7]);
+/// }
+/// ```
+#[cfg(all(feature = "std", feature = "serde"))]
+#[derive(Debug, Default)]
+pub struct ReusableBuffer {
+    buf: Vec<u8>,
+}
+
+#[cfg(all(feature = "std", feature = "serde"))]
+impl ReusableBuffer {
+    /// Create empty buffer
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Create buffer with pre-allocated capacity
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            buf: Vec::with_capacity(capacity),
+        }
+    }
+    /// Serialize `value` into the buffer, replacing its previous contents
+    ///
+    /// Returns serialized data, same as [`ser_to_vec_ordered()`] would return.
+    pub fn ser_into<T>(&mut self, value: &T, order: Order) -> Result<&[u8]>
+    where
+        T: ?Sized + serde::ser::Serialize,
+    {
+        self.buf.clear();
+        self.buf
+            .resize(calc_size(value, params::AscendingOrder)?, 0);
+        ser_to_buf_asc_exact(&mut self.buf, value)?;
+        if matches!(order, Order::Descending) {
+            primitives::invert_buffer(&mut self.buf);
+        }
+        Ok(&self.buf)
+    }
+}
+
+/// Serializer for a stream of independent values written to a single writer
+///
+/// Each value is serialized separately, with its own tail section, so records can be decoded
+/// one at a time given their lengths (returned by [`StreamSerializer::write_value()`]).
+/// Values are serialized into an internal [`ReusableBuffer`] and then written to the writer,
+/// which is flushed with [`WriteBytes::flush()`](buf::WriteBytes::flush) after each value.
+///
+/// *Example*
+/// ```
+/// # use ordcode::{ Order, StreamSerializer, de_from_bytes_asc };
+/// let mut stream = StreamSerializer::new(Vec::new(), Order::Ascending);
+/// let len = stream.write_value(&"abc").unwrap();
+/// stream.write_value(&1_u16).unwrap();
+/// assert_eq!(stream.total_written(), len + 2);
+/// let buf = stream.into_inner();
+/// assert_eq!(de_from_bytes_asc::<String>(&buf[..len]).unwrap(), "abc");
+/// ```
+#[cfg(all(feature = "std", feature = "serde"))]
+pub struct StreamSerializer<W> {
+    writer: W,
+    order: Order,
+    scratch: ReusableBuffer,
+    total: usize,
+}
+
+#[cfg(all(feature = "std", feature = "serde"))]
+impl<W> StreamSerializer<W>
+where
+    W: buf::WriteBytes,
+{
+    /// Create stream serializer over `writer`
+    pub fn new(writer: W, order: Order) -> Self {
+        Self {
+            writer,
+            order,
+            scratch: ReusableBuffer::new(),
+            total: 0,
+        }
+    }
+    /// Serialize `value` and write it to the underlying writer
+    ///
+    /// Returns number of bytes written for this value.
+    pub fn write_value<T>(&mut self, value: &T) -> Result<usize>
+    where
+        T: ?Sized + serde::ser::Serialize,
+    {
+        let data = self.scratch.ser_into(value, self.order)?;
+        self.writer.write(data)?;
+        self.writer.flush()?;
+        self.total += data.len();
+        Ok(data.len())
+    }
+    /// Total number of bytes written
+    #[must_use]
+    pub fn total_written(&self) -> usize {
+        self.total
+    }
+    /// Get back the underlying writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Serialize `value` into byte vector, growing it as needed
+///
+/// Unlike [`ser_to_vec_ordered()`], does not calculate serialized data size in advance.
+/// This may be faster for values which are expensive to traverse, e.g. deeply nested structures.
+///
+/// *Example*
+/// ```
+/// # use ordcode::{ Order, ser_to_vec_grow };
+/// # use serde::ser::Serialize;
+///
+/// #[derive(serde_derive::Serialize)]
+/// struct Foo(u16, String);
+/// let foo = Foo(1, "abc".to_string());
+///
+/// let buf = ser_to_vec_grow(&foo, Order::Ascending).unwrap();
+/// assert_eq!(buf, [0, 1, b'a', b'b', b'c', 7]);
+/// ```
+#[cfg(all(feature = "std", feature = "serde"))]
+pub fn ser_to_vec_grow<T>(value: &T, order: Order) -> Result<Vec<u8>>
+where
+    T: ?Sized + serde::ser::Serialize,
+{
+    let mut writer = buf::VecWriter::new();
+    let mut ser = new_ser_asc(&mut writer);
+    value.serialize(&mut ser)?;
+    let mut byte_buf = writer.finalize();
+    if matches!(order, Order::Descending) {
+        primitives::invert_buffer(&mut byte_buf);
+    }
+    Ok(byte_buf)
+}
+
+// Data format version of `Serializer` used by `ser_to_vec_ordered()` and similar functions
+#[cfg(feature = "serde")]
+const ASC_FORMAT_VERSION: u32 =
+    <Serializer<(), params::AscendingOrder> as FormatVersion<params::AscendingOrder>>::VERSION;
+
+/// Serialize `value` into byte vector, prepended with data format version
+///
+/// Format version ([`FormatVersion::VERSION`]) is written as varint before serialized value
+/// and is never inverted, regardless of `order`. Use [`de_from_bytes_versioned()`] to
+/// deserialize. Version prefix is the same for all values, so it does not affect ordering.
+///
+/// *Example*
+/// ```
+/// # use ordcode::{ Order, ser_to_vec_versioned, de_from_bytes_versioned };
+/// let mut buf = ser_to_vec_versioned(&(1_u8, "abc"), Order::Descending).unwrap();
+/// let v: (u8, String) = de_from_bytes_versioned(&mut buf, Order::Descending).unwrap();
+/// assert_eq!(v, (1, "abc".to_string()));
+/// ```
+#[cfg(all(feature = "std", feature = "serde"))]
+pub fn ser_to_vec_versioned<T>(value: &T, order: Order) -> Result<Vec<u8>>
+where
+    T: ?Sized + serde::ser::Serialize,
+{
+    use varint::VarUInt;
+    let vlen = ASC_FORMAT_VERSION.varu_encoded_len() as usize;
+    let mut byte_buf = vec![0_u8; vlen + calc_size(value, params::AscendingOrder)?];
+    let (version, data) = byte_buf.split_at_mut(vlen);
+    ASC_FORMAT_VERSION.varu_to_writer(DeBytesWriter::new(version))?;
+    let mut de_buf = DeBytesWriter::new(data);
+    let mut ser = new_ser_asc(&mut de_buf);
+    value.serialize(&mut ser)?;
+    de_buf.is_complete()?;
+    if matches!(order, Order::Descending) {
+        primitives::invert_buffer(data);
+    }
+    Ok(byte_buf)
+}
+
+/// Deserialize value serialized with [`ser_to_vec_versioned()`]
+///
+/// Returns [`Error::VersionMismatch`] if data format version prefix differs from the current one.
+#[cfg(feature = "serde")]
+pub fn de_from_bytes_versioned<'de, T>(input: &'de mut [u8], order: Order) -> Result<T>
+where
+    T: serde::de::Deserialize<'de>,
+{
+    use varint::VarUInt;
+    let mut reader = DeBytesReader::new(input);
+    let found = u32::varu_from_reader(&mut reader)?;
+    if found != ASC_FORMAT_VERSION {
+        return Err(Error::VersionMismatch {
+            expected: ASC_FORMAT_VERSION,
+            found,
+        });
+    }
+    let vlen = reader.head_pos();
+    de_from_bytes_ordered(&mut input[vlen..], order)
+}
+
+/// Serialize `value` into byte vector, followed by CRC-32 checksum of serialized data
+///
+/// Checksum is calculated over serialized data (after inversion for [`Order::Descending`])
+/// and is appended as 4 bytes in big-endian layout, never inverted. Use
+/// [`de_from_bytes_checksummed()`] to verify checksum and deserialize.
+///
+/// Checksum is intended for detection of data corruption in long-lived stored values.
+/// Since it follows serialized data, which may be of variable length, and is not itself
+/// ordered, it breaks lexicographic ordering of serialized values, so don't use it for keys.
+///
+/// *Example*
+/// ```
+/// # use ordcode::{ Order, ser_to_vec_checksummed, de_from_bytes_checksummed };
+/// let mut buf = ser_to_vec_checksummed(&(1_u8, "abc"), Order::Ascending).unwrap();
+/// assert_eq!(buf.len(), 5 + 4);
+/// let v: (u8, String) = de_from_bytes_checksummed(&mut buf, Order::Ascending).unwrap();
+/// assert_eq!(v, (1, "abc".to_string()));
+/// ```
+#[cfg(all(feature = "std", feature = "serde"))]
+pub fn ser_to_vec_checksummed<T>(value: &T, order: Order) -> Result<Vec<u8>>
+where
+    T: ?Sized + serde::ser::Serialize,
+{
+    let mut byte_buf = ser_to_vec_ordered(value, order)?;
+    let crc = checksum::crc32(&byte_buf);
+    byte_buf.extend_from_slice(&crc.to_be_bytes());
+    Ok(byte_buf)
+}
+
+/// Deserialize value serialized with [`ser_to_vec_checksummed()`]
+///
+/// Returns [`Error::ChecksumMismatch`] if checksum of serialized data does not match
+/// the stored one.
+#[cfg(all(feature = "std", feature = "serde"))]
+pub fn de_from_bytes_checksummed<'de, T>(input: &'de mut [u8], order: Order) -> Result<T>
+where
+    T: serde::de::Deserialize<'de>,
+{
+    if input.len() < 4 {
+        return Err(Error::PrematureEndOfInput);
+    }
+    let (data, crc) = input.split_at_mut(input.len() - 4);
+    if checksum::crc32(data).to_be_bytes() != *crc {
+        return Err(Error::ChecksumMismatch);
+    }
+    de_from_bytes_ordered(data, order)
+}
+
+/// Serialize `value` into byte vector as a length-delimited frame
+///
+/// Frame consists of serialized data length in varint encoding, followed by serialized data.
+/// Frames can be concatenated, e.g. in a log file, and read back one by one with
+/// [`read_frame()`]. Length prefix is never inverted, so frames with different lengths are
+/// not ordered, don't use them for keys.
+///
+/// *Example*
+/// ```
+/// # use ordcode::{ Order, frame_to_vec, read_frame };
+/// let mut log = frame_to_vec(&"abc", Order::Ascending).unwrap();
+/// log.extend(frame_to_vec(&1_u16, Order::Ascending).unwrap());
+/// assert_eq!(log, [9, b'a', b'b', b'c', 7, 5, 0, 1]);
+/// let (s, rest): (String, _) = read_frame(&log, Order::Ascending).unwrap();
+/// let (v, rest): (u16, _) = read_frame(rest, Order::Ascending).unwrap();
+/// assert_eq!((s.as_str(), v), ("abc", 1));
+/// assert!(rest.is_empty());
+/// ```
+#[cfg(all(feature = "std", feature = "serde"))]
+pub fn frame_to_vec<T>(value: &T, order: Order) -> Result<Vec<u8>>
+where
+    T: ?Sized + serde::ser::Serialize,
+{
+    use varint::VarUInt;
+    let len = calc_size_asc(value)?;
+    let mut byte_buf = Vec::new();
+    (len as u64).varu_to_writer(&mut byte_buf)?;
+    let start = byte_buf.len();
+    byte_buf.resize(start + len, 0);
+    ser_to_buf_asc_exact(&mut byte_buf[start..], value)?;
+    if matches!(order, Order::Descending) {
+        primitives::invert_buffer(&mut byte_buf[start..]);
+    }
+    Ok(byte_buf)
+}
+
+/// Deserialize value from the first length-delimited frame in `input`, written with
+/// [`frame_to_vec()`]; returns the value and the rest of input after the frame
+///
+/// The whole frame must be consumed by deserialization, otherwise [`Error::TrailingBytes`]
+/// is returned. Values of frames with [`Order::Descending`] are deserialized without
+/// modification of `input`, so they can't borrow from it.
+#[cfg(all(feature = "std", feature = "serde"))]
+pub fn read_frame<'de, T>(input: &'de [u8], order: Order) -> Result<(T, &'de [u8])>
+where
+    T: serde::de::Deserialize<'de>,
+{
+    use core::convert::TryFrom;
+    use varint::VarUInt;
+    let mut reader = DeBytesReader::new(input);
+    let len =
+        usize::try_from(u64::varu_from_reader(&mut reader)?).map_err(|_| Error::IntegerOverflow)?;
+    let frame = reader.read_slice(len)?;
+    let value = if matches!(order, Order::Descending) {
+        let mut frame_reader = DeBytesReader::new(frame);
+        let value = T::deserialize(&mut new_de_desc(&mut frame_reader))?;
+        ensure_consumed(&mut frame_reader)?;
+        value
+    } else {
+        de_from_bytes_asc_exact::<T>(frame)?
+    };
+    Ok((value, reader.remaining_slice()))
+}
+
 /// Deserialize value from byte slice with [`params::AscendingOrder`]
 ///
 /// *Example*
@@ -257,9 +767,102 @@ pub fn de_from_bytes_asc<'de, T>(input: &'de [u8]) -> Result<T>
 where
     T: serde::de::Deserialize<'de>,
 {
-    let mut reader = DeBytesReader::new(input.as_ref());
-    let mut deser = new_de_asc(&mut reader);
-    T::deserialize(&mut deser)
+    let mut reader = DeBytesReader::new(input);
+    T::deserialize(&mut new_de_asc_borrowed(&mut reader))
+}
+
+/// Deserialize value from byte slice with [`params::AscendingOrder`], reporting offset of error
+///
+/// Same as [`de_from_bytes_asc()`], but error is returned together with the input offset at
+/// which it occurred, please see [`DeBytesReader::error_offset()`]. Errors which are not related
+/// to reading input are reported at the head cursor position.
+///
+/// *Example*
+/// ```
+/// # use ordcode::{ de_from_bytes_asc_at, Error };
+/// let buf = [0_u8, 7, 0];
+/// let err = de_from_bytes_asc_at::<(u16, u32)>(&buf).unwrap_err();
+/// assert!(matches!(err.error, Error::PrematureEndOfInput));
+/// assert_eq!(err.offset, 2);
+/// assert_eq!(err.to_string(), "premature end of input at offset 2");
+/// ```
+#[cfg(feature = "serde")]
+pub fn de_from_bytes_asc_at<'de, T>(input: &'de [u8]) -> Result<T, ErrorAt>
+where
+    T: serde::de::Deserialize<'de>,
+{
+    let mut reader = DeBytesReader::new(input);
+    T::deserialize(&mut new_de_asc_borrowed(&mut reader)).map_err(|error| {
+        let offset = reader.error_offset().unwrap_or_else(|| reader.head_pos());
+        ErrorAt { offset, error }
+    })
+}
+
+/// Deserialize value from byte slice with [`params::AscendingOrder`], requiring that
+/// the whole input is consumed.
+///
+/// Same as [`de_from_bytes_asc()`], but returns [`Error::TrailingBytes`] if any input bytes
+/// remain unconsumed after deserialization.
+///
+/// *Example*
+/// ```
+/// # use ordcode::{ de_from_bytes_asc_exact, Error };
+/// let buf = [0_u8, 1, 0, 2];
+/// let v: (u8, u8, u16) = de_from_bytes_asc_exact(&buf).unwrap();
+/// assert_eq!(v, (0, 1, 2));
+/// assert!(matches!(de_from_bytes_asc_exact::<(u8, u16)>(&buf), Err(Error::TrailingBytes)));
+/// ```
+#[cfg(feature = "serde")]
+pub fn de_from_bytes_asc_exact<'de, T>(input: &'de [u8]) -> Result<T>
+where
+    T: serde::de::Deserialize<'de>,
+{
+    let mut reader = DeBytesReader::new(input);
+    let value = T::deserialize(&mut new_de_asc_borrowed(&mut reader))?;
+    ensure_consumed(&mut reader)?;
+    Ok(value)
+}
+
+/// Deserialize value from byte slice, returns value and number of bytes it occupied
+///
+/// Number of consumed bytes is the sum of bytes read from the head and from the tail of `input`.
+/// It can be used to advance to the next value when parsing values which were serialized
+/// separately and concatenated, if they have no tail section: that is, with
+/// [`PortableBinary`](params::PortableBinary) parameters, or for values without variable-length
+/// data. Otherwise the tail section is read from the end of `input`, so
+/// use [`read_frame()`] for concatenated records, or [`DeBytesReader::remaining_slice()`]
+/// for values serialized into the same double-ended buffer.
+///
+/// *Example*
+/// ```
+/// # use ordcode::{ de_from_bytes_consumed, params::PortableBinary };
+/// #[derive(serde_derive::Serialize, serde_derive::Deserialize, Debug, PartialEq)]
+/// struct Rec(u8, String);
+///
+/// let buf = [1_u8, 5, b'a', b'b', 3, 3, b'c'];
+/// let (first, len) = de_from_bytes_consumed::<Rec, _>(&buf, PortableBinary).unwrap();
+/// assert_eq!((first, len), (Rec(1, "ab".into()), 4));
+/// let (second, _) = de_from_bytes_consumed::<Rec, _>(&buf[len..], PortableBinary).unwrap();
+/// assert_eq!(second, Rec(3, "c".into()));
+/// ```
+#[cfg(feature = "serde")]
+pub fn de_from_bytes_consumed<'de, T, P>(input: &'de [u8], params: P) -> Result<(T, usize)>
+where
+    T: serde::de::Deserialize<'de>,
+    P: params::SerializerParams,
+{
+    let mut reader = DeBytesReader::new(input);
+    let value = T::deserialize(&mut Deserializer::new_borrowed(&mut reader, params))?;
+    Ok((value, input.len() - reader.remaining()))
+}
+
+/// Convenience method: same as [`de_from_bytes_consumed()`], with [`params::AscendingOrder`]
+#[cfg(feature = "serde")]
+pub fn de_from_bytes_asc_consumed<'de, T>(input: &'de [u8]) -> Result<(T, usize)>
+where
+    T: serde::de::Deserialize<'de>,
+{
+    de_from_bytes_consumed(input, params::AscendingOrder)
 }
 
 /// Deserialize value from mutable byte slice.
@@ -287,9 +890,65 @@ where
     if matches!(order, Order::Descending) {
         primitives::invert_buffer(input.as_mut());
     }
-    let mut reader = DeBytesReader::new(input.as_mut());
+    let mut reader = DeBytesReader::new(input);
+    T::deserialize(&mut new_de_asc_borrowed(&mut reader))
+}
+
+/// Deserialize value from mutable byte slice, requiring that the whole input is consumed.
+///
+/// Same as [`de_from_bytes_ordered()`], but returns [`Error::TrailingBytes`] if any input bytes
+/// remain unconsumed after deserialization.
+#[cfg(feature = "serde")]
+pub fn de_from_bytes_ordered_exact<'de, T>(input: &'de mut [u8], order: Order) -> Result<T>
+where
+    T: serde::de::Deserialize<'de>,
+{
+    if matches!(order, Order::Descending) {
+        primitives::invert_buffer(input);
+    }
+    let mut reader = DeBytesReader::new(input);
+    let value = T::deserialize(&mut new_de_asc_borrowed(&mut reader))?;
+    ensure_consumed(&mut reader)?;
+    Ok(value)
+}
+
+/// Deserialize value from a frame of `frame_len` bytes read from `std::io::Read`,
+/// with [`params::AscendingOrder`]
+///
+/// The whole frame must be consumed by deserialization, otherwise [`Error::TrailingBytes`]
+/// is returned. Please see [`buf::IoReader`] for buffering details.
+///
+/// *Example*
+/// ```
+/// # use ordcode::de_from_reader;
+/// let data = [0_u8, 1, b'a', b'b', b'c', 7];
+/// let v: (u16, String) = de_from_reader(&data[..], data.len()).unwrap();
+/// assert_eq!(v, (1, "abc".to_string()));
+/// ```
+#[cfg(all(feature = "std", feature = "serde"))]
+pub fn de_from_reader<T, R>(reader: R, frame_len: usize) -> Result<T>
+where
+    T: serde::de::DeserializeOwned,
+    R: std::io::Read,
+{
+    let mut reader = buf::IoReader::new(reader, frame_len);
     let mut deser = new_de_asc(&mut reader);
-    T::deserialize(&mut deser)
+    let value = T::deserialize(&mut deser)?;
+    if reader.remaining() == 0 {
+        Ok(value)
+    } else {
+        Err(Error::TrailingBytes)
+    }
+}
+
+#[cfg(feature = "serde")]
+fn ensure_consumed(reader: &mut DeBytesReader) -> Result {
+    use buf::ReadBytes;
+    if reader.remaining_buffer().is_empty() {
+        Ok(())
+    } else {
+        Err(Error::TrailingBytes)
+    }
 }
 
 /// Create new default serializer instance (with [`params::AscendingOrder`])
@@ -302,6 +961,30 @@ where
     Serializer::new(writer, params::AscendingOrder)
 }
 
+/// Create new serializer instance for descending order
+///
+/// Serializes with [`params::AscendingOrder`] into [`buf::InvertWriter`], so that serialized
+/// bytes are inverted as they are written, same as with [`ser_to_vec_ordered()`] with
+/// [`Order::Descending`]. Useful for streaming descending order keys directly into the writer.
+///
+/// *Example*
+/// ```
+/// # use ordcode::{ Order, buf::IoWriter, new_ser_desc, ser_to_vec_ordered };
+/// # use serde::ser::Serialize;
+/// let mut writer = IoWriter::new(Vec::new());
+/// ("abc", 1_u8).serialize(&mut new_ser_desc(&mut writer)).unwrap();
+/// let buf = writer.finalize().unwrap();
+/// assert_eq!(buf, ser_to_vec_ordered(&("abc", 1_u8), Order::Descending).unwrap());
+/// ```
+#[cfg(feature = "serde")]
+#[inline]
+pub fn new_ser_desc<W>(writer: W) -> Serializer<buf::InvertWriter<W>, params::AscendingOrder>
+where
+    W: buf::TailWriteBytes,
+{
+    Serializer::new(buf::InvertWriter(writer), params::AscendingOrder)
+}
+
 /// Create new default deserializer instance (with [`params::AscendingOrder`])
 #[cfg(feature = "serde")]
 #[inline]
@@ -311,3 +994,29 @@ where
 {
     Deserializer::new(reader, params::AscendingOrder)
 }
+
+/// Create new deserializer instance with [`params::AscendingOrder`], which borrows `&str` and
+/// `&[u8]` values from `reader`
+///
+/// Please see [`Deserializer::new_borrowed()`].
+#[cfg(feature = "serde")]
+#[inline]
+pub fn new_de_asc_borrowed<'de, R>(reader: R) -> Deserializer<R, params::AscendingOrder, Borrowed>
+where
+    R: buf::BorrowReadBytes<'de> + buf::TailReadBytes,
+{
+    Deserializer::new_borrowed(reader, params::AscendingOrder)
+}
+
+/// Create new deserializer instance for descending order
+///
+/// Deserializes with [`params::AscendingOrder`] from [`buf::InvertReader`], so that input
+/// does not have to be inverted in place. Counterpart of [`new_ser_desc()`].
+#[cfg(all(feature = "std", feature = "serde"))]
+#[inline]
+pub fn new_de_desc<R>(reader: R) -> Deserializer<buf::InvertReader<R>, params::AscendingOrder>
+where
+    R: buf::TailReadBytes,
+{
+    Deserializer::new(buf::InvertReader::new(reader), params::AscendingOrder)
+}
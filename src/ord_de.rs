@@ -1,52 +1,358 @@
-use crate::params::{AscendingOrder, NativeBinary, PortableBinary};
+use crate::ord_ser::packed_options;
+use crate::params::{
+    AscendingOrder, FiniteFloats, NativeBinary, NativeBinaryRaw, PortableBinary, SelfDescribing,
+};
 use crate::primitives::SerializableValue;
 use crate::{
-    buf::TailReadBytes,
+    buf::{BorrowReadBytes, DeBytesReader, TailReadBytes},
     params::{LengthEncoder, SerializerParams},
-    Error, FormatVersion, Result,
+    type_tag, Error, FormatVersion, Result,
 };
-use serde::de::IntoDeserializer;
+use core::convert::TryFrom;
+use core::marker::PhantomData;
+use serde::de::{Deserialize, IntoDeserializer};
 
 /// `serde` deserializer for binary data format which may preserve lexicographic ordering of values
 ///
 /// Please see `Serializer` documentation for more details.
-pub struct Deserializer<R, P> {
+///
+/// Enum discriminant which is out of range of known variants is passed to the enum visitor, so it
+/// can be mapped to a `#[serde(other)]` unit variant; without such variant,
+/// [`Error::InvalidVariantIndex`] is returned. Value of unknown variant is skipped for self-describing
+/// [`SerializerParams`], otherwise it is assumed to be unit, so it is safe to add only unit variants.
+/// Catch-all variant should be the last one, and new variants should be added after it.
+///
+/// Ignored values (`serde::de::IgnoredAny`) are skipped according to type tags for
+/// self-describing [`SerializerParams`]. Otherwise [`Error::DeserializeIgnoredAny`] is returned,
+/// unless ignored byte sequences are opted in with [`SerializerParams::SKIP_IGNORED_AS_BYTES`].
+///
+/// Leading fields of a key can be decoded without the rest of it: deserialize a tuple (or struct)
+/// of the leading fields from data of a longer tuple, and trailing fields are not read at all.
+/// This works for outermost tuple or struct only, and the leading fields must have the same types
+/// as serialized. Leading fields don't have to be fixed-size: lengths of variable-size fields are
+/// read from the tail in the same order as fields are read from the head. Do not use functions
+/// which check that input is fully consumed, such as
+/// [`de_from_bytes_asc_exact()`](crate::de_from_bytes_asc_exact), for this.
+///
+/// Type parameter `L` defines whether `&'de str` and `&'de [u8]` can be deserialized: deserializer
+/// created with [`Deserializer::new()`] passes copies of strings and byte sequences to visitors,
+/// while one created with [`Deserializer::new_borrowed()`] lends them from the reader.
+#[derive(Clone)]
+pub struct Deserializer<R, P, L = Copied> {
     reader: R,
     params: P,
+    depth: usize,
+    // number of nested `Some` values remaining, with `SerializerParams::PACKED_OPTIONS`
+    option_chain: Option<u8>,
+    _lend: PhantomData<L>,
+}
+
+/// Defines how [`Deserializer`] passes strings and byte sequences read by `R` to visitors
+pub trait Lend<'de, R> {
+    /// Get `n` bytes borrowed from `reader` and advance by `n` bytes, or return `None`
+    /// without advancing if data should be copied
+    fn read_borrowed(reader: &mut R, n: usize) -> Result<Option<&'de [u8]>>;
 }
 
-impl<'de, R, P> Deserializer<R, P>
+/// Strings and byte sequences are copied, default for [`Deserializer`]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Copied;
+
+impl<'de, R> Lend<'de, R> for Copied {
+    #[inline]
+    fn read_borrowed(_reader: &mut R, _n: usize) -> Result<Option<&'de [u8]>> {
+        Ok(None)
+    }
+}
+
+/// Strings and byte sequences are borrowed from reader, please see [`Deserializer::new_borrowed()`]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Borrowed;
+
+impl<'de, R: BorrowReadBytes<'de>> Lend<'de, R> for Borrowed {
+    #[inline]
+    fn read_borrowed(reader: &mut R, n: usize) -> Result<Option<&'de [u8]>> {
+        reader.read_borrowed(n).map(Some)
+    }
+}
+
+impl<R, P, L> core::fmt::Debug for Deserializer<R, P, L>
+where
+    R: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Deserializer")
+            .field("params", &core::any::type_name::<P>())
+            .field("reader", &self.reader)
+            .field("depth", &self.depth)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<R, P> Deserializer<R, P>
 where
     R: TailReadBytes,
     P: SerializerParams,
 {
     #[must_use]
     pub fn new(reader: R, params: P) -> Self {
-        Deserializer { reader, params }
+        Deserializer {
+            reader,
+            params,
+            depth: 0,
+            option_chain: None,
+            _lend: PhantomData,
+        }
     }
+}
+
+impl<R, P> Deserializer<R, P, Borrowed>
+where
+    P: SerializerParams,
+{
+    /// Create deserializer which borrows `&'de str` and `&'de [u8]` values from `reader`
+    ///
+    /// *Example*
+    /// ```
+    /// # use ordcode::{ DeBytesReader, Deserializer, Order, params::AscendingOrder, ser_to_vec_ordered };
+    /// # use serde::de::Deserialize;
+    /// let buf = ser_to_vec_ordered(&(7_u16, "ab"), Order::Ascending).unwrap();
+    /// let mut reader = DeBytesReader::new(&buf);
+    /// let v = <(u16, &str)>::deserialize(&mut Deserializer::new_borrowed(&mut reader, AscendingOrder));
+    /// assert_eq!(v.unwrap(), (7, "ab"));
+    /// assert_eq!(reader.remaining(), 0);
+    /// ```
+    #[must_use]
+    pub fn new_borrowed<'de>(reader: R, params: P) -> Self
+    where
+        R: BorrowReadBytes<'de> + TailReadBytes,
+    {
+        Deserializer {
+            reader,
+            params,
+            depth: 0,
+            option_chain: None,
+            _lend: PhantomData,
+        }
+    }
+}
+
+impl<'de, R, P, L> Deserializer<R, P, L>
+where
+    R: TailReadBytes,
+    P: SerializerParams,
+{
     pub fn into_reader(self) -> R {
         self.reader
     }
 
+    // read length of sequence, map, string or byte sequence
+    fn read_len(&mut self) -> Result<usize> {
+        if P::STRICT_VARINT {
+            P::SeqLenEncoder::read_strict(&mut self.reader)
+        } else {
+            P::SeqLenEncoder::read(&mut self.reader)
+        }
+    }
+    fn read_discr(&mut self) -> Result<u32> {
+        if P::STRICT_VARINT {
+            P::DiscriminantEncoder::read_strict(&mut self.reader)
+        } else {
+            P::DiscriminantEncoder::read(&mut self.reader)
+        }
+    }
+    // read length of sequence or map, checking it against `SerializerParams::MAX_SEQ_LEN`
+    fn read_seq_len(&mut self) -> Result<usize> {
+        let len = self.read_len()?;
+        if len as u64 > P::MAX_SEQ_LEN {
+            Err(Error::SequenceTooLong)
+        } else {
+            Ok(len)
+        }
+    }
+    // deserialize nested value, checking nesting depth against `SerializerParams::MAX_DEPTH`
+    fn nested<F, T>(&mut self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Self) -> Result<T>,
+    {
+        if self.depth >= P::MAX_DEPTH {
+            return Err(Error::RecursionLimitExceeded);
+        }
+        self.depth += 1;
+        let r = f(self);
+        self.depth -= 1;
+        r
+    }
+    #[cfg(feature = "std")]
     fn visit_bytebuf<V, F>(&mut self, f: F) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
         F: FnOnce(&[u8]) -> Result<V::Value>,
     {
-        let len = P::SeqLenEncoder::read(&mut self.reader)?;
+        if P::ESCAPED_BYTES {
+            return f(&self.read_escaped()?);
+        }
+        let len = self.read_len()?;
         self.reader.read(len, f)
     }
+    // read string or byte sequence written with `SerializerParams::ESCAPED_BYTES`
+    #[cfg(feature = "std")]
+    fn read_escaped(&mut self) -> Result<Vec<u8>> {
+        crate::bytes_esc::deserialize_escaped(&mut self.reader, self.params)
+    }
+    // unescaping requires a buffer, so escaped sequences can't be read without `std`
+    #[cfg(not(feature = "std"))]
+    fn read_escaped(&mut self) -> Result<&'static [u8]> {
+        Err(Error::CannotUnescapeInNoStdContext)
+    }
 }
 
-impl<W> FormatVersion<AscendingOrder> for Deserializer<W, AscendingOrder> {
+impl<'de, R, P, L> Deserializer<R, P, L>
+where
+    R: TailReadBytes,
+    P: SerializerParams,
+    L: Lend<'de, R>,
+{
+    fn visit_str<V>(&mut self, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        if P::ESCAPED_BYTES {
+            let buf = self.read_escaped()?;
+            return visitor
+                .visit_str(core::str::from_utf8(&buf).map_err(|_| Error::InvalidUtf8Encoding)?);
+        }
+        let len = self.read_len()?;
+        if let Some(buf) = L::read_borrowed(&mut self.reader, len)? {
+            return visitor.visit_borrowed_str(
+                core::str::from_utf8(buf).map_err(|_| Error::InvalidUtf8Encoding)?,
+            );
+        }
+        self.reader.read(len, |buf| {
+            visitor.visit_str(core::str::from_utf8(buf).map_err(|_| Error::InvalidUtf8Encoding)?)
+        })
+    }
+    fn visit_bytes<V>(&mut self, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        if P::ESCAPED_BYTES {
+            return visitor.visit_bytes(&self.read_escaped()?);
+        }
+        let len = self.read_len()?;
+        if let Some(buf) = L::read_borrowed(&mut self.reader, len)? {
+            return visitor.visit_borrowed_bytes(buf);
+        }
+        self.reader.read(len, |buf| visitor.visit_bytes(buf))
+    }
+    fn visit_option<V>(&mut self, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        let value = <u8>::from_reader(&mut self.reader, self.params)?;
+        match (value, P::OPTION_NULLS_LAST) {
+            (0, false) | (1, true) => visitor.visit_none(),
+            (1, false) | (0, true) => self.nested(|de| visitor.visit_some(de)),
+            _ => Err(Error::InvalidTagEncoding),
+        }
+    }
+    fn visit_seq<V>(&mut self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        self.nested(|de| {
+            visitor.visit_seq(SeqAccess {
+                deserializer: de,
+                len,
+            })
+        })
+    }
+    fn visit_map<V>(&mut self, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        let len = self.read_seq_len()?;
+        self.nested(|de| {
+            visitor.visit_map(MapAccess {
+                deserializer: de,
+                len,
+            })
+        })
+    }
+    // read and check type tag in self-describing deserialization
+    fn expect_tag(&mut self, tag: u8) -> Result {
+        if P::SELF_DESCRIBING && self.read_tag()? != tag {
+            Err(Error::InvalidTagEncoding)
+        } else {
+            self.end_option_chain()
+        }
+    }
+    // packed options: value of the innermost `Some` must follow, when all `Some` were visited
+    #[inline]
+    fn end_option_chain(&mut self) -> Result {
+        if packed_options::<P>() && matches!(self.option_chain.take(), Some(n) if n > 0) {
+            Err(Error::InvalidTagEncoding)
+        } else {
+            Ok(())
+        }
+    }
+    fn visit_packed_option<V>(&mut self, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        let n = if let Some(n) = self.option_chain.take() {
+            n
+        } else {
+            let v = <u8>::from_reader(&mut self.reader, self.params)?;
+            if P::OPTION_NULLS_LAST {
+                !v
+            } else {
+                v
+            }
+        };
+        if n == 0 {
+            visitor.visit_none()
+        } else {
+            self.option_chain = Some(n - 1);
+            self.nested(|de| visitor.visit_some(de))
+        }
+    }
+    fn read_tag(&mut self) -> Result<u8> {
+        self.reader.read(1, |buf| Ok(buf[0]))
+    }
+}
+
+impl<'a, P, L> Deserializer<DeBytesReader<'a>, P, L> {
+    /// Consume deserializer, returning unconsumed part of the input
+    ///
+    /// Please see [`DeBytesReader::remaining_slice()`].
+    #[must_use]
+    pub fn into_remaining(self) -> &'a [u8] {
+        self.reader.remaining_slice()
+    }
+}
+
+impl<W, L> FormatVersion<AscendingOrder> for Deserializer<W, AscendingOrder, L> {
     const VERSION: u32 = 1;
 }
 
-impl<W> FormatVersion<PortableBinary> for Deserializer<W, PortableBinary> {
+impl<W, L> FormatVersion<FiniteFloats> for Deserializer<W, FiniteFloats, L> {
     const VERSION: u32 = 1;
 }
 
-impl<W> FormatVersion<NativeBinary> for Deserializer<W, NativeBinary> {
+impl<W, L> FormatVersion<SelfDescribing> for Deserializer<W, SelfDescribing, L> {
+    const VERSION: u32 = 1;
+}
+
+impl<W, L> FormatVersion<PortableBinary> for Deserializer<W, PortableBinary, L> {
+    const VERSION: u32 = 1;
+}
+
+impl<W, L> FormatVersion<NativeBinary> for Deserializer<W, NativeBinary, L> {
+    const VERSION: u32 = 1;
+}
+
+impl<W, L> FormatVersion<NativeBinaryRaw> for Deserializer<W, NativeBinaryRaw, L> {
     const VERSION: u32 = 1;
 }
 
@@ -57,24 +363,85 @@ macro_rules! impl_nums {
         where
             V: serde::de::Visitor<'de>,
         {
+            if P::SELF_DESCRIBING {
+                return self.deserialize_any(visitor);
+            }
+            self.end_option_chain()?;
             let value = <$ty>::from_reader(&mut self.reader, self.params)?;
             visitor.$visitor_method(value)
         }
     };
 }
 
-impl<'a, 'de: 'a, R, P> serde::Deserializer<'de> for &'a mut Deserializer<R, P>
+// forward to `deserialize_any()` for self-describing deserialization,
+// otherwise check that packed options are complete
+macro_rules! forward_self_describing {
+    ($self:ident, $visitor:ident) => {
+        if P::SELF_DESCRIBING {
+            return $self.deserialize_any($visitor);
+        }
+        $self.end_option_chain()?;
+    };
+}
+
+impl<'a, 'de: 'a, R, P, L> serde::Deserializer<'de> for &'a mut Deserializer<R, P, L>
 where
     R: TailReadBytes,
     P: SerializerParams,
+    L: Lend<'de, R>,
 {
     type Error = Error;
 
-    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value>
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
-        Err(Error::DeserializeAnyNotSupported)
+        macro_rules! visit_num {
+            ($ty:ty, $visitor_method:ident) => {
+                visitor.$visitor_method(<$ty>::from_reader(&mut self.reader, self.params)?)
+            };
+        }
+        if !P::SELF_DESCRIBING {
+            return Err(Error::DeserializeAnyNotSupported);
+        }
+        match self.read_tag()? {
+            type_tag::UNIT => visitor.visit_unit(),
+            type_tag::BOOL => visit_num!(bool, visit_bool),
+            type_tag::U8 => visit_num!(u8, visit_u8),
+            type_tag::U16 => visit_num!(u16, visit_u16),
+            type_tag::U32 => visit_num!(u32, visit_u32),
+            type_tag::U64 => visit_num!(u64, visit_u64),
+            type_tag::I8 => visit_num!(i8, visit_i8),
+            type_tag::I16 => visit_num!(i16, visit_i16),
+            type_tag::I32 => visit_num!(i32, visit_i32),
+            type_tag::I64 => visit_num!(i64, visit_i64),
+            #[cfg(not(no_i128))]
+            type_tag::U128 => visit_num!(u128, visit_u128),
+            #[cfg(not(no_i128))]
+            type_tag::I128 => visit_num!(i128, visit_i128),
+            type_tag::F32 => visit_num!(f32, visit_f32),
+            type_tag::F64 => visit_num!(f64, visit_f64),
+            type_tag::CHAR => visit_num!(char, visit_char),
+            type_tag::STR => self.visit_str(visitor),
+            type_tag::BYTES => self.visit_bytes(visitor),
+            type_tag::OPTION => self.visit_option(visitor),
+            type_tag::SEQ => {
+                let len = self.read_seq_len()?;
+                self.visit_seq(len, visitor)
+            }
+            type_tag::MAP => self.visit_map(visitor),
+            type_tag::ENUM => {
+                // enum is visited as a map with single `discriminant => value` entry
+                let discr = self.read_discr()?;
+                self.nested(|de| {
+                    visitor.visit_map(VariantMapAccess {
+                        deserializer: de,
+                        discr: Some(discr),
+                    })
+                })
+            }
+            _ => Err(Error::InvalidTagEncoding),
+        }
     }
     impl_nums!(u8, deserialize_u8, visit_u8);
     impl_nums!(u16, deserialize_u16, visit_u16);
@@ -98,14 +465,14 @@ where
     where
         V: serde::de::Visitor<'de>,
     {
-        self.visit_bytebuf::<V, _>(|buf| {
-            visitor.visit_str(core::str::from_utf8(buf).map_err(|_| Error::InvalidUtf8Encoding)?)
-        })
+        forward_self_describing!(self, visitor);
+        self.visit_str(visitor)
     }
     fn deserialize_string<V>(self, visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
+        forward_self_describing!(self, visitor);
         #[cfg(not(feature = "std"))]
         return self.deserialize_str(visitor);
         #[cfg(feature = "std")]
@@ -119,7 +486,8 @@ where
     where
         V: serde::de::Visitor<'de>,
     {
-        self.visit_bytebuf::<V, _>(|buf| visitor.visit_bytes(buf))
+        forward_self_describing!(self, visitor);
+        self.visit_bytes(visitor)
     }
     fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
     where
@@ -131,46 +499,45 @@ where
     where
         V: serde::de::Visitor<'de>,
     {
-        let value = <u8>::from_reader(&mut self.reader, self.params)?;
-        match value {
-            0 => visitor.visit_none(),
-            1 => visitor.visit_some(&mut *self),
-            _ => Err(Error::InvalidTagEncoding),
+        if packed_options::<P>() {
+            return self.visit_packed_option(visitor);
         }
+        forward_self_describing!(self, visitor);
+        self.visit_option(visitor)
     }
     fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
+        forward_self_describing!(self, visitor);
         visitor.visit_unit()
     }
     fn deserialize_unit_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
-        visitor.visit_unit()
+        self.deserialize_unit(visitor)
     }
     fn deserialize_newtype_struct<V>(self, _name: &str, visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
-        visitor.visit_newtype_struct(self)
+        self.nested(|de| visitor.visit_newtype_struct(de))
     }
     fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
-        let len = P::SeqLenEncoder::read(&mut self.reader)?;
-        self.deserialize_tuple(len, visitor)
+        forward_self_describing!(self, visitor);
+        let len = self.read_seq_len()?;
+        self.visit_seq(len, visitor)
     }
     fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
-        visitor.visit_seq(SeqAccess {
-            deserializer: self,
-            len,
-        })
+        forward_self_describing!(self, visitor);
+        self.visit_seq(len, visitor)
     }
 
     fn deserialize_tuple_struct<V>(
@@ -188,11 +555,8 @@ where
     where
         V: serde::de::Visitor<'de>,
     {
-        let len = P::SeqLenEncoder::read(&mut self.reader)?;
-        visitor.visit_map(MapAccess {
-            deserializer: self,
-            len,
-        })
+        forward_self_describing!(self, visitor);
+        self.visit_map(visitor)
     }
     fn deserialize_struct<V>(
         self,
@@ -208,30 +572,19 @@ where
     fn deserialize_enum<V>(
         self,
         _enum: &'static str,
-        _variants: &'static [&'static str],
+        variants: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
-        impl<'a, 'de: 'a, R, P> serde::de::EnumAccess<'de> for &'a mut Deserializer<R, P>
-        where
-            R: TailReadBytes,
-            P: SerializerParams,
-        {
-            type Error = Error;
-            type Variant = Self;
-
-            fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
-            where
-                V: serde::de::DeserializeSeed<'de>,
-            {
-                let idx = P::DiscriminantEncoder::read(&mut self.reader)?;
-                let val: Result<_> = seed.deserialize(idx.into_deserializer());
-                Ok((val?, self))
-            }
-        }
-        visitor.visit_enum(self)
+        self.expect_tag(type_tag::ENUM)?;
+        self.nested(|de| {
+            visitor.visit_enum(EnumAccess {
+                deserializer: de,
+                variants: variants.len(),
+            })
+        })
     }
     fn deserialize_identifier<V>(self, _visitor: V) -> Result<V::Value>
     where
@@ -240,24 +593,45 @@ where
         Err(Error::DeserializeIdentifierNotSupported)
     }
 
-    fn deserialize_ignored_any<V>(self, _visitor: V) -> Result<V::Value>
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
-        Err(Error::DeserializeIgnoredAny)
+        forward_self_describing!(self, visitor);
+        if !P::SKIP_IGNORED_AS_BYTES {
+            return Err(Error::DeserializeIgnoredAny);
+        }
+        // no type information: skip value as a byte sequence, which must span the rest of
+        // input, so that values of other types are rejected instead of being skipped incorrectly
+        if P::ESCAPED_BYTES {
+            self.read_escaped()?;
+        } else {
+            let len = self.read_seq_len()?;
+            if len != self.reader.remaining_buffer().len() {
+                return Err(Error::DeserializeIgnoredAny);
+            }
+            self.reader.advance(len);
+        }
+        if !self.reader.remaining_buffer().is_empty() {
+            return Err(Error::DeserializeIgnoredAny);
+        }
+        visitor.visit_unit()
     }
     fn is_human_readable(&self) -> bool {
         false
     }
 }
 
-struct SeqAccess<'a, R: TailReadBytes, P: SerializerParams> {
-    deserializer: &'a mut Deserializer<R, P>,
+struct SeqAccess<'a, R: TailReadBytes, P: SerializerParams, L> {
+    deserializer: &'a mut Deserializer<R, P, L>,
     len: usize,
 }
 
-impl<'a, 'de: 'a, R: TailReadBytes, P: SerializerParams> serde::de::SeqAccess<'de>
-    for SeqAccess<'a, R, P>
+impl<'a, 'de: 'a, R, P, L> serde::de::SeqAccess<'de> for SeqAccess<'a, R, P, L>
+where
+    R: TailReadBytes,
+    P: SerializerParams,
+    L: Lend<'de, R>,
 {
     type Error = Error;
     fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
@@ -277,12 +651,15 @@ impl<'a, 'de: 'a, R: TailReadBytes, P: SerializerParams> serde::de::SeqAccess<'d
     }
 }
 
-struct MapAccess<'a, R: TailReadBytes, P: SerializerParams> {
-    deserializer: &'a mut Deserializer<R, P>,
+struct MapAccess<'a, R: TailReadBytes, P: SerializerParams, L> {
+    deserializer: &'a mut Deserializer<R, P, L>,
     len: usize,
 }
-impl<'a, 'de: 'a, R: TailReadBytes, P: SerializerParams> serde::de::MapAccess<'de>
-    for MapAccess<'a, R, P>
+impl<'a, 'de: 'a, R, P, L> serde::de::MapAccess<'de> for MapAccess<'a, R, P, L>
+where
+    R: TailReadBytes,
+    P: SerializerParams,
+    L: Lend<'de, R>,
 {
     type Error = Error;
     fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
@@ -304,39 +681,129 @@ impl<'a, 'de: 'a, R: TailReadBytes, P: SerializerParams> serde::de::MapAccess<'d
         let value = seed.deserialize(&mut *self.deserializer)?;
         Ok(value)
     }
+    // exact number of remaining entries, lets maps like `HashMap` reserve capacity upfront
     fn size_hint(&self) -> Option<usize> {
         Some(self.len)
     }
 }
 
-impl<'a, 'de: 'a, R, P> serde::de::VariantAccess<'de> for &'a mut Deserializer<R, P>
+// Enum variant as a map with single entry, for self-describing deserialization
+struct VariantMapAccess<'a, R: TailReadBytes, P: SerializerParams, L> {
+    deserializer: &'a mut Deserializer<R, P, L>,
+    discr: Option<u32>,
+}
+
+impl<'a, 'de: 'a, R, P, L> serde::de::MapAccess<'de> for VariantMapAccess<'a, R, P, L>
+where
+    R: TailReadBytes,
+    P: SerializerParams,
+    L: Lend<'de, R>,
+{
+    type Error = Error;
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: serde::de::DeserializeSeed<'de>,
+    {
+        match self.discr {
+            Some(discr) => seed.deserialize(discr.into_deserializer()).map(Some),
+            None => Ok(None),
+        }
+    }
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: serde::de::DeserializeSeed<'de>,
+    {
+        self.discr = None;
+        seed.deserialize(&mut *self.deserializer)
+    }
+    fn size_hint(&self) -> Option<usize> {
+        Some(usize::from(self.discr.is_some()))
+    }
+}
+
+// Discriminant is passed to the variant visitor as is. If it is out of range of known variants,
+// visitor may map it to `#[serde(other)]` unit variant, otherwise `InvalidVariantIndex` is returned,
+// instead of visitor's error.
+struct EnumAccess<'a, R: TailReadBytes, P: SerializerParams, L> {
+    deserializer: &'a mut Deserializer<R, P, L>,
+    variants: usize,
+}
+
+impl<'a, 'de: 'a, R, P, L> serde::de::EnumAccess<'de> for EnumAccess<'a, R, P, L>
+where
+    R: TailReadBytes,
+    P: SerializerParams,
+    L: Lend<'de, R>,
+{
+    type Error = Error;
+    type Variant = VariantAccess<'a, R, P, L>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where
+        V: serde::de::DeserializeSeed<'de>,
+    {
+        let idx = self.deserializer.read_discr()?;
+        let unknown = usize::try_from(idx).map_or(true, |idx| idx >= self.variants);
+        let val: Result<_> = seed.deserialize(idx.into_deserializer());
+        let val = val.map_err(|e| {
+            if unknown {
+                Error::InvalidVariantIndex(idx)
+            } else {
+                e
+            }
+        })?;
+        Ok((
+            val,
+            VariantAccess {
+                deserializer: self.deserializer,
+                unknown,
+            },
+        ))
+    }
+}
+
+struct VariantAccess<'a, R: TailReadBytes, P: SerializerParams, L> {
+    deserializer: &'a mut Deserializer<R, P, L>,
+    unknown: bool,
+}
+
+impl<'a, 'de: 'a, R, P, L> serde::de::VariantAccess<'de> for VariantAccess<'a, R, P, L>
 where
     R: TailReadBytes,
     P: SerializerParams,
+    L: Lend<'de, R>,
 {
     type Error = Error;
 
     fn unit_variant(self) -> Result {
+        if !self.unknown {
+            return self.deserializer.expect_tag(type_tag::UNIT);
+        }
+        // unknown variant mapped to `#[serde(other)]`: its value can be skipped only if data
+        // is self-describing, otherwise it's assumed to be unit
+        if P::SELF_DESCRIBING {
+            serde::de::IgnoredAny::deserialize(self.deserializer)?;
+        }
         Ok(())
     }
     fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
     where
         T: serde::de::DeserializeSeed<'de>,
     {
-        seed.deserialize(self)
+        seed.deserialize(self.deserializer)
     }
 
     fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
-        serde::Deserializer::deserialize_tuple(self, len, visitor)
+        serde::Deserializer::deserialize_tuple(self.deserializer, len, visitor)
     }
 
     fn struct_variant<V>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
-        serde::Deserializer::deserialize_tuple(self, fields.len(), visitor)
+        serde::Deserializer::deserialize_tuple(self.deserializer, fields.len(), visitor)
     }
 }
@@ -1,9 +1,11 @@
-use crate::params::{AscendingOrder, NativeBinary, PortableBinary};
+use crate::params::{
+    AscendingOrder, FiniteFloats, NativeBinary, NativeBinaryRaw, PortableBinary, SelfDescribing,
+};
 use crate::primitives::SerializableValue;
 use crate::{
     buf::TailWriteBytes,
     params::{LengthEncoder, SerializerParams},
-    Error, FormatVersion, Result,
+    type_tag, Error, FormatVersion, Result,
 };
 use serde::{ser, Serialize};
 
@@ -24,9 +26,30 @@ use serde::{ser, Serialize};
 /// serialized data. To know required buffer size in advance, please use `calc_size` with the same
 /// `SerializerParams`. Size calculation is cheap, for fixed-size structures it folds into
 /// compile-time constant.
+///
+/// The only exception is sorting of map entries, which is opt-in with
+/// [`SerializerParams::SORT_MAP_ENTRIES`].
+///
+/// Structs with `#[serde(flatten)]` fields are not supported, since _serde_ serializes them
+/// as maps of unknown length; [`Error::FlattenNotSupported`] is returned for them.
+#[derive(Clone)]
 pub struct Serializer<W, P> {
     writer: W,
     params: P,
+    // number of nested `Some` values pending, with `SerializerParams::PACKED_OPTIONS`
+    option_chain: u8,
+}
+
+impl<W, P> core::fmt::Debug for Serializer<W, P>
+where
+    W: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Serializer")
+            .field("params", &core::any::type_name::<P>())
+            .field("writer", &self.writer)
+            .finish_non_exhaustive()
+    }
 }
 
 impl<W, P> Serializer<W, P>
@@ -35,25 +58,105 @@ where
     P: SerializerParams,
 {
     pub fn new(writer: W, params: P) -> Self {
-        Self { writer, params }
+        Self {
+            writer,
+            params,
+            option_chain: 0,
+        }
     }
     pub fn into_writer(self) -> W {
         self.writer
     }
+    /// Notify writer that the top-level value is completely serialized
+    ///
+    /// Call it after serializing each top-level value, to let writer commit written data,
+    /// see [`WriteBytes::flush()`](crate::buf::WriteBytes::flush).
+    /// ```
+    /// # use ordcode::new_ser_asc;
+    /// # use serde::Serialize;
+    /// let mut buf = Vec::new();
+    /// let mut ser = new_ser_asc(&mut buf);
+    /// (1_u8, 2_u16).serialize(&mut ser).unwrap();
+    /// ser.finish().unwrap();
+    /// ```
+    pub fn finish(&mut self) -> Result {
+        self.writer.flush()
+    }
 
     #[inline]
     fn write_len(&mut self, v: usize) -> Result {
         P::SeqLenEncoder::write(&mut self.writer, v)
     }
+    // check length of string or byte sequence against `SerializerParams::MAX_STR_LEN`
+    fn check_str_len(v: usize) -> Result {
+        if v > P::MAX_STR_LEN {
+            Err(Error::StringTooLong)
+        } else {
+            Ok(())
+        }
+    }
     fn write_discr(&mut self, v: u32) -> Result {
         P::DiscriminantEncoder::write(&mut self.writer, v)
     }
+    // write type tag for self-describing serialization
+    #[inline]
+    fn write_tag(&mut self, tag: u8) -> Result {
+        if P::SELF_DESCRIBING {
+            self.writer.write(&[tag])
+        } else {
+            self.end_option_chain()
+        }
+    }
+    // packed options: write discriminant of pending nested `Some` values before the inner value
+    #[inline]
+    fn end_option_chain(&mut self) -> Result {
+        if packed_options::<P>() && self.option_chain > 0 {
+            let n = core::mem::take(&mut self.option_chain);
+            self.write_option_chain(n)
+        } else {
+            Ok(())
+        }
+    }
+    fn write_option_chain(&mut self, n: u8) -> Result {
+        let v = if P::OPTION_NULLS_LAST { !n } else { n };
+        v.to_writer(&mut self.writer, self.params)
+    }
+    // write type tag and length for tuples and structs, which are sequences in
+    // self-describing serialization
+    #[inline]
+    fn write_tuple_header(&mut self, len: usize) -> Result {
+        if P::SELF_DESCRIBING {
+            self.write_tag(type_tag::SEQ)?;
+            self.write_len(len)
+        } else {
+            self.end_option_chain()
+        }
+    }
+    #[inline]
+    fn write_variant_header(&mut self, variant_index: u32) -> Result {
+        self.write_tag(type_tag::ENUM)?;
+        self.write_discr(variant_index)
+    }
+}
+
+// nested options are packed into single discriminant, see `SerializerParams::PACKED_OPTIONS`
+#[inline]
+pub(crate) fn packed_options<P: SerializerParams>() -> bool {
+    P::PACKED_OPTIONS && !P::SELF_DESCRIBING
 }
 
 impl<W> FormatVersion<AscendingOrder> for Serializer<W, AscendingOrder> {
     const VERSION: u32 = 1;
 }
 
+impl<W> FormatVersion<FiniteFloats> for Serializer<W, FiniteFloats> {
+    const VERSION: u32 = 1;
+}
+
+impl<W> FormatVersion<SelfDescribing> for Serializer<W, SelfDescribing> {
+    const VERSION: u32 = 1;
+}
+
 impl<W> FormatVersion<PortableBinary> for Serializer<W, PortableBinary> {
     const VERSION: u32 = 1;
 }
@@ -62,9 +165,14 @@ impl<W> FormatVersion<NativeBinary> for Serializer<W, NativeBinary> {
     const VERSION: u32 = 1;
 }
 
+impl<W> FormatVersion<NativeBinaryRaw> for Serializer<W, NativeBinaryRaw> {
+    const VERSION: u32 = 1;
+}
+
 macro_rules! serialize_fn {
-    ($fn:ident, $t:ty) => {
+    ($fn:ident, $t:ty, $tag:expr) => {
         fn $fn(self, v: $t) -> Result {
+            self.write_tag($tag)?;
             v.to_writer(&mut self.writer, self.params)
         }
     };
@@ -82,46 +190,74 @@ where
     type SerializeTuple = SerializeCompound<'a, W, P>;
     type SerializeTupleStruct = SerializeCompound<'a, W, P>;
     type SerializeTupleVariant = SerializeCompound<'a, W, P>;
-    type SerializeMap = SerializeCompoundSeq<'a, W, P>;
+    type SerializeMap = SerializeCompoundMap<'a, W, P>;
     type SerializeStruct = SerializeCompound<'a, W, P>;
     type SerializeStructVariant = SerializeCompound<'a, W, P>;
 
-    serialize_fn!(serialize_bool, bool);
-    serialize_fn!(serialize_u8, u8);
-    serialize_fn!(serialize_u16, u16);
-    serialize_fn!(serialize_u32, u32);
-    serialize_fn!(serialize_u64, u64);
-    serialize_fn!(serialize_i8, i8);
-    serialize_fn!(serialize_i16, i16);
-    serialize_fn!(serialize_i32, i32);
-    serialize_fn!(serialize_i64, i64);
-    serialize_fn!(serialize_f32, f32);
-    serialize_fn!(serialize_f64, f64);
+    serialize_fn!(serialize_bool, bool, type_tag::BOOL);
+    serialize_fn!(serialize_u8, u8, type_tag::U8);
+    serialize_fn!(serialize_u16, u16, type_tag::U16);
+    serialize_fn!(serialize_u32, u32, type_tag::U32);
+    serialize_fn!(serialize_u64, u64, type_tag::U64);
+    serialize_fn!(serialize_i8, i8, type_tag::I8);
+    serialize_fn!(serialize_i16, i16, type_tag::I16);
+    serialize_fn!(serialize_i32, i32, type_tag::I32);
+    serialize_fn!(serialize_i64, i64, type_tag::I64);
+    serialize_fn!(serialize_f32, f32, type_tag::F32);
+    serialize_fn!(serialize_f64, f64, type_tag::F64);
     serde_if_integer128! {
-        serialize_fn!(serialize_u128,  u128);
-        serialize_fn!(serialize_i128,  i128);
+        serialize_fn!(serialize_u128, u128, type_tag::U128);
+        serialize_fn!(serialize_i128, i128, type_tag::I128);
     }
-    serialize_fn!(serialize_char, char);
+    serialize_fn!(serialize_char, char, type_tag::CHAR);
 
     fn serialize_str(self, v: &str) -> Result {
-        self.serialize_bytes(v.as_ref())
+        Serializer::<W, P>::check_str_len(v.len())?;
+        self.write_tag(type_tag::STR)?;
+        if P::ESCAPED_BYTES {
+            return crate::bytes_esc::serialize_escaped(
+                &mut self.writer,
+                v.as_bytes(),
+                self.params,
+            );
+        }
+        self.write_len(v.len())?;
+        self.writer.write(v.as_bytes())
     }
     fn serialize_bytes(self, v: &[u8]) -> Result {
+        Serializer::<W, P>::check_str_len(v.len())?;
+        self.write_tag(type_tag::BYTES)?;
+        if P::ESCAPED_BYTES {
+            return crate::bytes_esc::serialize_escaped(&mut self.writer, v, self.params);
+        }
         self.write_len(v.len())?;
         self.writer.write(&v)
     }
     fn serialize_none(self) -> Result {
-        self.serialize_u8(0)
+        if packed_options::<P>() {
+            let n = core::mem::take(&mut self.option_chain);
+            return self.write_option_chain(n);
+        }
+        self.write_tag(type_tag::OPTION)?;
+        u8::from(P::OPTION_NULLS_LAST).to_writer(&mut self.writer, self.params)
     }
     fn serialize_some<T>(self, value: &T) -> Result
     where
         T: ?Sized + Serialize,
     {
-        self.serialize_u8(1)?;
+        if packed_options::<P>() {
+            self.option_chain = self
+                .option_chain
+                .checked_add(1)
+                .ok_or(Error::RecursionLimitExceeded)?;
+            return value.serialize(self);
+        }
+        self.write_tag(type_tag::OPTION)?;
+        u8::from(!P::OPTION_NULLS_LAST).to_writer(&mut self.writer, self.params)?;
         value.serialize(self)
     }
     fn serialize_unit(self) -> Result {
-        Ok(())
+        self.write_tag(type_tag::UNIT)
     }
 
     fn serialize_unit_struct(self, _name: &'static str) -> Result {
@@ -133,7 +269,8 @@ where
         variant_index: u32,
         _variant: &'static str,
     ) -> Result {
-        self.write_discr(variant_index)
+        self.write_variant_header(variant_index)?;
+        self.write_tag(type_tag::UNIT)
     }
     fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result
     where
@@ -151,19 +288,21 @@ where
     where
         T: serde::ser::Serialize,
     {
-        self.write_discr(variant_index)?;
+        self.write_variant_header(variant_index)?;
         value.serialize(self)
     }
 
-    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
+        self.write_tuple_header(len)?;
         Ok(SerializeCompound::new(self))
     }
 
     fn serialize_tuple_struct(
         self,
         _name: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<Self::SerializeTupleStruct> {
+        self.write_tuple_header(len)?;
         Ok(SerializeCompound::new(self))
     }
 
@@ -172,12 +311,14 @@ where
         _name: &'static str,
         variant_index: u32,
         _variant: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
-        self.write_discr(variant_index)?;
+        self.write_variant_header(variant_index)?;
+        self.write_tuple_header(len)?;
         Ok(SerializeCompound::new(self))
     }
-    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+        self.write_tuple_header(len)?;
         Ok(SerializeCompound::new(self))
     }
     fn serialize_struct_variant(
@@ -185,19 +326,27 @@ where
         _name: &'static str,
         variant_index: u32,
         _variant: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<Self::SerializeStructVariant> {
-        self.write_discr(variant_index)?;
+        self.write_variant_header(variant_index)?;
+        self.write_tuple_header(len)?;
         Ok(SerializeCompound::new(self))
     }
     // map and seq are variable-length sequences, use double encoding
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
         let len = len.ok_or(Error::SerializeSequenceMustHaveLength)?;
+        self.write_tag(type_tag::SEQ)?;
         SerializeCompoundSeq::new(len, self)
     }
     fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
-        let len = len.ok_or(Error::SerializeSequenceMustHaveLength)?;
-        SerializeCompoundSeq::new(len, self)
+        // serde derive serializes structs with flattened fields as maps of unknown length
+        let len = len.ok_or(Error::FlattenNotSupported)?;
+        #[cfg(not(feature = "std"))]
+        if P::SORT_MAP_ENTRIES {
+            return Err(Error::CannotSortMapInNoStdContext);
+        }
+        self.write_tag(type_tag::MAP)?;
+        SerializeCompoundMap::new(len, self)
     }
     #[cfg(not(feature = "std"))]
     fn collect_str<T: ?Sized>(self, _value: &T) -> Result<Self::Ok, Self::Error>
@@ -312,7 +461,104 @@ where
     }
 }
 
-impl<'a, W, P> serde::ser::SerializeMap for SerializeCompoundSeq<'a, W, P>
+pub struct SerializeCompoundMap<'a, W, P: SerializerParams> {
+    ser: &'a mut Serializer<W, P>,
+    // buffered entries, with `SerializerParams::SORT_MAP_ENTRIES`
+    #[cfg(feature = "std")]
+    entries: Vec<MapEntry>,
+}
+
+// Head and tail sections of serialized map entry key and value
+#[cfg(feature = "std")]
+#[derive(Default)]
+struct MapEntry {
+    key: (Vec<u8>, Vec<u8>),
+    value: (Vec<u8>, Vec<u8>),
+}
+
+impl<'a, W, P> SerializeCompoundMap<'a, W, P>
+where
+    W: TailWriteBytes,
+    P: SerializerParams,
+{
+    fn new(len: usize, ser: &'a mut Serializer<W, P>) -> Result<Self> {
+        ser.write_len(len)?;
+        Ok(Self {
+            ser,
+            #[cfg(feature = "std")]
+            entries: if P::SORT_MAP_ENTRIES {
+                Vec::with_capacity(len)
+            } else {
+                Vec::new()
+            },
+        })
+    }
+    #[cfg(feature = "std")]
+    fn serialize_sections<T>(&self, value: &T) -> Result<(Vec<u8>, Vec<u8>)>
+    where
+        T: ?Sized + serde::ser::Serialize,
+    {
+        let mut writer = crate::buf::VecWriter::new();
+        value.serialize(&mut Serializer::new(&mut writer, self.ser.params))?;
+        Ok(writer.into_sections())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, W, P> serde::ser::SerializeMap for SerializeCompoundMap<'a, W, P>
+where
+    W: TailWriteBytes,
+    P: SerializerParams,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result
+    where
+        T: ?Sized + serde::ser::Serialize,
+    {
+        if !P::SORT_MAP_ENTRIES {
+            return key.serialize(&mut *self.ser);
+        }
+        let key = self.serialize_sections(key)?;
+        self.entries.push(MapEntry {
+            key,
+            ..MapEntry::default()
+        });
+        Ok(())
+    }
+    fn serialize_value<T>(&mut self, value: &T) -> Result
+    where
+        T: ?Sized + serde::ser::Serialize,
+    {
+        if !P::SORT_MAP_ENTRIES {
+            return value.serialize(&mut *self.ser);
+        }
+        let value = self.serialize_sections(value)?;
+        if let Some(entry) = self.entries.last_mut() {
+            entry.value = value;
+        }
+        Ok(())
+    }
+    fn end(mut self) -> Result {
+        // serialized key is head section followed by tail section
+        self.entries.sort_by(|a, b| {
+            let (a_head, a_tail) = &a.key;
+            let (b_head, b_tail) = &b.key;
+            a_head.iter().chain(a_tail).cmp(b_head.iter().chain(b_tail))
+        });
+        let writer = &mut self.ser.writer;
+        for entry in &self.entries {
+            for (head, tail) in &[&entry.key, &entry.value] {
+                writer.write_pair(head, tail)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<'a, W, P> serde::ser::SerializeMap for SerializeCompoundMap<'a, W, P>
 where
     W: TailWriteBytes,
     P: SerializerParams,
@@ -3,9 +3,11 @@
 #![allow(clippy::module_name_repetitions)]
 
 use crate::{
-    buf::{TailReadBytes, TailWriteBytes},
-    varint, Result,
+    buf::{ReadFromTail, TailReadBytes, TailWriteBytes, WriteToTail},
+    primitives::SerializableValue,
+    varint, Error, Result,
 };
+use core::convert::TryFrom;
 
 /// lexicographic ordering for serialization
 ///
@@ -15,7 +17,8 @@ use crate::{
 pub enum Order {
     Ascending,
     Descending,
-    /// For use by other crates. For the purposes of `ordcode`, same as [`Ascending`](Order::Ascending).
+    /// For use by other crates. For the purposes of `ordcode`, same as [`Ascending`](Order::Ascending),
+    /// except that signed integers may be written as is, see [`EncodingParams::RAW_SIGNED_INTS`].
     Unordered,
 }
 
@@ -39,6 +42,27 @@ pub trait EncodingParams: Copy {
     /// Endianness for encoding integer and float values; for encodings which preserve
     /// lexicographic ordering, should be [`Endianness::Big`]
     const ENDIANNESS: Endianness;
+
+    /// Canonicalize floating point values before encoding, for total ordering of float keys
+    ///
+    /// If set, all NaN bit patterns are collapsed into a single canonical quiet NaN, and `-0.0`
+    /// is collapsed into `+0.0`, so logically equal values always have equal encodings.
+    /// Deserialization then yields canonical values. Off by default.
+    const CANONICAL_NAN: bool = false;
+
+    /// Reject NaN and infinite floating point values
+    ///
+    /// If set, encoding NaN, `+inf` or `-inf` returns [`Error::NonFiniteFloat`], e.g. for keys
+    /// which come from JSON numbers. Encoding of finite values is not affected. Off by default,
+    /// see [`FiniteFloats`] preset.
+    const FINITE_FLOATS: bool = false;
+
+    /// Write signed integers as is, with [`Order::Unordered`]
+    ///
+    /// If set, signed integers are written in two's complement representation with specified
+    /// [`Endianness`], without min-value complement which is required for ordering. Ignored for
+    /// ordered encodings. Off by default, set for [`NativeBinaryRaw`] preset.
+    const RAW_SIGNED_INTS: bool = false;
 }
 
 /// Parameters for implementations of `serde` serializer and deserializer
@@ -47,7 +71,99 @@ pub trait SerializerParams: EncodingParams {
     type SeqLenEncoder: LengthEncoder<Value = usize>;
 
     /// Encoder for discriminant values
+    ///
+    /// Varint by default, [`U8DiscrEncoder`], [`U16DiscrEncoder`] and [`U32DiscrEncoder`] give
+    /// fixed-width discriminants.
     type DiscriminantEncoder: LengthEncoder<Value = u32>;
+
+    /// Prepend a one-byte type tag to each serialized value
+    ///
+    /// Makes serialized data self-describing, so it can be deserialized with
+    /// `deserialize_any()`, e.g. into a generic value type. Tuples and structs are encoded as
+    /// sequences with explicit length. Type tags do not affect lexicographic ordering of values
+    /// of the same type. Off by default, see [`SelfDescribing`] preset.
+    const SELF_DESCRIBING: bool = false;
+
+    /// Order `None` after all `Some` values when serializing `Option<T>`
+    ///
+    /// By default, `None` is encoded with discriminant byte 0 and `Some` with 1 (nulls first).
+    /// If set, discriminant bytes are swapped (nulls last), as in SQL `NULLS LAST` indexes.
+    const OPTION_NULLS_LAST: bool = false;
+
+    /// Encode directly nested `Option` values with a single discriminant byte
+    ///
+    /// Discriminant byte is the number of nested `Some` values (e.g. 0 for `None`, 2 for
+    /// `Some(Some(None))` and 3 for `Some(Some(Some(x)))` of `Option<Option<Option<T>>>`),
+    /// bitwise inverted with [`OPTION_NULLS_LAST`](SerializerParams::OPTION_NULLS_LAST),
+    /// which preserves ordering of nested options. Single `Option` is encoded same as without
+    /// packing, unless nulls are last. Nesting deeper than 255 returns
+    /// [`Error::RecursionLimitExceeded`]. Ignored for self-describing serialization.
+    /// Off by default.
+    const PACKED_OPTIONS: bool = false;
+
+    /// Maximum number of elements in deserialized sequence or map
+    ///
+    /// Deserializing sequence or map with larger length returns [`Error::SequenceTooLong`],
+    /// before any elements are deserialized. Set it to bound memory usage when deserializing
+    /// untrusted input. Unlimited by default.
+    const MAX_SEQ_LEN: u64 = u64::MAX;
+
+    /// Maximum length in bytes of serialized string or byte sequence
+    ///
+    /// Serializing longer string or byte sequence returns [`Error::StringTooLong`] before anything
+    /// is written, e.g. to enforce key size limits of the storage. Unlimited by default.
+    const MAX_STR_LEN: usize = usize::MAX;
+
+    /// Maximum nesting depth of deserialized values
+    ///
+    /// Each nested sequence, map, tuple, struct, enum variant, `Some` value or newtype struct
+    /// counts as one level. Deserializing deeper nested data returns
+    /// [`Error::RecursionLimitExceeded`], instead of overflowing the stack on hostile input.
+    /// Defaults to 128.
+    const MAX_DEPTH: usize = 128;
+
+    /// Reject non-canonical varint encodings of sequence lengths and discriminants
+    ///
+    /// Varint with more bytes than needed for its value decodes to the same value, so different
+    /// serialized keys may deserialize to equal values. If set, deserializing such varint
+    /// returns [`Error::InvalidVarintEncoding`]. Serializer always produces canonical
+    /// encodings. Debug builds reject non-canonical encodings regardless of this setting.
+    /// Off by default.
+    const STRICT_VARINT: bool = false;
+
+    /// Sort map entries by serialized keys
+    ///
+    /// Maps with arbitrary iteration order (e.g. `HashMap`) get deterministic encoding, same as
+    /// `BTreeMap` with the same entries. Entries are buffered in heap-allocated vectors for
+    /// sorting; without `std` feature, serializing a map returns
+    /// [`Error::CannotSortMapInNoStdContext`](crate::Error). Off by default, entries are
+    /// serialized in iteration order without allocations.
+    const SORT_MAP_ENTRIES: bool = false;
+
+    /// Skip ignored trailing values (`serde::de::IgnoredAny`) as length-prefixed byte sequences
+    ///
+    /// Data which is not self-describing has no type information, so ignored values can't be
+    /// skipped in general, and [`Error::DeserializeIgnoredAny`] is returned by default. If set,
+    /// ignored value is assumed to be a string or byte sequence, which is skipped together with
+    /// its length. This allows to drop trailing string fields when reading data written by newer
+    /// struct version. Skipped sequence must span the rest of input, otherwise
+    /// [`Error::DeserializeIgnoredAny`] is returned: ignored value is not the last one, or it is
+    /// not a string or byte sequence, e.g. a scalar. Note that a trailing scalar may still be
+    /// mistaken for a byte sequence if its last bytes happen to encode its remaining length.
+    /// Ignored for self-describing serialization, where values are skipped according to type
+    /// tags. Off by default.
+    const SKIP_IGNORED_AS_BYTES: bool = false;
+
+    /// Write strings and byte sequences escaped and terminated, instead of writing their length
+    ///
+    /// Sequences are written with [`bytes_esc::serialize_escaped()`](crate::bytes_esc::serialize_escaped),
+    /// so a sequence which is a prefix of another one is ordered before it regardless of
+    /// following data, at the cost of one extra byte per zero byte and two terminator bytes.
+    /// Lengths of other sequences and maps are still written to the tail. Deserialized values
+    /// are unescaped into heap-allocated buffers, so they can't be borrowed; without `std`
+    /// feature, deserializing a string or byte sequence returns
+    /// [`Error::CannotUnescapeInNoStdContext`](crate::Error). Off by default.
+    const ESCAPED_BYTES: bool = false;
 }
 
 /// Encoder for array lengths, enum discriminants etc.
@@ -55,18 +171,133 @@ pub trait LengthEncoder {
     /// Value type, may be `u32`, `u64` or usize
     type Value;
 
+    /// Encoded values are written to the tail of the double-ended buffer
+    const TAIL: bool = false;
+
     /// Calculate serialized size for value
     fn calc_size(value: Self::Value) -> usize;
     fn read(reader: impl TailReadBytes) -> Result<Self::Value>;
+    /// Same as `read()`, but rejects non-canonical encodings, see
+    /// [`SerializerParams::STRICT_VARINT`]
+    ///
+    /// Fixed-width encodings have single encoding for each value, and don't need to override it.
+    #[inline]
+    fn read_strict(reader: impl TailReadBytes) -> Result<Self::Value> {
+        Self::read(reader)
+    }
     fn write(writer: impl TailWriteBytes, value: Self::Value) -> Result;
 }
 
+/// Fixed-width encoding for sequence lengths, which writes 4-byte big-endian value
+/// to the end of the double-ended buffer
+///
+/// Takes more space than [`varint::VarIntTailLenEncoder`], but serialized size of a value
+/// depends only on lengths of its sequences, not on length values, which gives predictable
+/// record layout (e.g. all strings of the same length are encoded with the same size).
+/// Serializing sequence longer than `u32::MAX` returns [`Error::IntegerOverflow`].
+pub struct FixedTailLenEncoder;
+
+impl LengthEncoder for FixedTailLenEncoder {
+    type Value = usize;
+    const TAIL: bool = true;
+
+    #[inline]
+    fn calc_size(_value: Self::Value) -> usize {
+        core::mem::size_of::<u32>()
+    }
+    #[inline]
+    fn read(mut reader: impl TailReadBytes) -> Result<usize> {
+        let v = u32::from_reader(ReadFromTail(&mut reader), AscendingOrder)?;
+        usize::try_from(v).map_err(|_| Error::IntegerOverflow)
+    }
+    #[inline]
+    fn write(mut writer: impl TailWriteBytes, value: usize) -> Result {
+        let v = u32::try_from(value).map_err(|_| Error::IntegerOverflow)?;
+        v.to_writer(WriteToTail(&mut writer), AscendingOrder)
+    }
+}
+
+/// Single-byte encoding for sequence lengths, which writes raw length byte
+/// to the end of the double-ended buffer
+///
+/// For schemas where all sequences are known to be short: avoids varint decoding,
+/// and takes the same space as [`varint::VarIntTailLenEncoder`] for lengths up to 127.
+/// Serializing sequence longer than 255 returns [`Error::IntegerOverflow`].
+pub struct ByteTailLenEncoder;
+
+impl LengthEncoder for ByteTailLenEncoder {
+    type Value = usize;
+    const TAIL: bool = true;
+
+    #[inline]
+    fn calc_size(_value: Self::Value) -> usize {
+        1
+    }
+    #[inline]
+    fn read(mut reader: impl TailReadBytes) -> Result<usize> {
+        reader.read_tail(1, |buf| Ok(usize::from(buf[0])))
+    }
+    #[inline]
+    fn write(mut writer: impl TailWriteBytes, value: usize) -> Result {
+        let v = u8::try_from(value).map_err(|_| Error::IntegerOverflow)?;
+        writer.write_tail(&[v])
+    }
+}
+
+macro_rules! fixed_discr_encoder {
+    ($name:ident, $ty:ty, $doc:literal) => {
+        #[doc = $doc]
+        ///
+        /// Discriminant is written to the head of the buffer as big-endian value, so that it is
+        /// found at a fixed offset and variants keep their declaration order. Serializing variant
+        /// index which does not fit returns [`Error::IntegerOverflow`].
+        pub struct $name;
+
+        impl LengthEncoder for $name {
+            type Value = u32;
+
+            #[inline]
+            fn calc_size(_value: Self::Value) -> usize {
+                core::mem::size_of::<$ty>()
+            }
+            #[inline]
+            fn read(reader: impl TailReadBytes) -> Result<u32> {
+                <$ty>::from_reader(reader, AscendingOrder).map(u32::from)
+            }
+            #[inline]
+            fn write(writer: impl TailWriteBytes, value: u32) -> Result {
+                let v = <$ty>::try_from(value).map_err(|_| Error::IntegerOverflow)?;
+                v.to_writer(writer, AscendingOrder)
+            }
+        }
+    };
+}
+
+fixed_discr_encoder!(
+    U8DiscrEncoder,
+    u8,
+    "Fixed-width encoding for enum discriminants as a single byte"
+);
+fixed_discr_encoder!(
+    U16DiscrEncoder,
+    u16,
+    "Fixed-width encoding for enum discriminants as 2-byte value"
+);
+fixed_discr_encoder!(
+    U32DiscrEncoder,
+    u32,
+    "Fixed-width encoding for enum discriminants as 4-byte value"
+);
+
 impl<T> EncodingParams for &T
 where
     T: EncodingParams,
 {
     const ORDER: Order = T::ORDER;
     const ENDIANNESS: Endianness = T::ENDIANNESS;
+    const CANONICAL_NAN: bool = T::CANONICAL_NAN;
+    const FINITE_FLOATS: bool = T::FINITE_FLOATS;
+    const RAW_SIGNED_INTS: bool = T::RAW_SIGNED_INTS;
 }
 
 impl<T> SerializerParams for &T
@@ -75,6 +306,16 @@ where
 {
     type SeqLenEncoder = T::SeqLenEncoder;
     type DiscriminantEncoder = T::DiscriminantEncoder;
+    const SELF_DESCRIBING: bool = T::SELF_DESCRIBING;
+    const OPTION_NULLS_LAST: bool = T::OPTION_NULLS_LAST;
+    const PACKED_OPTIONS: bool = T::PACKED_OPTIONS;
+    const MAX_SEQ_LEN: u64 = T::MAX_SEQ_LEN;
+    const MAX_STR_LEN: usize = T::MAX_STR_LEN;
+    const MAX_DEPTH: usize = T::MAX_DEPTH;
+    const STRICT_VARINT: bool = T::STRICT_VARINT;
+    const SORT_MAP_ENTRIES: bool = T::SORT_MAP_ENTRIES;
+    const SKIP_IGNORED_AS_BYTES: bool = T::SKIP_IGNORED_AS_BYTES;
+    const ESCAPED_BYTES: bool = T::ESCAPED_BYTES;
 }
 
 /// Serializer parameters for lexicographic order-preserving serialization in ascending order
@@ -91,11 +332,32 @@ impl SerializerParams for AscendingOrder {
     type DiscriminantEncoder = varint::VarIntDiscrEncoder;
 }
 
+/// Serializer parameters for lexicographic order-preserving serialization in ascending order,
+/// which reject non-finite floats
+///
+/// Same as [`AscendingOrder`], but serializing NaN or infinite float returns
+/// [`Error::NonFiniteFloat`]. Please see [`EncodingParams::FINITE_FLOATS`].
+#[derive(Copy, Clone, Default)]
+pub struct FiniteFloats;
+
+impl EncodingParams for FiniteFloats {
+    const ORDER: Order = Order::Ascending;
+    const ENDIANNESS: Endianness = Endianness::Big;
+    const FINITE_FLOATS: bool = true;
+}
+
+impl SerializerParams for FiniteFloats {
+    type SeqLenEncoder = varint::VarIntTailLenEncoder;
+    type DiscriminantEncoder = varint::VarIntDiscrEncoder;
+}
+
 /// Encoding parameters for lexicographic order-preserving serialization in descending order
 ///
 /// Note: deliberately implements only [`EncodingParams`] trait, not [`SerializerParams`], so it can
 /// be used with serialization primitives, but not with [`Serializer`](crate::Serializer)
-/// or [`Deserializer`](crate::Deserializer).
+/// or [`Deserializer`](crate::Deserializer). For descending order serialization without
+/// inverting the whole buffer, use [`new_ser_desc()`](crate::new_ser_desc) and
+/// [`new_de_desc()`](crate::new_de_desc).
 #[derive(Copy, Clone, Default)]
 pub struct DescendingOrder;
 
@@ -104,6 +366,26 @@ impl EncodingParams for DescendingOrder {
     const ENDIANNESS: Endianness = Endianness::Big;
 }
 
+/// Serializer parameters for self-describing, lexicographic order-preserving serialization
+/// in ascending order
+///
+/// Same as [`AscendingOrder`], but each value is prepended with a type tag, so serialized data
+/// can be deserialized without knowing its type, with `deserialize_any()`.
+/// Please see [`SerializerParams::SELF_DESCRIBING`].
+#[derive(Copy, Clone, Default)]
+pub struct SelfDescribing;
+
+impl EncodingParams for SelfDescribing {
+    const ORDER: Order = Order::Ascending;
+    const ENDIANNESS: Endianness = Endianness::Big;
+}
+
+impl SerializerParams for SelfDescribing {
+    type SeqLenEncoder = varint::VarIntTailLenEncoder;
+    type DiscriminantEncoder = varint::VarIntDiscrEncoder;
+    const SELF_DESCRIBING: bool = true;
+}
+
 /// Serializer parameters for portable binary format, which does not need double-ended buffer
 ///
 /// However, it still requires implementation of [`TailReadBytes`](crate::buf::TailReadBytes),
@@ -142,3 +424,129 @@ impl SerializerParams for NativeBinary {
     type SeqLenEncoder = varint::VarIntLenEncoder;
     type DiscriminantEncoder = varint::VarIntDiscrEncoder;
 }
+
+/// Serializer parameters for platform-specific binary format, where integers and floats are
+/// written as is, without any transformations
+///
+/// Same as [`NativeBinary`], except that signed integers are not min-value complemented
+/// (see [`EncodingParams::RAW_SIGNED_INTS`]), so data serialized with these parameters is not
+/// compatible with data serialized with [`NativeBinary`].
+#[derive(Copy, Clone, Default)]
+pub struct NativeBinaryRaw;
+
+impl EncodingParams for NativeBinaryRaw {
+    const ORDER: Order = Order::Unordered;
+    const ENDIANNESS: Endianness = Endianness::Native;
+    const RAW_SIGNED_INTS: bool = true;
+}
+
+impl SerializerParams for NativeBinaryRaw {
+    type SeqLenEncoder = varint::VarIntLenEncoder;
+    type DiscriminantEncoder = varint::VarIntDiscrEncoder;
+}
+
+/// Wrapper which reverses lexicographic ordering of a single value within a composite key
+///
+/// Wrapped value is serialized on its own, with strings and byte sequences escaped and terminated
+/// (see [`SerializerParams::ESCAPED_BYTES`]) instead of having their lengths in the tail, and
+/// the result is bitwise inverted and embedded as a byte sequence. Other fields of the composite
+/// key are not affected. Escaped encoding is prefix-free, so ordering of the field is decided by
+/// the embedded bytes alone, and a string which is a prefix of another one is ordered after it
+/// (e.g. `Reverse("ab")` after `Reverse("abc")`). Lengths of other sequences and maps within
+/// the wrapped value are written to its tail, which is embedded after its data, so they are
+/// ordered as if they were followed by other data.
+///
+/// Requires `std` feature, since wrapped value is serialized into temporary buffer.
+/// ```
+/// # use ordcode::{ params::Reverse, ser_to_vec_ordered, Order };
+/// let k1 = ser_to_vec_ordered(&("abc", Reverse(10u32)), Order::Ascending).unwrap();
+/// let k2 = ser_to_vec_ordered(&("abc", Reverse(20u32)), Order::Ascending).unwrap();
+/// assert!(k1 > k2);
+/// let k1 = ser_to_vec_ordered(&(Reverse("ab"), 1u8), Order::Ascending).unwrap();
+/// let k2 = ser_to_vec_ordered(&(Reverse("ab\u{1}"), 0u8), Order::Ascending).unwrap();
+/// assert!(k1 > k2);
+/// ```
+#[cfg(all(feature = "std", feature = "serde"))]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Reverse<T>(pub T);
+
+// Parameters for value wrapped in `Reverse`: same as `AscendingOrder`, with escaped sequences
+#[cfg(all(feature = "std", feature = "serde"))]
+#[derive(Copy, Clone)]
+struct ReverseInner;
+
+#[cfg(all(feature = "std", feature = "serde"))]
+impl EncodingParams for ReverseInner {
+    const ORDER: Order = Order::Ascending;
+    const ENDIANNESS: Endianness = Endianness::Big;
+}
+
+#[cfg(all(feature = "std", feature = "serde"))]
+impl SerializerParams for ReverseInner {
+    type SeqLenEncoder = varint::VarIntTailLenEncoder;
+    type DiscriminantEncoder = varint::VarIntDiscrEncoder;
+    const ESCAPED_BYTES: bool = true;
+}
+
+#[cfg(all(feature = "std", feature = "serde"))]
+impl<T> serde::Serialize for Reverse<T>
+where
+    T: serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::Error;
+        let mut buf =
+            vec![0_u8; crate::calc_size(&self.0, ReverseInner).map_err(S::Error::custom)?];
+        let mut writer = crate::DeBytesWriter::new(&mut buf);
+        self.0
+            .serialize(&mut crate::Serializer::new(&mut writer, ReverseInner))
+            .map_err(S::Error::custom)?;
+        writer.is_complete().map_err(S::Error::custom)?;
+        crate::primitives::invert_buffer(&mut buf);
+        serializer.serialize_bytes(&buf)
+    }
+}
+
+#[cfg(all(feature = "std", feature = "serde"))]
+impl<'de, T> serde::Deserialize<'de> for Reverse<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct ReverseVisitor<T>(core::marker::PhantomData<T>);
+
+        impl<T> serde::de::Visitor<'_> for ReverseVisitor<T>
+        where
+            T: serde::de::DeserializeOwned,
+        {
+            type Value = Reverse<T>;
+
+            fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.write_str("reverse ordered value")
+            }
+            fn visit_bytes<E>(self, v: &[u8]) -> core::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                let mut buf = v.to_vec();
+                crate::primitives::invert_buffer(&mut buf);
+                let mut reader = crate::DeBytesReader::new(&buf);
+                let value =
+                    T::deserialize(&mut crate::Deserializer::new(&mut reader, ReverseInner))
+                        .map_err(E::custom)?;
+                if reader.remaining() == 0 {
+                    Ok(Reverse(value))
+                } else {
+                    Err(E::custom(crate::Error::TrailingBytes))
+                }
+            }
+        }
+        deserializer.deserialize_bytes(ReverseVisitor(core::marker::PhantomData))
+    }
+}
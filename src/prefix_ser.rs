@@ -0,0 +1,253 @@
+//! Serialization of key prefixes, consisting of leading fields of tuples and structs
+//!
+//! Please see [`ser_prefix()`](crate::ser_prefix).
+use crate::{
+    buf::{TailWriteBytes, WriteBytes},
+    params::AscendingOrder,
+    Error, Result, Serializer,
+};
+use serde::{ser, Serialize};
+
+// Writer which appends head section to the vector and discards tail section
+struct HeadOnly<'a>(&'a mut Vec<u8>);
+
+impl WriteBytes for HeadOnly<'_> {
+    fn write(&mut self, value: &[u8]) -> Result {
+        self.0.extend_from_slice(value);
+        Ok(())
+    }
+}
+
+impl TailWriteBytes for HeadOnly<'_> {
+    fn write_tail(&mut self, _value: &[u8]) -> Result {
+        Ok(())
+    }
+}
+
+/// Serialize head section of the first `fields` fields of a tuple or struct, in ascending order
+pub(crate) fn ser_prefix_asc<T>(value: &T, fields: usize) -> Result<Vec<u8>>
+where
+    T: ?Sized + Serialize,
+{
+    let mut buf = Vec::new();
+    let mut ser = Serializer::new(HeadOnly(&mut buf), AscendingOrder);
+    value.serialize(PrefixSerializer {
+        ser: &mut ser,
+        fields,
+    })?;
+    Ok(buf)
+}
+
+// Serializer which passes the first `fields` fields of top-level tuple or struct
+// to the inner serializer, and skips the rest
+struct PrefixSerializer<'a, W> {
+    ser: &'a mut Serializer<W, AscendingOrder>,
+    fields: usize,
+}
+
+// Wrapper for compound serializer, which skips fields past the prefix
+struct PrefixCompound<C> {
+    inner: C,
+    fields: usize,
+    index: usize,
+}
+
+impl<C> PrefixCompound<C> {
+    fn new(inner: C, fields: usize) -> Self {
+        Self {
+            inner,
+            fields,
+            index: 0,
+        }
+    }
+    // returns true if current field is within prefix, advances to the next field
+    fn next_in_prefix(&mut self) -> bool {
+        self.index += 1;
+        self.index <= self.fields
+    }
+}
+
+macro_rules! not_compound {
+    ($($fn:ident($($arg:ident: $t:ty),*)),* $(,)?) => {
+        $(
+        fn $fn(self, $($arg: $t),*) -> Result {
+            $(let _ = $arg;)*
+            Err(Error::PrefixOfNonCompoundValue)
+        }
+        )*
+    };
+}
+
+impl<'a, W> ser::Serializer for PrefixSerializer<'a, W>
+where
+    W: TailWriteBytes,
+{
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = ser::Impossible<(), Error>;
+    type SerializeTuple =
+        PrefixCompound<<&'a mut Serializer<W, AscendingOrder> as ser::Serializer>::SerializeTuple>;
+    type SerializeTupleStruct = PrefixCompound<
+        <&'a mut Serializer<W, AscendingOrder> as ser::Serializer>::SerializeTupleStruct,
+    >;
+    type SerializeTupleVariant = ser::Impossible<(), Error>;
+    type SerializeMap = ser::Impossible<(), Error>;
+    type SerializeStruct =
+        PrefixCompound<<&'a mut Serializer<W, AscendingOrder> as ser::Serializer>::SerializeStruct>;
+    type SerializeStructVariant = ser::Impossible<(), Error>;
+
+    not_compound!(
+        serialize_bool(v: bool),
+        serialize_i8(v: i8),
+        serialize_i16(v: i16),
+        serialize_i32(v: i32),
+        serialize_i64(v: i64),
+        serialize_u8(v: u8),
+        serialize_u16(v: u16),
+        serialize_u32(v: u32),
+        serialize_u64(v: u64),
+        serialize_f32(v: f32),
+        serialize_f64(v: f64),
+        serialize_char(v: char),
+        serialize_str(v: &str),
+        serialize_bytes(v: &[u8]),
+        serialize_none(),
+        serialize_unit(),
+        serialize_unit_struct(name: &'static str),
+        serialize_unit_variant(name: &'static str, index: u32, variant: &'static str),
+    );
+    fn serialize_some<T>(self, _value: &T) -> Result
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(Error::PrefixOfNonCompoundValue)
+    }
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(Error::PrefixOfNonCompoundValue)
+    }
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(Error::PrefixOfNonCompoundValue)
+    }
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
+        let inner = ser::Serializer::serialize_tuple(self.ser, len)?;
+        Ok(PrefixCompound::new(inner, self.fields))
+    }
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        let inner = ser::Serializer::serialize_tuple_struct(self.ser, name, len)?;
+        Ok(PrefixCompound::new(inner, self.fields))
+    }
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(Error::PrefixOfNonCompoundValue)
+    }
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(Error::PrefixOfNonCompoundValue)
+    }
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+        let inner = ser::Serializer::serialize_struct(self.ser, name, len)?;
+        Ok(PrefixCompound::new(inner, self.fields))
+    }
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(Error::PrefixOfNonCompoundValue)
+    }
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+impl<C> ser::SerializeTuple for PrefixCompound<C>
+where
+    C: ser::SerializeTuple<Ok = (), Error = Error>,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result
+    where
+        T: ?Sized + Serialize,
+    {
+        if self.next_in_prefix() {
+            self.inner.serialize_element(value)
+        } else {
+            Ok(())
+        }
+    }
+    fn end(self) -> Result {
+        self.inner.end()
+    }
+}
+
+impl<C> ser::SerializeTupleStruct for PrefixCompound<C>
+where
+    C: ser::SerializeTupleStruct<Ok = (), Error = Error>,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result
+    where
+        T: ?Sized + Serialize,
+    {
+        if self.next_in_prefix() {
+            self.inner.serialize_field(value)
+        } else {
+            Ok(())
+        }
+    }
+    fn end(self) -> Result {
+        self.inner.end()
+    }
+}
+
+impl<C> ser::SerializeStruct for PrefixCompound<C>
+where
+    C: ser::SerializeStruct<Ok = (), Error = Error>,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result
+    where
+        T: ?Sized + Serialize,
+    {
+        if self.next_in_prefix() {
+            self.inner.serialize_field(key, value)
+        } else {
+            Ok(())
+        }
+    }
+    fn end(self) -> Result {
+        self.inner.end()
+    }
+}
@@ -9,11 +9,61 @@
 //! **Deserializing a value which was serialized for different [`EncodingParams`](crate::params::EncodingParams)
 //! is unchecked and is undefined behaviour!**
 //!
-//! Note that `u128` and `i128` may not be supported on some platforms.
+//! Note that `u128` and `i128` may not be supported on some platforms. On such platforms,
+//! 128-bit values can be handled as pairs of 64-bit words with [`serialize_u128_words()`],
+//! [`serialize_i128_words()`] and corresponding deserialize functions, which produce
+//! exactly the same encoding as native `u128` and `i128`.
+//!
+//! `usize` and `isize` are not [`SerializableValue`], because their size is platform-dependent;
+//! use [`serialize_usize()`], [`serialize_isize()`] and corresponding deserialize functions,
+//! which always encode them as 64-bit integers.
+//!
+//! Fixed-point decimals with a fixed per-column scale are encoded as ordered `i128` mantissas
+//! with [`serialize_decimal()`] and [`deserialize_decimal()`].
+//!
+//! `f32` values can be stored as `f64` with [`serialize_f32_as_f64()`], so that they sort
+//! together with `f64` values.
+//! [`NonNanF64`] wrapper excludes NaN at the type level and implements `Ord`.
+//!
+//! Non-zero integer types (`NonZeroU32` etc.) are encoded same as corresponding integer types;
+//! deserializing zero into non-zero type returns [`Error::UnexpectedZero`].
+//!
+//! [`Duration`] is serialized with [`serialize_duration()`] as seconds (`u64`) followed by
+//! subsecond nanoseconds (`u32`); use [`DurationKey`] wrapper to get this encoding with _serde_.
+//! Timestamps of `u32` seconds and nanoseconds are packed into a single `u64` with
+//! [`serialize_timestamp()`].
+//!
+//! IP addresses (`Ipv4Addr`, `Ipv6Addr`) are encoded as octets in network byte order, regardless
+//! of [`Endianness`] parameter. `IpAddr` is serialized with [`serialize_ip_addr()`] as a
+//! discriminant byte (0 for v4, 1 for v6) followed by address octets, so all v4 addresses
+//! sort before all v6 addresses. Use [`IpAddrKey`], [`Ipv4AddrKey`] and [`Ipv6AddrKey`]
+//! wrappers to get this encoding with _serde_. `SocketAddr` is serialized with
+//! [`serialize_socket_addr()`] as `IpAddr` followed by port in network byte order.
+//!
+//! Up to 8 boolean flags can be packed into a single byte with [`serialize_bitflags()`],
+//! or with [`BitFlags8`] wrapper for _serde_. Nullable booleans with `false < true < null`
+//! ordering are encoded as a single byte with [`TriBool`] wrapper.
+//!
+//! `Cow<str>` fields can be deserialized without copying with [`deserialize_cow()`].
+//!
+//! With `collation` feature, strings can be ordered by locale-aware collation with
+//! `serialize_collated()` and user-provided `Collation`.
+//!
+//! [`to_hex()`], [`from_hex()`] and [`HexKey`] wrapper help to inspect encoded keys when debugging.
+//!
+//! [`MaxEncodedLen`] gives compile-time upper bound of encoded size of primitive types, e.g. for
+//! stack buffers in `no_std` environment.
+//!
+//! [`min_encoded()`] and [`max_encoded()`] write the smallest and the largest possible encodings
+//! for types implementing [`EncodedBounds`], for use as bounds in key range scans.
 //!
 //! ### Encoding details
 //! - unsigned integers are encoded in big-endian layout
 //! - integers are encoded min-value-complemented, big-endian layout
+//! - with [`Order::Unordered`] and [`EncodingParams::RAW_SIGNED_INTS`], signed integers are
+//!   written as is, in two's complement representation; with [`Endianness::Native`] (as in
+//!   [`NativeBinaryRaw`](crate::params::NativeBinaryRaw)), all integers and floats are written as is,
+//!   in native byte order
 //!
 //! ### Parameters
 //! Encoding parameters are passed via impl of `EncodingParams` (usually ZST struct).
@@ -23,7 +73,18 @@ use crate::{
     params::{EncodingParams, Endianness, Order},
     Error, Result,
 };
-use core::convert::TryInto;
+use core::convert::{TryFrom, TryInto};
+#[cfg(not(no_i128))]
+use core::num::{NonZeroI128, NonZeroU128};
+use core::num::{
+    NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8,
+};
+use core::ops::Range;
+use core::time::Duration;
+#[cfg(all(feature = "std", feature = "serde"))]
+use std::borrow::Cow;
+#[cfg(feature = "std")]
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 
 /// Serializable value
 ///
@@ -34,9 +95,75 @@ pub trait SerializableValue: Sized {
     fn from_reader<P: EncodingParams>(reader: impl ReadBytes, params: P) -> Result<Self>;
 }
 
+/// Maximum size of serialized value
+///
+/// Implemented for primitive types, which have fixed-size encoding. Allows to declare stack
+/// buffers of sufficient size:
+/// ```
+/// # use ordcode::{ primitives::{ MaxEncodedLen, SerializableValue }, params::AscendingOrder, DeBytesWriter };
+/// let mut buf = [0_u8; u64::MAX_ENCODED_LEN];
+/// u64::MAX.to_writer(DeBytesWriter::new(&mut buf), AscendingOrder).unwrap();
+/// ```
+/// For variable length integers, see [`VarUInt::VARU_MAX_LEN`](crate::varint::VarUInt::VARU_MAX_LEN).
+pub trait MaxEncodedLen: SerializableValue {
+    /// Maximum number of bytes `to_writer()` writes for a value of this type
+    const MAX_ENCODED_LEN: usize;
+}
+
+macro_rules! max_encoded_len {
+    ($($t:ty),*) => {
+        $(
+        impl MaxEncodedLen for $t {
+            const MAX_ENCODED_LEN: usize = core::mem::size_of::<$t>();
+        }
+        )*
+    };
+}
+
+max_encoded_len!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+max_encoded_len!(
+    NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64
+);
+#[cfg(not(no_i128))]
+max_encoded_len!(u128, i128, NonZeroU128, NonZeroI128);
+
+impl MaxEncodedLen for bool {
+    const MAX_ENCODED_LEN: usize = 1;
+}
+
+impl MaxEncodedLen for char {
+    const MAX_ENCODED_LEN: usize = 4;
+}
+
+/// Encoded length of `char` in bytes
+#[must_use]
+pub const fn encoded_len_char() -> usize {
+    char::MAX_ENCODED_LEN
+}
+
+/// Encoded length of `bool` in bytes
+#[must_use]
+pub const fn encoded_len_bool() -> usize {
+    bool::MAX_ENCODED_LEN
+}
+
+/// Encoded length of `f32` in bytes
+#[must_use]
+pub const fn encoded_len_f32() -> usize {
+    f32::MAX_ENCODED_LEN
+}
+
+/// Encoded length of `f64` in bytes
+#[must_use]
+pub const fn encoded_len_f64() -> usize {
+    f64::MAX_ENCODED_LEN
+}
+
 /// Serialization data format version
 pub const VERSION: u8 = 1;
 
+// `ORDER` is an associated const, so for parameter ZSTs such as `DescendingOrder` the match
+// is resolved at monomorphization, and only one of the branches is compiled in
 macro_rules! ord_cond {
     ($param:ident, $desc:expr, $asc:expr) => {
         match <$param>::ORDER {
@@ -92,12 +219,28 @@ macro_rules! serialize_int {
         }
         impl SerializableValue for $it {
             #[inline]
-            fn to_writer<P: EncodingParams>(&self, writer: impl WriteBytes, params: P) -> Result {
-                ((self ^ <$it>::min_value()) as $ut).to_writer(writer, params)
+            fn to_writer<P: EncodingParams>(
+                &self,
+                mut writer: impl WriteBytes,
+                params: P,
+            ) -> Result {
+                if is_raw::<P>() {
+                    writer.write(to_bytes!(P, self))
+                } else {
+                    ((self ^ <$it>::min_value()) as $ut).to_writer(writer, params)
+                }
             }
             #[inline]
-            fn from_reader<P: EncodingParams>(reader: impl ReadBytes, params: P) -> Result<Self> {
-                <$ut>::from_reader(reader, params).map(|u| (u as $it) ^ <$it>::min_value())
+            fn from_reader<P: EncodingParams>(
+                mut reader: impl ReadBytes,
+                params: P,
+            ) -> Result<Self> {
+                if is_raw::<P>() {
+                    const N: usize = core::mem::size_of::<$it>();
+                    reader.read(N, |buf| Ok(from_bytes!(P, $it, buf)))
+                } else {
+                    <$ut>::from_reader(reader, params).map(|u| (u as $it) ^ <$it>::min_value())
+                }
             }
         }
     };
@@ -111,6 +254,198 @@ serialize_int!(u64, i64);
 #[cfg(not(no_i128))]
 serialize_int!(u128, i128);
 
+/// Serialize 128-bit unsigned integer given as high and low 64-bit words
+///
+/// Encoding is identical to `u128` encoding for all [`EncodingParams`], and preserves ordering.
+/// Useful on platforms without native 128-bit integer support.
+pub fn serialize_u128_words<P: EncodingParams>(
+    hi: u64,
+    lo: u64,
+    mut writer: impl WriteBytes,
+    params: P,
+) -> Result {
+    let (first, second) = if is_little_endian::<P>() {
+        (lo, hi)
+    } else {
+        (hi, lo)
+    };
+    first.to_writer(&mut writer, params)?;
+    second.to_writer(writer, params)
+}
+
+/// Deserialize 128-bit unsigned integer as `(high, low)` 64-bit words
+///
+/// Reads data serialized with [`serialize_u128_words()`] or as `u128`.
+pub fn deserialize_u128_words<P: EncodingParams>(
+    mut reader: impl ReadBytes,
+    params: P,
+) -> Result<(u64, u64)> {
+    let first = u64::from_reader(&mut reader, params)?;
+    let second = u64::from_reader(reader, params)?;
+    if is_little_endian::<P>() {
+        Ok((second, first))
+    } else {
+        Ok((first, second))
+    }
+}
+
+/// Serialize 128-bit signed integer given as high (signed) and low 64-bit words
+///
+/// Encoding is identical to `i128` encoding for all [`EncodingParams`], and preserves ordering.
+#[allow(clippy::cast_sign_loss)]
+pub fn serialize_i128_words<P: EncodingParams>(
+    hi: i64,
+    lo: u64,
+    writer: impl WriteBytes,
+    params: P,
+) -> Result {
+    let hi = if is_raw::<P>() { hi } else { hi ^ i64::MIN };
+    serialize_u128_words(hi as u64, lo, writer, params)
+}
+
+/// Deserialize 128-bit signed integer as `(high, low)` 64-bit words
+///
+/// Reads data serialized with [`serialize_i128_words()`] or as `i128`.
+#[allow(clippy::cast_possible_wrap)]
+pub fn deserialize_i128_words<P: EncodingParams>(
+    reader: impl ReadBytes,
+    params: P,
+) -> Result<(i64, u64)> {
+    let (hi, lo) = deserialize_u128_words(reader, params)?;
+    if is_raw::<P>() {
+        Ok((hi as i64, lo))
+    } else {
+        Ok(((hi as i64) ^ i64::MIN, lo))
+    }
+}
+
+// Signed integers are written as is, without transformations for ordering: they are not
+// offset by their minimal value
+fn is_raw<P: EncodingParams>() -> bool {
+    P::RAW_SIGNED_INTS && matches!(P::ORDER, Order::Unordered)
+}
+
+fn is_little_endian<P: EncodingParams>() -> bool {
+    match P::ENDIANNESS {
+        Endianness::Little => true,
+        Endianness::Big => false,
+        Endianness::Native => cfg!(target_endian = "little"),
+    }
+}
+
+// Non-zero integers are encoded same as underlying integer types, zero is rejected on read
+macro_rules! serialize_nonzero {
+    ($nzt:ty, $t:ty) => {
+        impl SerializableValue for $nzt {
+            #[inline]
+            fn to_writer<P: EncodingParams>(&self, writer: impl WriteBytes, params: P) -> Result {
+                self.get().to_writer(writer, params)
+            }
+            #[inline]
+            fn from_reader<P: EncodingParams>(reader: impl ReadBytes, params: P) -> Result<Self> {
+                <$nzt>::new(<$t>::from_reader(reader, params)?).ok_or(Error::UnexpectedZero)
+            }
+        }
+    };
+}
+
+serialize_nonzero!(NonZeroU8, u8);
+serialize_nonzero!(NonZeroU16, u16);
+serialize_nonzero!(NonZeroU32, u32);
+serialize_nonzero!(NonZeroU64, u64);
+serialize_nonzero!(NonZeroI8, i8);
+serialize_nonzero!(NonZeroI16, i16);
+serialize_nonzero!(NonZeroI32, i32);
+serialize_nonzero!(NonZeroI64, i64);
+
+#[cfg(not(no_i128))]
+serialize_nonzero!(NonZeroU128, u128);
+#[cfg(not(no_i128))]
+serialize_nonzero!(NonZeroI128, i128);
+
+/// Serialize `usize` as `u64`, so encoding does not depend on target pointer width
+pub fn serialize_usize<P: EncodingParams>(
+    value: usize,
+    writer: impl WriteBytes,
+    params: P,
+) -> Result {
+    (value as u64).to_writer(writer, params)
+}
+
+/// Deserialize `usize` serialized with [`serialize_usize()`]
+///
+/// Returns [`Error::IntegerOverflow`] if value does not fit into `usize` on this target.
+pub fn deserialize_usize<P: EncodingParams>(reader: impl ReadBytes, params: P) -> Result<usize> {
+    u64::from_reader(reader, params)?
+        .try_into()
+        .map_err(|_| Error::IntegerOverflow)
+}
+
+/// Serialize `isize` as `i64`, so encoding does not depend on target pointer width
+pub fn serialize_isize<P: EncodingParams>(
+    value: isize,
+    writer: impl WriteBytes,
+    params: P,
+) -> Result {
+    (value as i64).to_writer(writer, params)
+}
+
+/// Deserialize `isize` serialized with [`serialize_isize()`]
+///
+/// Returns [`Error::IntegerOverflow`] if value does not fit into `isize` on this target.
+pub fn deserialize_isize<P: EncodingParams>(reader: impl ReadBytes, params: P) -> Result<isize> {
+    i64::from_reader(reader, params)?
+        .try_into()
+        .map_err(|_| Error::IntegerOverflow)
+}
+
+/// Serialize fixed-point decimal `mantissa * 10^-scale` with fixed per-column `column_scale`
+///
+/// Mantissa is rescaled to `column_scale` and encoded as `i128`, so decimals serialized with
+/// the same `column_scale` are ordered numerically. Scale itself is not serialized: comparing
+/// values serialized with different column scales is the caller's responsibility.
+///
+/// Returns [`Error::IntegerOverflow`] if rescaled mantissa does not fit into `i128`, and
+/// [`Error::PrecisionLoss`] if value has more fractional digits than `column_scale` allows.
+#[cfg(not(no_i128))]
+pub fn serialize_decimal<P: EncodingParams>(
+    mantissa: i128,
+    scale: u8,
+    column_scale: u8,
+    writer: impl WriteBytes,
+    params: P,
+) -> Result {
+    let mantissa = if column_scale >= scale {
+        pow10(column_scale - scale)
+            .and_then(|m| mantissa.checked_mul(m))
+            .ok_or(Error::IntegerOverflow)?
+    } else {
+        match pow10(scale - column_scale) {
+            Some(d) if mantissa % d == 0 => mantissa / d,
+            // divisor exceeds any mantissa, so only zero can be rescaled
+            None if mantissa == 0 => 0,
+            _ => return Err(Error::PrecisionLoss),
+        }
+    };
+    mantissa.to_writer(writer, params)
+}
+
+/// Deserialize fixed-point decimal serialized with [`serialize_decimal()`],
+/// returns `(mantissa, column_scale)`
+#[cfg(not(no_i128))]
+pub fn deserialize_decimal<P: EncodingParams>(
+    reader: impl ReadBytes,
+    column_scale: u8,
+    params: P,
+) -> Result<(i128, u8)> {
+    Ok((i128::from_reader(reader, params)?, column_scale))
+}
+
+#[cfg(not(no_i128))]
+fn pow10(exp: u8) -> Option<i128> {
+    10_i128.checked_pow(u32::from(exp))
+}
+
 impl SerializableValue for bool {
     fn to_writer<P: EncodingParams>(&self, writer: impl WriteBytes, params: P) -> Result {
         let v: u8 = if *self { 1 } else { 0 };
@@ -129,13 +464,14 @@ impl SerializableValue for char {
 
     fn from_reader<P: EncodingParams>(reader: impl ReadBytes, params: P) -> Result<Self> {
         let ch = u32::from_reader(reader, params)?;
-        core::char::from_u32(ch).ok_or(Error::InvalidUtf8Encoding)
+        // surrogates and values above `char::MAX`
+        core::char::from_u32(ch).ok_or(Error::InvalidCharCodePoint(ch))
     }
 }
 
 // Ordered serialization of floats
 macro_rules! serialize_float {
-    ($ft:ty, $ift:ty, $uft:ty) => {
+    ($ft:ty, $ift:ty, $uft:ty, $qnan:expr) => {
         impl SerializableValue for $ft {
             #[inline]
             fn to_writer<P: EncodingParams>(
@@ -143,7 +479,21 @@ macro_rules! serialize_float {
                 mut writer: impl WriteBytes,
                 _params: P,
             ) -> Result {
-                let t = self.to_bits() as $ift;
+                if P::FINITE_FLOATS && !self.is_finite() {
+                    return Err(Error::NonFiniteFloat);
+                }
+                let bits = if !P::CANONICAL_NAN {
+                    self.to_bits()
+                } else if self.is_nan() {
+                    $qnan
+                } else if *self == 0.0 {
+                    0 // collapse -0.0 into +0.0
+                } else {
+                    self.to_bits()
+                };
+                let t = bits as $ift;
+                // only big-endian encodings preserve ordering, so sign-magnitude to ordered
+                // transform is applied for them only; it does not depend on host endianness
                 let ov = if matches!(P::ENDIANNESS, Endianness::Big) {
                     const MSBOFFS: usize = core::mem::size_of::<$ift>() * 8 - 1; // # of bits - 1
                     t ^ ((t >> MSBOFFS) | <$ift>::min_value())
@@ -167,12 +517,1126 @@ macro_rules! serialize_float {
     };
 }
 
-serialize_float!(f32, i32, u32);
-serialize_float!(f64, i64, u64);
+// canonical quiet NaN bit patterns, used with `EncodingParams::CANONICAL_NAN`
+serialize_float!(f32, i32, u32, 0x7fc0_0000);
+serialize_float!(f64, i64, u64, 0x7ff8_0000_0000_0000);
+
+/// Serialize `f32` value promoted to `f64`
+///
+/// Widening conversion is exact and preserves ordering, so `f32` values serialized this way
+/// occupy 8 bytes and compare correctly with `f64` values, e.g. in a column of mixed float keys.
+/// ```
+/// # use ordcode::{ primitives::{ serialize_f32_as_f64, SerializableValue }, params::AscendingOrder };
+/// let mut b1 = Vec::new();
+/// let mut b2 = Vec::new();
+/// serialize_f32_as_f64(1.5_f32, &mut b1, AscendingOrder).unwrap();
+/// 1.5_f64.to_writer(&mut b2, AscendingOrder).unwrap();
+/// assert_eq!(b1, b2);
+/// ```
+pub fn serialize_f32_as_f64<P: EncodingParams>(
+    value: f32,
+    writer: impl WriteBytes,
+    params: P,
+) -> Result {
+    f64::from(value).to_writer(writer, params)
+}
+
+/// Deserialize `f64` value and narrow it to `f32`
+///
+/// Values which can't be represented as `f32` exactly are rounded to the nearest `f32`.
+/// Please see [`serialize_f32_as_f64()`].
+#[allow(clippy::cast_possible_truncation)]
+pub fn deserialize_f32_as_f64<P: EncodingParams>(reader: impl ReadBytes, params: P) -> Result<f32> {
+    f64::from_reader(reader, params).map(|v| v as f32)
+}
+
+/// Deserialize `f64` value and narrow it to `f32`, checking for precision loss
+///
+/// Returns [`Error::PrecisionLoss`] if value can't be represented as `f32` exactly; NaN values
+/// are always accepted. Please see [`serialize_f32_as_f64()`].
+#[allow(clippy::cast_possible_truncation)]
+pub fn deserialize_f32_as_f64_exact<P: EncodingParams>(
+    reader: impl ReadBytes,
+    params: P,
+) -> Result<f32> {
+    let v = f64::from_reader(reader, params)?;
+    let narrowed = v as f32;
+    if v.is_nan() || f64::from(narrowed).to_bits() == v.to_bits() {
+        Ok(narrowed)
+    } else {
+        Err(Error::PrecisionLoss)
+    }
+}
+
+/// `f64` value which is statically known not to be NaN
+///
+/// Constructed with `NonNanF64::try_from()`, which returns [`Error::NonFiniteFloat`] for NaN;
+/// infinite values are allowed. Negative zero is stored as positive zero, so equal values have
+/// equal encodings. Implements `Ord` and `Hash`, and its ordering matches ordering of encoded
+/// values and `PartialOrd` of the inner `f64`. Encoded same as `f64`; deserializing NaN returns
+/// [`Error::NonFiniteFloat`].
+/// ```
+/// # use ordcode::{ primitives::NonNanF64, Error };
+/// # use std::convert::TryFrom;
+/// assert!(matches!(NonNanF64::try_from(f64::NAN), Err(Error::NonFiniteFloat)));
+/// let mut v = vec![NonNanF64::try_from(1.5).unwrap(), NonNanF64::try_from(-2.0).unwrap()];
+/// v.sort();
+/// assert_eq!(v[0].get(), -2.0);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct NonNanF64(f64);
+
+impl NonNanF64 {
+    /// Inner `f64` value
+    #[must_use]
+    pub fn get(self) -> f64 {
+        self.0
+    }
+}
+
+impl TryFrom<f64> for NonNanF64 {
+    type Error = Error;
+
+    fn try_from(v: f64) -> Result<Self> {
+        if v.is_nan() {
+            Err(Error::NonFiniteFloat)
+        } else {
+            Ok(NonNanF64(v + 0.0)) // -0.0 + 0.0 is +0.0
+        }
+    }
+}
+
+impl From<NonNanF64> for f64 {
+    fn from(v: NonNanF64) -> Self {
+        v.0
+    }
+}
+
+impl Eq for NonNanF64 {}
+
+impl PartialOrd for NonNanF64 {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for NonNanF64 {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        // same as `partial_cmp()` of inner values, as there are no NaNs or negative zeros
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl core::hash::Hash for NonNanF64 {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+impl SerializableValue for NonNanF64 {
+    #[inline]
+    fn to_writer<P: EncodingParams>(&self, writer: impl WriteBytes, params: P) -> Result {
+        self.0.to_writer(writer, params)
+    }
+    #[inline]
+    fn from_reader<P: EncodingParams>(reader: impl ReadBytes, params: P) -> Result<Self> {
+        Self::try_from(f64::from_reader(reader, params)?)
+    }
+}
+
+impl MaxEncodedLen for NonNanF64 {
+    const MAX_ENCODED_LEN: usize = f64::MAX_ENCODED_LEN;
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for NonNanF64 {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_f64(self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for NonNanF64 {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Self::try_from(f64::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Primitive types which have values with the smallest and the largest encodings
+///
+/// Used by [`min_encoded()`] and [`max_encoded()`] functions.
+pub trait EncodedBounds: SerializableValue {
+    /// Value which has the smallest encoding in ascending order, with parameters `P`
+    fn min_encoded_value<P: EncodingParams>() -> Self;
+    /// Value which has the largest encoding in ascending order, with parameters `P`
+    fn max_encoded_value<P: EncodingParams>() -> Self;
+}
+
+macro_rules! encoded_bounds {
+    ($($t:ty),*) => {
+        $(
+        impl EncodedBounds for $t {
+            fn min_encoded_value<P: EncodingParams>() -> Self {
+                <$t>::MIN
+            }
+            fn max_encoded_value<P: EncodingParams>() -> Self {
+                <$t>::MAX
+            }
+        }
+        )*
+    };
+}
+
+encoded_bounds!(u8, u16, u32, u64, i8, i16, i32, i64);
+#[cfg(not(no_i128))]
+encoded_bounds!(u128, i128);
+
+// NaNs with sign bit set are ordered before negative infinity, and NaNs without sign bit are
+// ordered after positive infinity; with `EncodingParams::CANONICAL_NAN`, only canonical
+// positive NaN may be encoded
+macro_rules! encoded_bounds_float {
+    ($t:ty, $uft:ty) => {
+        impl EncodedBounds for $t {
+            fn min_encoded_value<P: EncodingParams>() -> Self {
+                if P::CANONICAL_NAN {
+                    <$t>::NEG_INFINITY
+                } else {
+                    <$t>::from_bits(<$uft>::MAX)
+                }
+            }
+            fn max_encoded_value<P: EncodingParams>() -> Self {
+                if P::CANONICAL_NAN {
+                    <$t>::NAN
+                } else {
+                    <$t>::from_bits(<$uft>::MAX >> 1)
+                }
+            }
+        }
+    };
+}
+
+encoded_bounds_float!(f32, u32);
+encoded_bounds_float!(f64, u64);
+
+impl EncodedBounds for bool {
+    fn min_encoded_value<P: EncodingParams>() -> Self {
+        false
+    }
+    fn max_encoded_value<P: EncodingParams>() -> Self {
+        true
+    }
+}
+
+impl EncodedBounds for char {
+    fn min_encoded_value<P: EncodingParams>() -> Self {
+        '\0'
+    }
+    fn max_encoded_value<P: EncodingParams>() -> Self {
+        char::MAX
+    }
+}
+
+/// Write the smallest possible encoding of a value of type `T`
+///
+/// Encodings of all values of type `T` are greater or equal to it, so it can be used
+/// as an inclusive lower bound for range scans. Bounds are meaningful only for big-endian
+/// encodings, which preserve ordering.
+/// ```
+/// # use ordcode::{ primitives::{ min_encoded, max_encoded }, params::{ AscendingOrder, DescendingOrder } };
+/// let mut min = Vec::new();
+/// let mut max = Vec::new();
+/// min_encoded::<u32, _>(&mut min, AscendingOrder).unwrap();
+/// max_encoded::<u32, _>(&mut max, AscendingOrder).unwrap();
+/// assert_eq!(min, [0, 0, 0, 0]);
+/// assert_eq!(max, [255, 255, 255, 255]);
+/// min.clear();
+/// min_encoded::<i16, _>(&mut min, DescendingOrder).unwrap();
+/// assert_eq!(min, [0, 0]);
+/// ```
+pub fn min_encoded<T: EncodedBounds, P: EncodingParams>(
+    writer: impl WriteBytes,
+    params: P,
+) -> Result {
+    ord_cond!(P, T::max_encoded_value::<P>(), T::min_encoded_value::<P>()).to_writer(writer, params)
+}
+
+/// Write the largest possible encoding of a value of type `T`
+///
+/// Encodings of all values of type `T` are less or equal to it, so it can be used as
+/// an inclusive upper bound for range scans. Please see [`min_encoded()`].
+pub fn max_encoded<T: EncodedBounds, P: EncodingParams>(
+    writer: impl WriteBytes,
+    params: P,
+) -> Result {
+    ord_cond!(P, T::min_encoded_value::<P>(), T::max_encoded_value::<P>()).to_writer(writer, params)
+}
+
+/// Serialize `Duration` as whole seconds (`u64`) followed by subsecond nanoseconds (`u32`)
+///
+/// Serialized durations preserve ordering. Encoding is 12 bytes long.
+/// ```
+/// # use ordcode::{ primitives::serialize_duration, params::AscendingOrder };
+/// # use core::time::Duration;
+/// let mut b1 = Vec::new();
+/// let mut b2 = Vec::new();
+/// serialize_duration(&Duration::from_nanos(1), &mut b1, AscendingOrder).unwrap();
+/// serialize_duration(&Duration::from_secs(1), &mut b2, AscendingOrder).unwrap();
+/// assert!(b1 < b2);
+/// ```
+pub fn serialize_duration<P: EncodingParams>(
+    value: &Duration,
+    mut writer: impl WriteBytes,
+    params: P,
+) -> Result {
+    value.as_secs().to_writer(&mut writer, params)?;
+    value.subsec_nanos().to_writer(writer, params)
+}
+
+/// Deserialize `Duration` serialized with [`serialize_duration()`]
+///
+/// Returns [`Error::InvalidDuration`] if subsecond nanoseconds part is out of range.
+pub fn deserialize_duration<P: EncodingParams>(
+    mut reader: impl ReadBytes,
+    params: P,
+) -> Result<Duration> {
+    let secs = u64::from_reader(&mut reader, params)?;
+    let nanos = u32::from_reader(reader, params)?;
+    if nanos >= 1_000_000_000 {
+        return Err(Error::InvalidDuration);
+    }
+    Ok(Duration::new(secs, nanos))
+}
+
+/// Serialize timestamp of whole seconds and subsecond nanoseconds as a single `u64`
+///
+/// Seconds are the high 32 bits and nanoseconds are the low 32 bits, so timestamps are ordered
+/// as instants with nanosecond precision. Returns [`Error::InvalidDuration`] if `nanos` is out
+/// of range. With big-endian encodings, result is the same as of serializing `(secs, nanos)`.
+/// ```
+/// # use ordcode::{ primitives::serialize_timestamp, params::AscendingOrder };
+/// let mut buf = Vec::new();
+/// serialize_timestamp(1, 500, &mut buf, AscendingOrder).unwrap();
+/// assert_eq!(buf, [0, 0, 0, 1, 0, 0, 1, 0xf4]);
+/// ```
+pub fn serialize_timestamp<P: EncodingParams>(
+    secs: u32,
+    nanos: u32,
+    writer: impl WriteBytes,
+    params: P,
+) -> Result {
+    if nanos >= 1_000_000_000 {
+        return Err(Error::InvalidDuration);
+    }
+    (u64::from(secs) << 32 | u64::from(nanos)).to_writer(writer, params)
+}
+
+/// Deserialize timestamp serialized with [`serialize_timestamp()`], returns `(secs, nanos)`
+///
+/// Returns [`Error::InvalidDuration`] if subsecond nanoseconds part is out of range.
+pub fn deserialize_timestamp<P: EncodingParams>(
+    reader: impl ReadBytes,
+    params: P,
+) -> Result<(u32, u32)> {
+    let v = u64::from_reader(reader, params)?;
+    #[allow(clippy::cast_possible_truncation)]
+    let (secs, nanos) = ((v >> 32) as u32, v as u32);
+    if nanos >= 1_000_000_000 {
+        return Err(Error::InvalidDuration);
+    }
+    Ok((secs, nanos))
+}
+
+/// Wrapper for `Duration` which implements _serde_ traits with ordered encoding
+///
+/// Serialized as a tuple of whole seconds (`u64`) and subsecond nanoseconds (`u32`), which with
+/// this crate serializer is the same as [`serialize_duration()`].
+#[cfg(feature = "serde")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DurationKey(pub Duration);
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for DurationKey {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        (self.0.as_secs(), self.0.subsec_nanos()).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for DurationKey {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (secs, nanos) = <(u64, u32)>::deserialize(deserializer)?;
+        if nanos >= 1_000_000_000 {
+            return Err(serde::de::Error::custom(Error::InvalidDuration));
+        }
+        Ok(DurationKey(Duration::new(secs, nanos)))
+    }
+}
+
+/// Serialize up to 8 boolean flags packed into a single byte
+///
+/// Flag 0 is the most significant bit, so serialized flags sort the same way as `[bool; N]`
+/// arrays, with flag 0 as the major sort key; unused low bits are zero. Returns
+/// [`Error::SequenceTooLong`] if `N` is larger than 8.
+/// ```
+/// # use ordcode::{ primitives::serialize_bitflags, params::AscendingOrder };
+/// let mut buf = Vec::new();
+/// serialize_bitflags(&[true, false, true], &mut buf, AscendingOrder).unwrap();
+/// assert_eq!(buf, [0b1010_0000]);
+/// ```
+pub fn serialize_bitflags<P: EncodingParams, const N: usize>(
+    flags: &[bool; N],
+    writer: impl WriteBytes,
+    params: P,
+) -> Result {
+    if N > 8 {
+        return Err(Error::SequenceTooLong);
+    }
+    pack_bits(flags).to_writer(writer, params)
+}
+
+/// Deserialize boolean flags serialized with [`serialize_bitflags()`]
+///
+/// Unused low bits are ignored.
+pub fn deserialize_bitflags<P: EncodingParams, const N: usize>(
+    reader: impl ReadBytes,
+    params: P,
+) -> Result<[bool; N]> {
+    if N > 8 {
+        return Err(Error::SequenceTooLong);
+    }
+    let mut flags = [false; N];
+    unpack_bits(u8::from_reader(reader, params)?, &mut flags);
+    Ok(flags)
+}
+
+// Flag 0 is the most significant bit, expects no more than 8 flags
+fn pack_bits(flags: &[bool]) -> u8 {
+    flags
+        .iter()
+        .enumerate()
+        .fold(0, |bits, (i, &f)| bits | (u8::from(f) << (7 - i)))
+}
+
+fn unpack_bits(bits: u8, flags: &mut [bool]) {
+    for (i, f) in flags.iter_mut().enumerate() {
+        *f = bits & (0x80 >> i) != 0;
+    }
+}
+
+/// Wrapper for 8 boolean flags which implements _serde_ traits with packed encoding
+///
+/// Serialized as a single `u8`, same as [`serialize_bitflags()`]; flag 0 is the most
+/// significant bit and the major sort key.
+#[cfg(feature = "serde")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BitFlags8(pub [bool; 8]);
+
+#[cfg(feature = "serde")]
+impl BitFlags8 {
+    /// Flags packed into a byte, flag 0 is the most significant bit
+    #[must_use]
+    pub fn bits(&self) -> u8 {
+        pack_bits(&self.0)
+    }
+    /// Unpack flags from a byte, flag 0 is the most significant bit
+    #[must_use]
+    pub fn from_bits(bits: u8) -> Self {
+        let mut flags = [false; 8];
+        unpack_bits(bits, &mut flags);
+        BitFlags8(flags)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for BitFlags8 {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_u8(self.bits())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for BitFlags8 {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        u8::deserialize(deserializer).map(BitFlags8::from_bits)
+    }
+}
+
+/// Wrapper for SQL-style nullable boolean, which implements _serde_ traits with single-byte encoding
+///
+/// Serialized as a `u8`: by default 0 for `false`, 1 for `true` and 2 for null (`None`), so
+/// `false < true < null`. With `NULLS_FIRST` parameter, encoded values are 0 for null, 1 for
+/// `false` and 2 for `true`. Ordering of `TriBool` values is the same as ordering of their
+/// encodings. Deserializing other values returns [`Error::InvalidTagEncoding`].
+/// ```
+/// # use ordcode::{ primitives::TriBool, ser_to_vec_ordered, Order };
+/// let null = ser_to_vec_ordered(&TriBool::<false>(None), Order::Ascending).unwrap();
+/// assert_eq!(null, [2]);
+/// assert!(TriBool::<true>(None) < TriBool(Some(false)));
+/// ```
+#[cfg(feature = "serde")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TriBool<const NULLS_FIRST: bool = false>(pub Option<bool>);
+
+#[cfg(feature = "serde")]
+impl<const NULLS_FIRST: bool> TriBool<NULLS_FIRST> {
+    // encoded value
+    fn code(self) -> u8 {
+        match (self.0, NULLS_FIRST) {
+            (Some(false), false) | (None, true) => 0,
+            (Some(true), false) | (Some(false), true) => 1,
+            (None, false) | (Some(true), true) => 2,
+        }
+    }
+    fn from_code(code: u8) -> Result<Self> {
+        let v = match (code, NULLS_FIRST) {
+            (0, false) | (1, true) => Some(false),
+            (1, false) | (2, true) => Some(true),
+            (2, false) | (0, true) => None,
+            _ => return Err(Error::InvalidTagEncoding),
+        };
+        Ok(TriBool(v))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const NULLS_FIRST: bool> PartialOrd for TriBool<NULLS_FIRST> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const NULLS_FIRST: bool> Ord for TriBool<NULLS_FIRST> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.code().cmp(&other.code())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const NULLS_FIRST: bool> serde::Serialize for TriBool<NULLS_FIRST> {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_u8(self.code())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const NULLS_FIRST: bool> serde::Deserialize<'de> for TriBool<NULLS_FIRST> {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Self::from_code(u8::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Serialize `Cow<str>` as a string, for use with `#[serde(serialize_with = "...")]`
+///
+/// Produces the same encoding as _serde_ does for `Cow<str>`, provided as counterpart
+/// for [`deserialize_cow()`].
+#[cfg(all(feature = "std", feature = "serde"))]
+pub fn serialize_cow<S>(value: &str, serializer: S) -> core::result::Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(value)
+}
+
+/// Collation of strings for locale-aware ordering, see [`serialize_collated()`]
+///
+/// Serialized strings sort in the order of their UTF-8 bytes, i.e. of Unicode code points, which
+/// is wrong for most natural languages (e.g. German phonebook order sorts `"ä"` as `"ae"`).
+/// This crate does not include collation tables; implement this trait with a collation library
+/// which produces sort keys, such as ICU `ucol_getSortKey()`. Sort keys of the stored data depend
+/// on the collation rules and their version, so changing them requires re-encoding the keys.
+#[cfg(feature = "collation")]
+pub trait Collation {
+    /// Sort key of `value`, such that sort keys compare as byte strings in collation order
+    fn sort_key(value: &str) -> Vec<u8>;
+}
+
+// byte string, serialized with `serialize_bytes()`
+#[cfg(feature = "collation")]
+struct SortKey<'a>(&'a [u8]);
+
+#[cfg(feature = "collation")]
+impl serde::Serialize for SortKey<'_> {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+// skips byte string written by `SortKey`
+#[cfg(feature = "collation")]
+struct SkipSortKey;
+
+#[cfg(feature = "collation")]
+impl<'de> serde::Deserialize<'de> for SkipSortKey {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct SkipVisitor;
+
+        impl serde::de::Visitor<'_> for SkipVisitor {
+            type Value = SkipSortKey;
+
+            fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.write_str("a byte string")
+            }
+            fn visit_bytes<E>(self, _v: &[u8]) -> core::result::Result<Self::Value, E> {
+                Ok(SkipSortKey)
+            }
+        }
+        deserializer.deserialize_bytes(SkipVisitor)
+    }
+}
+
+/// Serialize string with collation key of `C`, for use with `#[serde(serialize_with = "...")]`
+///
+/// Serialized as a tuple of the sort key (as byte string) and the original string, so values
+/// are ordered by collation, and strings with equal sort keys are ordered by their bytes.
+/// Sort key is escaped with [`serialize_escaped()`](crate::bytes_esc::serialize_escaped), so that
+/// the string which follows it doesn't affect ordering when one sort key is a prefix of another.
+/// Original string is deserialized with [`deserialize_collated()`]. If round-trip is not needed,
+/// [`serialize_collation_key()`] stores the sort key only.
+/// ```
+/// # use ordcode::{ primitives::Collation, ser_to_vec_ordered, de_from_bytes_asc, Order };
+/// // case-insensitive collation
+/// struct NoCase;
+/// impl Collation for NoCase {
+///     fn sort_key(value: &str) -> Vec<u8> {
+///         value.to_lowercase().into_bytes()
+///     }
+/// }
+/// #[derive(serde_derive::Serialize, serde_derive::Deserialize, Debug, PartialEq)]
+/// struct Key {
+///     #[serde(serialize_with = "ordcode::primitives::serialize_collated::<NoCase, _>",
+///             deserialize_with = "ordcode::primitives::deserialize_collated")]
+///     name: String,
+/// }
+/// let key = |name: &str| Key { name: name.into() };
+/// let a = ser_to_vec_ordered(&key("b"), Order::Ascending).unwrap();
+/// let b = ser_to_vec_ordered(&key("C"), Order::Ascending).unwrap();
+/// assert!(a < b);
+/// assert_eq!(de_from_bytes_asc::<Key>(&b).unwrap(), key("C"));
+/// ```
+#[cfg(feature = "collation")]
+pub fn serialize_collated<C, S>(value: &str, serializer: S) -> core::result::Result<S::Ok, S::Error>
+where
+    C: Collation,
+    S: serde::Serializer,
+{
+    use serde::{ser::Error, Serialize};
+    let mut key = Vec::new();
+    crate::bytes_esc::serialize_escaped(
+        &mut key,
+        &C::sort_key(value),
+        crate::params::AscendingOrder,
+    )
+    .map_err(S::Error::custom)?;
+    (SortKey(&key), value).serialize(serializer)
+}
+
+/// Deserialize string serialized with [`serialize_collated()`], for use with
+/// `#[serde(deserialize_with = "...")]`
+///
+/// Sort key is skipped, collation is not needed for deserialization.
+#[cfg(feature = "collation")]
+pub fn deserialize_collated<'de, D>(deserializer: D) -> core::result::Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    <(SkipSortKey, String) as serde::Deserialize>::deserialize(deserializer).map(|(_, s)| s)
+}
+
+/// Serialize collation key of `C` only, for use with `#[serde(serialize_with = "...")]`
+///
+/// Takes less space than [`serialize_collated()`], but original string can't be deserialized;
+/// serialized data is the sort key as byte string, and can be deserialized as bytes (e.g. into
+/// `serde_bytes::ByteBuf`). Strings with equal sort keys produce the same serialized data.
+#[cfg(feature = "collation")]
+pub fn serialize_collation_key<C, S>(
+    value: &str,
+    serializer: S,
+) -> core::result::Result<S::Ok, S::Error>
+where
+    C: Collation,
+    S: serde::Serializer,
+{
+    serializer.serialize_bytes(&C::sort_key(value))
+}
+
+/// Deserialize `Cow<str>` without copying, for use with `#[serde(deserialize_with = "...")]`
+///
+/// _Serde_ always deserializes `Cow<str>` into `Cow::Owned`. This function returns `Cow::Borrowed`
+/// pointing into input buffer if deserializer can lend data (e.g. when reading from byte slice
+/// with [`DeBytesReader`](crate::DeBytesReader)), and falls back to `Cow::Owned` otherwise,
+/// e.g. for streaming readers. Field should also be marked with `#[serde(borrow)]`.
+/// ```
+/// # use std::borrow::Cow;
+/// # use ordcode::{ de_from_bytes_asc, ser_to_vec_ordered, Order };
+/// #[derive(serde_derive::Serialize, serde_derive::Deserialize)]
+/// struct Key<'a> {
+///     #[serde(borrow, serialize_with = "ordcode::primitives::serialize_cow",
+///             deserialize_with = "ordcode::primitives::deserialize_cow")]
+///     name: Cow<'a, str>,
+/// }
+/// let buf = ser_to_vec_ordered(&Key { name: "abc".into() }, Order::Ascending).unwrap();
+/// let key: Key = de_from_bytes_asc(&buf).unwrap();
+/// assert!(matches!(key.name, Cow::Borrowed("abc")));
+/// ```
+#[cfg(all(feature = "std", feature = "serde"))]
+pub fn deserialize_cow<'de, D>(deserializer: D) -> core::result::Result<Cow<'de, str>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    struct CowVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for CowVisitor {
+        type Value = Cow<'de, str>;
+
+        fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.write_str("a string")
+        }
+        fn visit_borrowed_str<E>(self, v: &'de str) -> core::result::Result<Self::Value, E> {
+            Ok(Cow::Borrowed(v))
+        }
+        fn visit_str<E>(self, v: &str) -> core::result::Result<Self::Value, E> {
+            Ok(Cow::Owned(v.to_owned()))
+        }
+        fn visit_string<E>(self, v: String) -> core::result::Result<Self::Value, E> {
+            Ok(Cow::Owned(v))
+        }
+    }
+    deserializer.deserialize_str(CowVisitor)
+}
+
+/// Serialize fixed-size byte array, without length
+///
+/// Writes exactly `N` bytes, bitwise inverted for [`Order::Descending`]. Same encoding is
+/// produced by _serde_ serializer for `[u8; N]`, which is serialized as a tuple.
+/// ```
+/// # use ordcode::{ primitives::serialize_fixed_bytes, params::DescendingOrder };
+/// let mut buf = Vec::new();
+/// serialize_fixed_bytes(&[0_u8, 1, 2], &mut buf, DescendingOrder).unwrap();
+/// assert_eq!(buf, [255, 254, 253]);
+/// ```
+pub fn serialize_fixed_bytes<P: EncodingParams, const N: usize>(
+    value: &[u8; N],
+    writer: impl WriteBytes,
+    params: P,
+) -> Result {
+    crate::bytes_esc::serialize_bytes_noesc(writer, value, params)
+}
+
+/// Deserialize fixed-size byte array serialized with [`serialize_fixed_bytes()`]
+pub fn deserialize_fixed_bytes<P: EncodingParams, const N: usize>(
+    mut reader: impl ReadBytes,
+    _params: P,
+) -> Result<[u8; N]> {
+    reader.read(N, |buf| {
+        let mut value = [0_u8; N];
+        value.copy_from_slice(buf);
+        if matches!(P::ORDER, Order::Descending) {
+            invert_buffer(&mut value);
+        }
+        Ok(value)
+    })
+}
+
+// Ordered serialization of IP addresses as octets in network byte order
+macro_rules! serialize_ip {
+    ($t:ty, $n:expr) => {
+        #[cfg(feature = "std")]
+        impl SerializableValue for $t {
+            fn to_writer<P: EncodingParams>(&self, writer: impl WriteBytes, params: P) -> Result {
+                serialize_fixed_bytes(&self.octets(), writer, params)
+            }
+            fn from_reader<P: EncodingParams>(reader: impl ReadBytes, params: P) -> Result<Self> {
+                deserialize_fixed_bytes::<P, $n>(reader, params).map(<$t>::from)
+            }
+        }
+        #[cfg(feature = "std")]
+        impl MaxEncodedLen for $t {
+            const MAX_ENCODED_LEN: usize = $n;
+        }
+    };
+}
+
+serialize_ip!(Ipv4Addr, 4);
+serialize_ip!(Ipv6Addr, 16);
+
+/// Serialize `IpAddr` as discriminant byte (0 for v4, 1 for v6) followed by address octets
+///
+/// Addresses of the same family are ordered numerically, v4 addresses are ordered before v6.
+/// ```
+/// # use ordcode::{ primitives::serialize_ip_addr, params::AscendingOrder };
+/// # use std::net::IpAddr;
+/// let mut b1 = Vec::new();
+/// let mut b2 = Vec::new();
+/// serialize_ip_addr(&"10.0.0.1".parse::<IpAddr>().unwrap(), &mut b1, AscendingOrder).unwrap();
+/// serialize_ip_addr(&"::1".parse::<IpAddr>().unwrap(), &mut b2, AscendingOrder).unwrap();
+/// assert_eq!(b1, [0, 10, 0, 0, 1]);
+/// assert!(b1 < b2);
+/// ```
+#[cfg(feature = "std")]
+pub fn serialize_ip_addr<P: EncodingParams>(
+    value: &IpAddr,
+    mut writer: impl WriteBytes,
+    params: P,
+) -> Result {
+    match value {
+        IpAddr::V4(a) => {
+            0_u8.to_writer(&mut writer, params)?;
+            a.to_writer(writer, params)
+        }
+        IpAddr::V6(a) => {
+            1_u8.to_writer(&mut writer, params)?;
+            a.to_writer(writer, params)
+        }
+    }
+}
+
+/// Deserialize `IpAddr` serialized with [`serialize_ip_addr()`]
+///
+/// Returns [`Error::InvalidTagEncoding`] if discriminant byte is invalid.
+#[cfg(feature = "std")]
+pub fn deserialize_ip_addr<P: EncodingParams>(
+    mut reader: impl ReadBytes,
+    params: P,
+) -> Result<IpAddr> {
+    match u8::from_reader(&mut reader, params)? {
+        0 => Ipv4Addr::from_reader(reader, params).map(IpAddr::V4),
+        1 => Ipv6Addr::from_reader(reader, params).map(IpAddr::V6),
+        _ => Err(Error::InvalidTagEncoding),
+    }
+}
+
+/// Serialize `SocketAddr` as IP address, same as [`serialize_ip_addr()`], followed by port
+///
+/// Port is encoded as 2 bytes in network byte order, regardless of [`Endianness`] parameter, so
+/// socket addresses are ordered by address first, then by port. Note that IPv6 flow info and
+/// scope id are not serialized.
+/// ```
+/// # use ordcode::{ primitives::serialize_socket_addr, params::AscendingOrder };
+/// # use std::net::SocketAddr;
+/// let mut buf = Vec::new();
+/// serialize_socket_addr(&"10.0.0.1:443".parse::<SocketAddr>().unwrap(), &mut buf, AscendingOrder).unwrap();
+/// assert_eq!(buf, [0, 10, 0, 0, 1, 0x01, 0xbb]);
+/// ```
+#[cfg(feature = "std")]
+pub fn serialize_socket_addr<P: EncodingParams>(
+    value: &SocketAddr,
+    mut writer: impl WriteBytes,
+    params: P,
+) -> Result {
+    serialize_ip_addr(&value.ip(), &mut writer, params)?;
+    serialize_fixed_bytes(&value.port().to_be_bytes(), writer, params)
+}
+
+/// Deserialize `SocketAddr` serialized with [`serialize_socket_addr()`]
+///
+/// Returns [`Error::InvalidTagEncoding`] if address discriminant byte is invalid.
+#[cfg(feature = "std")]
+pub fn deserialize_socket_addr<P: EncodingParams>(
+    mut reader: impl ReadBytes,
+    params: P,
+) -> Result<SocketAddr> {
+    let ip = deserialize_ip_addr(&mut reader, params)?;
+    let port = deserialize_fixed_bytes::<P, 2>(reader, params)?;
+    Ok(SocketAddr::new(ip, u16::from_be_bytes(port)))
+}
+
+/// Wrapper for `Ipv4Addr` which implements _serde_ traits with ordered encoding
+///
+/// Serialized as a tuple of address octets, same as `Ipv4Addr` [`SerializableValue`] impl.
+#[cfg(all(feature = "std", feature = "serde"))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ipv4AddrKey(pub Ipv4Addr);
+
+/// Wrapper for `Ipv6Addr` which implements _serde_ traits with ordered encoding
+///
+/// Serialized as a tuple of address octets, same as `Ipv6Addr` [`SerializableValue`] impl.
+#[cfg(all(feature = "std", feature = "serde"))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ipv6AddrKey(pub Ipv6Addr);
+
+/// Wrapper for `IpAddr` which implements _serde_ traits with ordered encoding
+///
+/// Serialized as a tuple of discriminant byte and address octets, same as [`serialize_ip_addr()`].
+#[cfg(all(feature = "std", feature = "serde"))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IpAddrKey(pub IpAddr);
+
+#[cfg(all(feature = "std", feature = "serde"))]
+const _: () = {
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    impl Serialize for Ipv4AddrKey {
+        fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+            self.0.octets().serialize(serializer)
+        }
+    }
+    impl<'de> Deserialize<'de> for Ipv4AddrKey {
+        fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            <[u8; 4]>::deserialize(deserializer).map(|o| Ipv4AddrKey(o.into()))
+        }
+    }
+    impl Serialize for Ipv6AddrKey {
+        fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+            self.0.octets().serialize(serializer)
+        }
+    }
+    impl<'de> Deserialize<'de> for Ipv6AddrKey {
+        fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            <[u8; 16]>::deserialize(deserializer).map(|o| Ipv6AddrKey(o.into()))
+        }
+    }
+    impl Serialize for IpAddrKey {
+        fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+            match self.0 {
+                IpAddr::V4(a) => (0_u8, a.octets()).serialize(serializer),
+                IpAddr::V6(a) => (1_u8, a.octets()).serialize(serializer),
+            }
+        }
+    }
+    impl<'de> Deserialize<'de> for IpAddrKey {
+        fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            struct IpAddrVisitor;
+
+            impl<'de> de::Visitor<'de> for IpAddrVisitor {
+                type Value = IpAddrKey;
+
+                fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    f.write_str("IP address discriminant and octets")
+                }
+                fn visit_seq<A>(self, mut seq: A) -> core::result::Result<Self::Value, A::Error>
+                where
+                    A: de::SeqAccess<'de>,
+                {
+                    let missing = || de::Error::invalid_length(1, &self);
+                    let addr = match seq.next_element::<u8>()?.ok_or_else(missing)? {
+                        0 => IpAddr::from(seq.next_element::<[u8; 4]>()?.ok_or_else(missing)?),
+                        1 => IpAddr::from(seq.next_element::<[u8; 16]>()?.ok_or_else(missing)?),
+                        _ => return Err(de::Error::custom(Error::InvalidTagEncoding)),
+                    };
+                    Ok(IpAddrKey(addr))
+                }
+            }
+            deserializer.deserialize_tuple(2, IpAddrVisitor)
+        }
+    }
+};
+
+/// Compare serialized representations of two values, without heap allocation for small values
+///
+/// Result is the same as comparison of byte vectors produced by [`ser_to_vec_ordered()`](crate::ser_to_vec_ordered)
+/// for `a` and `b`, but values are serialized into stack buffers if their serialized size is
+/// not larger than 64 bytes. Larger values are serialized into heap-allocated buffers, or
+/// [`Error::BufferOverflow`] is returned if `std` feature is not enabled.
+/// ```
+/// # use ordcode::{ primitives::compare_serialized, Order };
+/// # use core::cmp::Ordering;
+/// assert_eq!(compare_serialized(&(1_u8, "b"), &(1_u8, "ab"), Order::Ascending).unwrap(), Ordering::Greater);
+/// assert_eq!(compare_serialized(&(1_u8, "b"), &(1_u8, "ab"), Order::Descending).unwrap(), Ordering::Less);
+/// ```
+#[cfg(feature = "serde")]
+pub fn compare_serialized<T>(a: &T, b: &T, order: Order) -> Result<core::cmp::Ordering>
+where
+    T: ?Sized + serde::Serialize,
+{
+    const SCRATCH_SIZE: usize = 64;
+    let a_len = crate::calc_size_asc(a)?;
+    let b_len = crate::calc_size_asc(b)?;
+    if a_len <= SCRATCH_SIZE && b_len <= SCRATCH_SIZE {
+        let mut a_buf = [0_u8; SCRATCH_SIZE];
+        let mut b_buf = [0_u8; SCRATCH_SIZE];
+        crate::ser_to_buf_ordered(&mut a_buf[..a_len], a, order)?;
+        crate::ser_to_buf_ordered(&mut b_buf[..b_len], b, order)?;
+        return Ok(a_buf[..a_len].cmp(&b_buf[..b_len]));
+    }
+    #[cfg(feature = "std")]
+    {
+        let a_buf = crate::ser_to_vec_ordered(a, order)?;
+        let b_buf = crate::ser_to_vec_ordered(b, order)?;
+        Ok(a_buf.cmp(&b_buf))
+    }
+    #[cfg(not(feature = "std"))]
+    Err(Error::BufferOverflow {
+        needed: a_len.max(b_len),
+        available: SCRATCH_SIZE,
+    })
+}
 
 /// Bitwise invert contents of a buffer
+///
+/// Processes the buffer in 64-bit words, which is considerably faster than inverting
+/// byte by byte for large buffers.
 pub fn invert_buffer(buf: &mut [u8]) {
-    for b in buf {
+    let mut chunks = buf.chunks_exact_mut(8);
+    for chunk in &mut chunks {
+        let mut word = [0_u8; 8];
+        word.copy_from_slice(chunk);
+        chunk.copy_from_slice(&(!u64::from_ne_bytes(word)).to_ne_bytes());
+    }
+    for b in chunks.into_remainder() {
         *b = !*b;
     }
 }
+
+/// Bitwise invert `range` of a buffer, leaving the rest of it untouched
+///
+/// Use it to apply descending order to a single field of a key, with the field range
+/// obtained from [`DeBytesWriter::head_range()`](crate::DeBytesWriter::head_range).
+/// Panics if `range` is out of buffer bounds.
+/// ```
+/// # use ordcode::primitives::invert_range;
+/// let mut buf = [1_u8, 2, 3, 4];
+/// invert_range(&mut buf, 1..3);
+/// assert_eq!(buf, [1, !2, !3, 4]);
+/// ```
+pub fn invert_range(buf: &mut [u8], range: Range<usize>) {
+    invert_buffer(&mut buf[range]);
+}
+
+/// Calculate the lexicographic successor of key prefix, in place
+///
+/// Finds the smallest key which is greater than all keys starting with `buf`, by incrementing
+/// the last byte which is not `0xFF` and truncating the rest. Returns length of the resulting
+/// key, which is `buf[..len]`, or `None` if `buf` is empty or consists of `0xFF` bytes only
+/// (there is no upper bound in this case).
+///
+/// Successor should be calculated for the final byte representation of the key, as stored in
+/// the database: for [`Order::Descending`], apply it to the already inverted buffer, never
+/// invert the successor afterwards.
+/// ```
+/// # use ordcode::primitives::successor_key_in_place;
+/// let mut buf = [1_u8, 2, 0xFF];
+/// assert_eq!(successor_key_in_place(&mut buf), Some(2));
+/// assert_eq!(&buf[..2], &[1, 3]);
+/// ```
+pub fn successor_key_in_place(buf: &mut [u8]) -> Option<usize> {
+    let pos = buf.iter().rposition(|b| *b != 0xFF)?;
+    buf[pos] += 1;
+    Some(pos + 1)
+}
+
+/// Calculate the lexicographic successor of key prefix
+///
+/// Returns the smallest key which is greater than all keys starting with `buf`, or `None` if
+/// there is no such key. Please see [`successor_key_in_place()`] for details.
+/// ```
+/// # use ordcode::primitives::successor_key;
+/// assert_eq!(successor_key(&[1, 2, 0xFF]), Some(vec![1, 3]));
+/// assert_eq!(successor_key(&[0xFF, 0xFF]), None);
+/// ```
+#[cfg(feature = "std")]
+#[must_use]
+pub fn successor_key(buf: &[u8]) -> Option<Vec<u8>> {
+    let mut v = buf.to_vec();
+    let len = successor_key_in_place(&mut v)?;
+    v.truncate(len);
+    Some(v)
+}
+
+/// Wrapper for printing encoded keys as hex, for debugging
+///
+/// `Display` and `Debug` print bytes as space-separated lowercase hex pairs.
+/// ```
+/// # use ordcode::primitives::HexKey;
+/// assert_eq!(HexKey(&[0x01, 0xab, 0xff]).to_string(), "01 ab ff");
+/// assert_eq!(format!("{:?}", HexKey(&[])), "");
+/// ```
+#[derive(Copy, Clone)]
+pub struct HexKey<'a>(pub &'a [u8]);
+
+impl core::fmt::Display for HexKey<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (i, b) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{b:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl core::fmt::Debug for HexKey<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(self, f)
+    }
+}
+
+/// Format byte buffer as lowercase hex string, for debugging
+/// ```
+/// # use ordcode::primitives::{ to_hex, from_hex };
+/// assert_eq!(to_hex(&[0x01, 0xab, 0xff]), "01abff");
+/// assert_eq!(from_hex("01abff").unwrap(), [0x01, 0xab, 0xff]);
+/// ```
+#[cfg(feature = "std")]
+#[must_use]
+pub fn to_hex(buf: &[u8]) -> String {
+    use core::fmt::Write;
+    let mut s = String::with_capacity(buf.len() * 2);
+    for b in buf {
+        let _ = write!(s, "{b:02x}");
+    }
+    s
+}
+
+/// Parse hex string into byte buffer, for debugging
+///
+/// Accepts upper and lower case hex digits, ignores whitespace, so output of [`HexKey`]
+/// can be parsed too. Returns [`Error::InvalidHexEncoding`] for other characters or odd
+/// number of digits.
+#[cfg(feature = "std")]
+pub fn from_hex(s: &str) -> Result<Vec<u8>> {
+    let digits = s
+        .bytes()
+        .filter(|c| !c.is_ascii_whitespace())
+        .map(|c| match c {
+            b'0'..=b'9' => Ok(c - b'0'),
+            b'a'..=b'f' => Ok(c - b'a' + 10),
+            b'A'..=b'F' => Ok(c - b'A' + 10),
+            _ => Err(Error::InvalidHexEncoding),
+        })
+        .collect::<Result<Vec<u8>>>()?;
+    if digits.len() % 2 != 0 {
+        return Err(Error::InvalidHexEncoding);
+    }
+    Ok(digits.chunks_exact(2).map(|d| (d[0] << 4) | d[1]).collect())
+}
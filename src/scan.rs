@@ -0,0 +1,148 @@
+//! Key ranges for range scans over keys with common leading fields
+//!
+//! Please see [`ScanBounds`].
+use crate::{params::Order, primitives::successor_key, ser_prefix, Result};
+use serde::Serialize;
+
+/// Range of serialized keys: `start` is inclusive, `end` is exclusive
+///
+/// Empty `start` is less or equal to any key, `end` equal to `None` means the range is not
+/// bounded from above. Bounds are in the final byte representation of keys, as stored in the
+/// database, and can be passed to the range scan of a key-value store as is.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyRange {
+    pub start: Vec<u8>,
+    pub end: Option<Vec<u8>>,
+}
+
+impl KeyRange {
+    /// Check if serialized key is within the range
+    #[must_use]
+    pub fn contains(&self, key: &[u8]) -> bool {
+        key >= self.start.as_slice()
+            && match &self.end {
+                Some(end) => key < end.as_slice(),
+                None => true,
+            }
+    }
+}
+
+/// Builder of [`KeyRange`] for scans over leading fields of tuple or struct keys
+///
+/// Bound values are serialized with [`ser_prefix()`](crate::ser_prefix), so only the first
+/// `fields` fields of a bound value are taken into account (all fields by default), and bound
+/// values must be tuples, tuple structs or structs. Upper bounds are calculated with
+/// [`successor_key()`](crate::primitives::successor_key), so that keys with any trailing fields
+/// are included. For [`Order::Descending`], lower and upper bounds of values are swapped
+/// to become upper and lower bounds of the inverted keys.
+///
+/// Same as with [`ser_prefix()`](crate::ser_prefix), bound on a variable-length field (e.g. `"ab"`)
+/// also includes keys where this field only starts with the same bytes (e.g. `"abc"`).
+///
+/// *Example*
+/// ```
+/// # use ordcode::{ Order, ScanBounds, ser_to_vec_ordered };
+/// let key = |id: u32, name: &str| ser_to_vec_ordered(&(id, name), Order::Descending).unwrap();
+/// let range = ScanBounds::new(Order::Descending).fields(1).between(&(2_u32,), &(4_u32,)).unwrap();
+/// assert!(range.contains(&key(2, "a")));
+/// assert!(range.contains(&key(4, "z")));
+/// assert!(!range.contains(&key(1, "a")));
+/// assert!(!range.contains(&key(5, "a")));
+/// ```
+#[derive(Copy, Clone)]
+pub struct ScanBounds {
+    order: Order,
+    fields: usize,
+}
+
+impl ScanBounds {
+    /// Create builder for keys serialized with `order`
+    #[must_use]
+    pub fn new(order: Order) -> Self {
+        Self {
+            order,
+            fields: usize::MAX,
+        }
+    }
+
+    /// Take into account only the first `fields` fields of bound values
+    #[must_use]
+    pub fn fields(self, fields: usize) -> Self {
+        Self { fields, ..self }
+    }
+
+    /// Keys whose leading fields are equal to leading fields of `value`
+    pub fn prefix<T>(&self, value: &T) -> Result<KeyRange>
+    where
+        T: ?Sized + Serialize,
+    {
+        let start = self.ser(value)?;
+        let end = successor_key(&start);
+        Ok(KeyRange { start, end })
+    }
+
+    /// Keys whose leading fields are greater or equal to leading fields of `value`
+    pub fn ge<T>(&self, value: &T) -> Result<KeyRange>
+    where
+        T: ?Sized + Serialize,
+    {
+        let bound = self.ser(value)?;
+        Ok(if self.is_descending() {
+            Self::up_to(&bound)
+        } else {
+            Self::starting_at(bound)
+        })
+    }
+
+    /// Keys whose leading fields are less or equal to leading fields of `value`
+    pub fn le<T>(&self, value: &T) -> Result<KeyRange>
+    where
+        T: ?Sized + Serialize,
+    {
+        let bound = self.ser(value)?;
+        Ok(if self.is_descending() {
+            Self::starting_at(bound)
+        } else {
+            Self::up_to(&bound)
+        })
+    }
+
+    /// Keys whose leading fields are between leading fields of `low` and `high`, inclusive
+    pub fn between<T>(&self, low: &T, high: &T) -> Result<KeyRange>
+    where
+        T: ?Sized + Serialize,
+    {
+        let (first, last) = if self.is_descending() {
+            (high, low)
+        } else {
+            (low, high)
+        };
+        let start = self.ser(first)?;
+        let end = successor_key(&self.ser(last)?);
+        Ok(KeyRange { start, end })
+    }
+
+    fn ser<T>(&self, value: &T) -> Result<Vec<u8>>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser_prefix(value, self.fields, self.order)
+    }
+    fn is_descending(&self) -> bool {
+        matches!(self.order, Order::Descending)
+    }
+    // keys starting from `bound`, inclusive
+    fn starting_at(bound: Vec<u8>) -> KeyRange {
+        KeyRange {
+            start: bound,
+            end: None,
+        }
+    }
+    // keys up to `bound` and keys starting with it, inclusive
+    fn up_to(bound: &[u8]) -> KeyRange {
+        KeyRange {
+            start: Vec::new(),
+            end: successor_key(bound),
+        }
+    }
+}
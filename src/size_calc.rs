@@ -1,4 +1,5 @@
 use crate::{
+    ord_ser::packed_options,
     params::{LengthEncoder, SerializerParams},
     Error, Result,
 };
@@ -7,12 +8,40 @@ use serde::{ser, Serialize};
 
 /// Serialized object size calculator
 ///
-/// Use as [`serde::Serializer`] on objects, then `.size()` will return serialized data size
+/// `&mut SizeCalc` implements [`serde::Serializer`], so any [`Serialize`] implementation, including
+/// manually written ones, can be run through it. Then `.size()` or `.finish()` will return serialized
+/// data size, `.head_size()` and `.tail_size()` return sizes of data and length sections of the
+/// double-ended buffer. Size is the same as [`Serializer`](crate::Serializer) with the same
+/// parameters would produce, [`calc_size()`](crate::calc_size) is a shortcut for this.
 ///
 /// Calculation process is inexpensive, for fixed-size objects it evaluates to compile-time constant,
 /// or a few `len()` method calls for variable-size objects (when compiling in release mode).
+///
+/// ```
+/// # use ordcode::{ params, SizeCalc };
+/// use serde::ser::{ Serialize, Serializer, SerializeTuple };
+///
+/// struct Point { x: u32, y: u32, label: &'static str }
+///
+/// impl Serialize for Point {
+///     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+///         let mut tup = serializer.serialize_tuple(3)?;
+///         tup.serialize_element(&self.x)?;
+///         tup.serialize_element(&self.y)?;
+///         tup.serialize_element(self.label)?;
+///         tup.end()
+///     }
+/// }
+///
+/// let mut sc = SizeCalc::<params::AscendingOrder>::new();
+/// Point { x: 1, y: 2, label: "abc" }.serialize(&mut sc).unwrap();
+/// assert_eq!(sc.finish(), 4 + 4 + 3 + 1);
+/// ```
 pub struct SizeCalc<P> {
-    size: usize,
+    head: usize,
+    tail: usize,
+    // inside of nested `Some` values, with `SerializerParams::PACKED_OPTIONS`
+    option_chain: bool,
     _marker: core::marker::PhantomData<P>,
 }
 
@@ -20,11 +49,14 @@ impl<P> SizeCalc<P>
 where
     P: SerializerParams,
 {
+    /// Create size calculator with zero size
     #[must_use]
     #[inline]
     pub fn new() -> Self {
         Self {
-            size: 0,
+            head: 0,
+            tail: 0,
+            option_chain: false,
             _marker: core::marker::PhantomData,
         }
     }
@@ -33,23 +65,82 @@ where
     #[inline]
     /// Returns calculated size
     pub fn size(&self) -> usize {
-        self.size
+        self.head + self.tail
+    }
+
+    #[must_use]
+    #[inline]
+    /// Consumes calculator, returns calculated size
+    pub fn finish(self) -> usize {
+        self.size()
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns calculated size of the head (data) section
+    pub fn head_size(&self) -> usize {
+        self.head
+    }
+
+    #[must_use]
+    #[inline]
+    /// Returns calculated size of the tail (sequence lengths) section
+    pub fn tail_size(&self) -> usize {
+        self.tail
     }
 
     // add serialized size of primitive type
     #[inline]
     fn add_ty<T>(&mut self) {
-        self.size += size_of::<T>();
+        self.head += size_of::<T>();
     }
 
     // add serialized length of sequence length or discriminant value
     #[inline]
     fn add_seq_len(&mut self, v: usize) {
-        self.size += P::SeqLenEncoder::calc_size(v);
+        self.add_encoded::<P::SeqLenEncoder>(v);
     }
     #[inline]
     fn add_discriminant_size(&mut self, v: u32) {
-        self.size += P::DiscriminantEncoder::calc_size(v);
+        self.add_encoded::<P::DiscriminantEncoder>(v);
+    }
+    #[inline]
+    fn add_encoded<E: LengthEncoder>(&mut self, v: E::Value) {
+        if E::TAIL {
+            self.tail += E::calc_size(v);
+        } else {
+            self.head += E::calc_size(v);
+        }
+    }
+    // add size of type tag for self-describing serialization
+    #[inline]
+    fn add_tag(&mut self) {
+        if P::SELF_DESCRIBING {
+            self.head += 1;
+        }
+        self.option_chain = false;
+    }
+    // packed options: single discriminant byte for all nested options
+    #[inline]
+    fn add_packed_option(&mut self, some: bool) {
+        if !self.option_chain {
+            self.add_ty::<u8>();
+        }
+        self.option_chain = some;
+    }
+    // tuples and structs are tagged sequences in self-describing serialization
+    #[inline]
+    fn add_tuple_header(&mut self, len: usize) {
+        if P::SELF_DESCRIBING {
+            self.add_tag();
+            self.add_seq_len(len);
+        }
+        self.option_chain = false;
+    }
+    #[inline]
+    fn add_variant_header(&mut self, v: u32) {
+        self.add_tag();
+        self.add_discriminant_size(v);
     }
 }
 
@@ -66,6 +157,7 @@ macro_rules! serialize_fn {
     ($fn:ident, $t:ty) => {
         #[inline]
         fn $fn(self, _v: $t) -> Result {
+            self.add_tag();
             self.add_ty::<$t>();
             Ok(())
         }
@@ -109,12 +201,26 @@ where
     }
     #[inline]
     fn serialize_bytes(self, v: &[u8]) -> Result {
+        if v.len() > P::MAX_STR_LEN {
+            return Err(Error::StringTooLong);
+        }
+        self.add_tag();
+        if P::ESCAPED_BYTES {
+            // zero bytes are escaped with two bytes, followed by two terminator bytes
+            self.head += v.len() + v.iter().map(|b| usize::from(*b == 0)).sum::<usize>() + 2;
+            return Ok(());
+        }
         self.add_seq_len(v.len());
-        self.size += v.len();
+        self.head += v.len();
         Ok(())
     }
     #[inline]
     fn serialize_none(self) -> Result {
+        if packed_options::<P>() {
+            self.add_packed_option(false);
+            return Ok(());
+        }
+        self.add_tag();
         self.add_ty::<u8>();
         Ok(())
     }
@@ -123,11 +229,17 @@ where
     where
         T: ?Sized + Serialize,
     {
+        if packed_options::<P>() {
+            self.add_packed_option(true);
+            return value.serialize(self);
+        }
+        self.add_tag();
         self.add_ty::<u8>();
         value.serialize(self)
     }
     #[inline]
     fn serialize_unit(self) -> Result {
+        self.add_tag();
         Ok(())
     }
 
@@ -142,7 +254,8 @@ where
         variant_index: u32,
         _variant: &'static str,
     ) -> Result {
-        self.add_discriminant_size(variant_index);
+        self.add_variant_header(variant_index);
+        self.add_tag();
         Ok(())
     }
     #[inline]
@@ -163,20 +276,22 @@ where
     where
         T: serde::ser::Serialize,
     {
-        self.add_discriminant_size(variant_index);
+        self.add_variant_header(variant_index);
         value.serialize(self)
     }
 
     #[inline]
-    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
+        self.add_tuple_header(len);
         Ok(SerializeCompound { ser: self })
     }
     #[inline]
     fn serialize_tuple_struct(
         self,
         _name: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<Self::SerializeTupleStruct> {
+        self.add_tuple_header(len);
         Ok(SerializeCompound { ser: self })
     }
     #[inline]
@@ -185,13 +300,15 @@ where
         _name: &'static str,
         variant_index: u32,
         _variant: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
-        self.add_discriminant_size(variant_index);
+        self.add_variant_header(variant_index);
+        self.add_tuple_header(len);
         Ok(SerializeCompound { ser: self })
     }
     #[inline]
-    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+        self.add_tuple_header(len);
         Ok(SerializeCompound { ser: self })
     }
     #[inline]
@@ -200,20 +317,24 @@ where
         _name: &'static str,
         variant_index: u32,
         _variant: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<Self::SerializeStructVariant> {
-        self.add_discriminant_size(variant_index);
+        self.add_variant_header(variant_index);
+        self.add_tuple_header(len);
         Ok(SerializeCompound { ser: self })
     }
     #[inline]
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
         let len = len.ok_or(Error::SerializeSequenceMustHaveLength)?;
+        self.add_tag();
         self.add_seq_len(len);
         Ok(SerializeCompound { ser: self })
     }
     #[inline]
     fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
-        let len = len.ok_or(Error::SerializeSequenceMustHaveLength)?;
+        // serde derive serializes structs with flattened fields as maps of unknown length
+        let len = len.ok_or(Error::FlattenNotSupported)?;
+        self.add_tag();
         self.add_seq_len(len);
         Ok(SerializeCompound { ser: self })
     }
@@ -312,3 +433,142 @@ where
         Ok(())
     }
 }
+
+/// Types which have constant serialized size, regardless of value
+///
+/// [`calc_size_const()`](crate::calc_size_const) returns [`ConstSize::SIZE`] for such types
+/// without traversing the value. Implemented for primitive types, and for tuples and arrays
+/// of `ConstSize` types. Implement it for your own fixed-size structs as the sum of field sizes:
+/// ```
+/// # use ordcode::ConstSize;
+/// #[derive(serde_derive::Serialize)]
+/// struct Point { x: u32, y: u32 }
+///
+/// impl ConstSize for Point {
+///     const SIZE: usize = u32::SIZE + u32::SIZE;
+/// }
+/// ```
+/// or with [`impl_const_size!`](crate::impl_const_size) macro. `SIZE` is a constant, so it can
+/// be used as length of stack buffers: `[u8; Point::SIZE]`.
+pub trait ConstSize: Serialize {
+    /// Serialized size, not counting type tags of self-describing serialization
+    const SIZE: usize;
+}
+
+macro_rules! const_size {
+    ($($t:ty),*) => {
+        $(
+        impl ConstSize for $t {
+            const SIZE: usize = size_of::<$t>();
+        }
+        )*
+    };
+}
+
+const_size!(
+    bool,
+    u8,
+    u16,
+    u32,
+    u64,
+    i8,
+    i16,
+    i32,
+    i64,
+    f32,
+    f64,
+    char,
+    ()
+);
+#[cfg(not(no_i128))]
+const_size!(u128, i128);
+
+macro_rules! const_size_tuple {
+    ($($t:ident),+) => {
+        impl<$($t: ConstSize),+> ConstSize for ($($t,)+) {
+            const SIZE: usize = 0 $(+ $t::SIZE)+;
+        }
+    };
+}
+
+const_size_tuple!(T0);
+const_size_tuple!(T0, T1);
+const_size_tuple!(T0, T1, T2);
+const_size_tuple!(T0, T1, T2, T3);
+const_size_tuple!(T0, T1, T2, T3, T4);
+const_size_tuple!(T0, T1, T2, T3, T4, T5);
+const_size_tuple!(T0, T1, T2, T3, T4, T5, T6);
+const_size_tuple!(T0, T1, T2, T3, T4, T5, T6, T7);
+const_size_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8);
+const_size_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9);
+const_size_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+const_size_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
+
+impl ConstSize for crate::primitives::NonNanF64 {
+    const SIZE: usize = size_of::<f64>();
+}
+
+impl<T: ConstSize, const N: usize> ConstSize for [T; N]
+where
+    [T; N]: Serialize,
+{
+    const SIZE: usize = T::SIZE * N;
+}
+
+/// Implement [`ConstSize`] for a struct as the sum of sizes of its fields
+///
+/// Field list is the same as in struct declaration. Fails to compile if any field type is
+/// not `ConstSize`, e.g. has variable size, or if field list does not match the struct:
+/// for structs with named fields, all fields must be listed with their types, in any order,
+/// and for tuple structs, all field types must be listed in declaration order.
+/// ```
+/// # use ordcode::{ impl_const_size, ConstSize, calc_size_asc };
+/// #[derive(serde_derive::Serialize)]
+/// struct Point { x: u32, y: (u16, u16) }
+/// impl_const_size!(Point { x: u32, y: (u16, u16) });
+///
+/// #[derive(serde_derive::Serialize)]
+/// struct Id(u64, [u8; 4]);
+/// impl_const_size!(Id(u64, [u8; 4]));
+///
+/// let buf = [0_u8; Point::SIZE + Id::SIZE];
+/// assert_eq!(buf.len(), 20);
+/// assert_eq!(calc_size_asc(&Id(1, [2; 4])).unwrap(), Id::SIZE);
+/// ```
+/// Missing field is a compile error:
+/// ```compile_fail
+/// # use ordcode::impl_const_size;
+/// #[derive(serde_derive::Serialize)]
+/// struct Point { x: u32, y: u32, z: u32 }
+/// impl_const_size!(Point { x: u32, y: u32 });
+/// ```
+/// So is a field with different type:
+/// ```compile_fail
+/// # use ordcode::impl_const_size;
+/// #[derive(serde_derive::Serialize)]
+/// struct Id(u64, u32);
+/// impl_const_size!(Id(u64, u16));
+/// ```
+#[macro_export]
+macro_rules! impl_const_size {
+    ($name:ident { $($field:ident : $t:ty),* $(,)? }) => {
+        impl $crate::ConstSize for $name {
+            const SIZE: usize = 0 $(+ <$t as $crate::ConstSize>::SIZE)*;
+        }
+        // check that fields and their types match struct declaration
+        const _: () = {
+            #[allow(dead_code)]
+            fn check_fields(v: $name) {
+                let $name { $($field),* } = v;
+                $(let _: $t = $field;)*
+            }
+        };
+    };
+    ($name:ident ( $($t:ty),* $(,)? )) => {
+        impl $crate::ConstSize for $name {
+            const SIZE: usize = 0 $(+ <$t as $crate::ConstSize>::SIZE)*;
+        }
+        // check that field types match struct declaration
+        const _: fn($($t),*) -> $name = $name;
+    };
+}
@@ -0,0 +1,185 @@
+//! Helpers for validating custom serialization parameters in tests
+//!
+//! Available with `testing` feature. It is easy to implement
+//! [`SerializerParams`](crate::params::SerializerParams) with a combination of parameters
+//! which silently breaks lexicographic ordering of serialized data (for example, little-endian
+//! integers with ascending order); these helpers check ordering on a set of sample values.
+//!
+//! *Example*
+//! ```
+//! # use ordcode::{ params, testing::assert_order_preserving };
+//! assert_order_preserving::<_, params::AscendingOrder>(&[(0_u16, "b"), (1, "a"), (1, "ab")]);
+//! ```
+//!
+//! [`roundtrip()`] and [`check_ordering()`] run the same checks for all parameter presets,
+//! and may be used in property tests of custom key types (e.g. with `proptest` or `arbitrary`).
+//! ```
+//! # use ordcode::testing::{ check_ordering, roundtrip };
+//! roundtrip(&(1_u8, "a".to_string(), vec![Some(-1_i64)]));
+//! check_ordering(&[(0_u16, "b"), (1, "a"), (1, "ab")]);
+//! ```
+use crate::{
+    buf::VecWriter,
+    calc_size, de_from_bytes_ordered_exact,
+    params::{
+        AscendingOrder, NativeBinary, Order, PortableBinary, SelfDescribing, SerializerParams,
+    },
+    ser_to_vec_ordered, DeBytesReader, Deserializer, Serializer,
+};
+use core::fmt::Debug;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Assert that ordering of serialized samples is the same as ordering of sample values
+///
+/// Samples are serialized with default value of `P`, serialized data is compared byte-wise
+/// for every pair of samples, so the check is quadratic in number of samples. Pairs of samples
+/// which are not comparable (e.g. with `NaN` floats) are skipped. If `P::ORDER` is
+/// [`Order::Descending`], serialized data must be ordered in reverse; for [`Order::Unordered`]
+/// only serialization of samples is checked.
+///
+/// # Panics
+///
+/// Panics if a sample can't be serialized, or if ordering of serialized data does not match
+/// ordering of any pair of samples.
+pub fn assert_order_preserving<T, P>(samples: &[T])
+where
+    T: Serialize + PartialOrd + Debug,
+    P: SerializerParams + Default,
+{
+    let encoded: Vec<Vec<u8>> = samples.iter().map(|s| encode(s, P::default())).collect();
+    for (i, (a, a_enc)) in samples.iter().zip(&encoded).enumerate() {
+        for (b, b_enc) in samples.iter().zip(&encoded).skip(i + 1) {
+            if let Some(ord) = a.partial_cmp(b) {
+                let expected = match P::ORDER {
+                    Order::Ascending => ord,
+                    Order::Descending => ord.reverse(),
+                    Order::Unordered => return,
+                };
+                let actual = a_enc.cmp(b_enc);
+                assert!(
+                    actual == expected,
+                    "ordering not preserved: {:?} is {:?} to {:?}, \
+                     but serialized {:?} is {:?} to {:?}",
+                    a,
+                    ord,
+                    b,
+                    a_enc,
+                    actual,
+                    b_enc
+                );
+            }
+        }
+    }
+}
+
+/// Assert that `value` is deserialized back unchanged
+///
+/// Checks ascending and descending order-preserving serialization, and
+/// [`PortableBinary`], [`NativeBinary`] and [`SelfDescribing`] presets. For each of them,
+/// serialized size must be equal to [`calc_size()`], and deserialization must consume
+/// all serialized data.
+///
+/// # Panics
+///
+/// Panics if value can't be serialized or deserialized, or if deserialized value is different.
+pub fn roundtrip<T>(value: &T)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    roundtrip_with(value, AscendingOrder);
+    roundtrip_with(value, PortableBinary);
+    roundtrip_with(value, NativeBinary);
+    roundtrip_with(value, SelfDescribing);
+
+    let mut buf = ser_to_vec_ordered(value, Order::Descending)
+        .unwrap_or_else(|e| panic!("serialization of {:?} failed: {}", value, e));
+    let decoded: T = de_from_bytes_ordered_exact(&mut buf, Order::Descending)
+        .unwrap_or_else(|e| panic!("deserialization of {:?} failed: {}", value, e));
+    assert_roundtrip(value, &decoded);
+}
+
+/// Assert that serialized `sorted` values are ordered in the same way as values
+///
+/// `sorted` must be sorted in ascending order. Serialized data of each value is compared to
+/// the next one, for ascending and descending order-preserving serialization: it must be
+/// equal for equal values, and greater (or less, for descending order) otherwise.
+///
+/// # Panics
+///
+/// Panics if a value can't be serialized, or if ordering of serialized data does not match.
+pub fn check_ordering<T>(sorted: &[T])
+where
+    T: Serialize + PartialOrd + Debug,
+{
+    for order in &[Order::Ascending, Order::Descending] {
+        let encoded: Vec<Vec<u8>> = sorted
+            .iter()
+            .map(|v| {
+                ser_to_vec_ordered(v, *order)
+                    .unwrap_or_else(|e| panic!("serialization of {:?} failed: {}", v, e))
+            })
+            .collect();
+        for (v, enc) in sorted.windows(2).zip(encoded.windows(2)) {
+            let ord = v[0]
+                .partial_cmp(&v[1])
+                .filter(|ord| ord.is_le())
+                .unwrap_or_else(|| panic!("values are not sorted: {:?}, {:?}", v[0], v[1]));
+            let expected = match order {
+                Order::Descending => ord.reverse(),
+                _ => ord,
+            };
+            let actual = enc[0].cmp(&enc[1]);
+            assert!(
+                actual == expected,
+                "ordering not preserved: {:?} is {:?} to {:?}, \
+                 but serialized {:?} is {:?} to {:?}",
+                v[0],
+                ord,
+                v[1],
+                enc[0],
+                actual,
+                enc[1]
+            );
+        }
+    }
+}
+
+// serialize value with params, panic on failure
+fn encode<T, P>(value: &T, params: P) -> Vec<u8>
+where
+    T: Serialize + Debug,
+    P: SerializerParams,
+{
+    let mut writer = VecWriter::new();
+    let mut ser = Serializer::new(&mut writer, params);
+    if let Err(e) = value.serialize(&mut ser) {
+        panic!("serialization of {:?} failed: {}", value, e);
+    }
+    writer.finalize()
+}
+
+fn roundtrip_with<T, P>(value: &T, params: P)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+    P: SerializerParams + Copy,
+{
+    let buf = encode(value, params);
+    match calc_size(value, params) {
+        Ok(size) => assert_eq!(size, buf.len(), "calculated size mismatch for {value:?}"),
+        Err(e) => panic!("size calculation of {:?} failed: {}", value, e),
+    }
+    let mut reader = DeBytesReader::new(&buf);
+    let decoded = T::deserialize(&mut Deserializer::new(&mut reader, params))
+        .unwrap_or_else(|e| panic!("deserialization of {:?} failed: {}", value, e));
+    assert_eq!(reader.remaining(), 0, "trailing bytes after {value:?}");
+    assert_roundtrip(value, &decoded);
+}
+
+fn assert_roundtrip<T: PartialEq + Debug>(value: &T, decoded: &T) {
+    assert!(
+        value == decoded,
+        "roundtrip failed: {:?} deserialized as {:?}",
+        value,
+        decoded
+    );
+}
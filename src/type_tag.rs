@@ -0,0 +1,31 @@
+// Type tags written before each value for self-describing serialization,
+// see `params::SerializerParams::SELF_DESCRIBING`.
+//
+// Tags are constant for values of the same type, so they do not affect lexicographic ordering
+// of serialized values of the same type.
+
+pub const UNIT: u8 = 0;
+pub const BOOL: u8 = 1;
+pub const U8: u8 = 2;
+pub const U16: u8 = 3;
+pub const U32: u8 = 4;
+pub const U64: u8 = 5;
+pub const U128: u8 = 6;
+pub const I8: u8 = 7;
+pub const I16: u8 = 8;
+pub const I32: u8 = 9;
+pub const I64: u8 = 10;
+pub const I128: u8 = 11;
+pub const F32: u8 = 12;
+pub const F64: u8 = 13;
+pub const CHAR: u8 = 14;
+pub const STR: u8 = 15;
+pub const BYTES: u8 = 16;
+// followed by option discriminant byte, same as in non-self-describing encoding
+pub const OPTION: u8 = 17;
+// followed by sequence length and elements; also used for tuples and structs
+pub const SEQ: u8 = 18;
+// followed by sequence length and key-value pairs
+pub const MAP: u8 = 19;
+// followed by discriminant and variant value (`UNIT` for unit variants)
+pub const ENUM: u8 = 20;
@@ -2,14 +2,24 @@
 //! Fast variable length serialization of unsigned integers with [`VarUInt`] trait.
 //!
 //! This trait is implemented by this crate for [`u32`], [`u64`] integer types.
+//! Signed integers can be encoded with zigzag encoding, see [`encode_zigzag_i64`].
+//! `u128` values can be encoded with [`encode_u128`].
 use crate::{
-    buf::{ReadBytes, ReadFromTail, TailReadBytes, TailWriteBytes, WriteBytes, WriteToTail},
+    buf::{
+        DeBytesReader, ReadBytes, ReadFromTail, TailReadBytes, TailWriteBytes, WriteBytes,
+        WriteToTail,
+    },
     params::LengthEncoder,
     Error, Result,
 };
+#[cfg(not(no_i128))]
+use core::convert::TryFrom;
 
 /// Methods for variable length serializaiton of unsigned integers
 pub trait VarUInt: Sized {
+    /// Maximum length of varint-encoded value in bytes
+    const VARU_MAX_LEN: usize;
+
     /// Get the length of an varint-encoded value in bytes
     fn varu_encoded_len(&self) -> u8;
 
@@ -22,6 +32,20 @@ pub trait VarUInt: Sized {
     /// Read variable length integer from `reader`
     fn varu_from_reader(reader: impl ReadBytes) -> Result<Self>;
 
+    /// Read variable length integer from `reader`, rejecting non-canonical encodings
+    ///
+    /// Returns [`Error::InvalidVarintEncoding`] if value is not encoded with the minimal number
+    /// of bytes, so each value has exactly one accepted encoding.
+    fn varu_from_reader_strict(mut reader: impl ReadBytes) -> Result<Self> {
+        let len = reader.peek(1, |buf| Ok(Self::varu_decoded_len(buf[0])))?;
+        let value = Self::varu_from_reader(reader)?;
+        if value.varu_encoded_len() == len {
+            Ok(value)
+        } else {
+            Err(Error::InvalidVarintEncoding)
+        }
+    }
+
     /// Decode variable length integer from slice
     fn varu_from_slice(bytes: &[u8]) -> Result<(Self, u8)>;
 
@@ -33,6 +57,8 @@ pub trait VarUInt: Sized {
 }
 
 impl VarUInt for u64 {
+    const VARU_MAX_LEN: usize = 9;
+
     #[inline]
     fn varu_encoded_len(&self) -> u8 {
         // indexing const array is twice as fast as 'match' in release mode
@@ -50,18 +76,18 @@ impl VarUInt for u64 {
     }
     #[inline]
     fn varu_to_writer(&self, mut writer: impl WriteBytes) -> Result {
-        let mut bytes = [0_u8; 9];
+        let mut bytes = [0_u8; Self::VARU_MAX_LEN];
         let length = self.varu_to_slice(&mut bytes);
         writer.write(&[bytes[0]])?;
         writer.write(&bytes[1..length as usize])
     }
     #[inline]
-    fn varu_from_reader(mut reader: impl ReadBytes) -> Result<Self> {
-        let (first_byte, varu_decoded_len) =
-            reader.read(1, |buf| Ok((buf[0], Self::varu_decoded_len(buf[0]))))?;
-        reader.read((varu_decoded_len - 1) as usize, |buf| {
-            varu64_decode(varu_decoded_len, first_byte, buf)
-        })
+    fn varu_from_reader(reader: impl ReadBytes) -> Result<Self> {
+        varu64_from_reader(reader, false)
+    }
+    #[inline]
+    fn varu_from_reader_strict(reader: impl ReadBytes) -> Result<Self> {
+        varu64_from_reader(reader, true)
     }
     #[inline]
     fn varu_from_slice(bytes: &[u8]) -> Result<(Self, u8)> {
@@ -70,7 +96,7 @@ impl VarUInt for u64 {
         }
         let varu_decoded_len = Self::varu_decoded_len(bytes[0]);
         Ok((
-            varu64_decode(varu_decoded_len, bytes[0], &bytes[1..])?,
+            varu64_decode(varu_decoded_len, bytes[0], &bytes[1..], false)?,
             varu_decoded_len,
         ))
     }
@@ -89,6 +115,8 @@ impl VarUInt for u64 {
 }
 
 impl VarUInt for u32 {
+    const VARU_MAX_LEN: usize = 5;
+
     #[inline]
     fn varu_encoded_len(&self) -> u8 {
         const LENGTHS: [u8; 33] = [
@@ -103,22 +131,18 @@ impl VarUInt for u32 {
     }
     #[inline]
     fn varu_to_writer(&self, mut writer: impl WriteBytes) -> Result {
-        let mut bytes = [0_u8; 5];
+        let mut bytes = [0_u8; Self::VARU_MAX_LEN];
         let length = self.varu_to_slice(&mut bytes);
         writer.write(&[bytes[0]])?;
         writer.write(&bytes[1..length as usize])
     }
     #[inline]
-    fn varu_from_reader(mut reader: impl ReadBytes) -> Result<Self> {
-        let (first_byte, varu_decoded_len) =
-            reader.read(1, |buf| Ok((buf[0], Self::varu_decoded_len(buf[0]))))?;
-        if varu_decoded_len <= 5 {
-            reader.read((varu_decoded_len - 1) as usize, |buf| {
-                varu32_decode(varu_decoded_len, first_byte, buf)
-            })
-        } else {
-            Err(Error::InvalidVarintEncoding)
-        }
+    fn varu_from_reader(reader: impl ReadBytes) -> Result<Self> {
+        varu32_from_reader(reader, false)
+    }
+    #[inline]
+    fn varu_from_reader_strict(reader: impl ReadBytes) -> Result<Self> {
+        varu32_from_reader(reader, true)
     }
     #[inline]
     fn varu_from_slice(bytes: &[u8]) -> Result<(Self, u8)> {
@@ -128,7 +152,7 @@ impl VarUInt for u32 {
         let varu_decoded_len = Self::varu_decoded_len(bytes[0]);
         if varu_decoded_len <= 5 {
             Ok((
-                varu32_decode(varu_decoded_len, bytes[0], &bytes[1..])?,
+                varu32_decode(varu_decoded_len, bytes[0], &bytes[1..], false)?,
                 varu_decoded_len,
             ))
         } else {
@@ -153,7 +177,12 @@ impl VarUInt for u32 {
 // Decode variable length bytes into `u64`, when decoded length is known
 // from previous call of `varu64_varu_decoded_len()`
 #[inline]
-fn varu64_decode(varu_encoded_length: u8, first_byte: u8, bytes: &[u8]) -> Result<u64> {
+fn varu64_decode(
+    varu_encoded_length: u8,
+    first_byte: u8,
+    bytes: &[u8],
+    strict: bool,
+) -> Result<u64> {
     if bytes.len() + 1 < varu_encoded_length as usize {
         return Err(Error::PrematureEndOfInput);
     }
@@ -172,13 +201,22 @@ fn varu64_decode(varu_encoded_length: u8, first_byte: u8, bytes: &[u8]) -> Resul
     if !(varu_encoded_length == 1 || result >= (1 << (7 * (varu_encoded_length - 1)))) {
         return Err(Error::InvalidVarintEncoding);
     }
+    // value must not fit into shorter encoding
+    if strict && !(varu_encoded_length == 1 || result >= (1 << (7 * (varu_encoded_length - 1)))) {
+        return Err(Error::InvalidVarintEncoding);
+    }
     Ok(result)
 }
 
 // Decode variable length bytes into `u32`, when decoded length is known
 // from previous call of `varu_varu_decoded_len()`. `varu_encoded_length` must be less or equal to 5.
 #[inline]
-fn varu32_decode(varu_encoded_length: u8, first_byte: u8, bytes: &[u8]) -> Result<u32> {
+fn varu32_decode(
+    varu_encoded_length: u8,
+    first_byte: u8,
+    bytes: &[u8],
+    strict: bool,
+) -> Result<u32> {
     if bytes.len() + 1 < varu_encoded_length as usize {
         return Err(Error::PrematureEndOfInput);
     }
@@ -197,9 +235,150 @@ fn varu32_decode(varu_encoded_length: u8, first_byte: u8, bytes: &[u8]) -> Resul
     if !(varu_encoded_length == 1 || result >= (1 << (7 * (varu_encoded_length - 1)))) {
         return Err(Error::InvalidVarintEncoding);
     }
+    // value must not fit into shorter encoding, unused bits of 5-byte encoding must be set
+    if strict
+        && (!(varu_encoded_length == 1 || result >= (1 << (7 * (varu_encoded_length - 1))))
+            || (varu_encoded_length == 5 && first_byte != 0xf0))
+    {
+        return Err(Error::InvalidVarintEncoding);
+    }
     Ok(result)
 }
 
+// Read `u64` varint, checking that encoding is minimal if `strict` is set
+#[inline]
+fn varu64_from_reader(mut reader: impl ReadBytes, strict: bool) -> Result<u64> {
+    let (first_byte, varu_decoded_len) =
+        reader.read(1, |buf| Ok((buf[0], <u64>::varu_decoded_len(buf[0]))))?;
+    reader.read((varu_decoded_len - 1) as usize, |buf| {
+        varu64_decode(varu_decoded_len, first_byte, buf, strict)
+    })
+}
+
+// Read `u32` varint, checking that encoding is minimal if `strict` is set
+#[inline]
+fn varu32_from_reader(mut reader: impl ReadBytes, strict: bool) -> Result<u32> {
+    let (first_byte, varu_decoded_len) = reader.read(1, |buf| {
+        let varu_decoded_len = <u32>::varu_decoded_len(buf[0]);
+        if varu_decoded_len <= 5 {
+            Ok((buf[0], varu_decoded_len))
+        } else {
+            Err(Error::InvalidVarintEncoding)
+        }
+    })?;
+    reader.read((varu_decoded_len - 1) as usize, |buf| {
+        varu32_decode(varu_decoded_len, first_byte, buf, strict)
+    })
+}
+
+/// Encode signed integer as zigzag variable length integer to `writer`
+///
+/// Small absolute values are encoded compactly regardless of the sign, which is useful for
+/// signed deltas in value payloads. **Zigzag encoding does not preserve lexicographic ordering**,
+/// do not use it for keys.
+#[inline]
+#[allow(clippy::cast_sign_loss)]
+pub fn encode_zigzag_i64(value: i64, writer: impl WriteBytes) -> Result {
+    (((value << 1) ^ (value >> 63)) as u64).varu_to_writer(writer)
+}
+
+/// Decode signed integer encoded with [`encode_zigzag_i64`] from `reader`
+#[inline]
+#[allow(clippy::cast_possible_wrap)]
+pub fn decode_zigzag_i64(reader: impl ReadBytes) -> Result<i64> {
+    let v = <u64>::varu_from_reader(reader)?;
+    Ok((v >> 1) as i64 ^ -((v & 1) as i64))
+}
+
+/// Encode signed integer as zigzag variable length integer to `writer`
+///
+/// Same as [`encode_zigzag_i64`], for `i32`. Does not preserve lexicographic ordering.
+#[inline]
+#[allow(clippy::cast_sign_loss)]
+pub fn encode_zigzag_i32(value: i32, writer: impl WriteBytes) -> Result {
+    (((value << 1) ^ (value >> 31)) as u32).varu_to_writer(writer)
+}
+
+/// Decode signed integer encoded with [`encode_zigzag_i32`] from `reader`
+#[inline]
+#[allow(clippy::cast_possible_wrap)]
+pub fn decode_zigzag_i32(reader: impl ReadBytes) -> Result<i32> {
+    let v = <u32>::varu_from_reader(reader)?;
+    Ok((v >> 1) as i32 ^ -((v & 1) as i32))
+}
+
+/// Read variable length integer from the tail of `reader`, without advancing the reader
+///
+/// When deserializing a sequence or a map with [`VarIntTailLenEncoder`], its length is the next
+/// value in the tail, so this can be used to size a collection before deserializing its elements.
+/// ```
+/// # use ordcode::{ DeBytesReader, varint::peek_len, ser_to_vec_ordered, Order };
+/// let buf = ser_to_vec_ordered(&vec![1_u8, 2, 3], Order::Ascending).unwrap();
+/// let reader = DeBytesReader::new(&buf);
+/// assert_eq!(peek_len(&reader).unwrap(), 3);
+/// assert_eq!(reader.remaining(), buf.len());
+/// ```
+pub fn peek_len(reader: &DeBytesReader<'_>) -> Result<u64> {
+    <u64>::varu_from_reader(ReadFromTail(&mut reader.clone()))
+}
+
+/// Encode `u128` as variable length integer to `writer`
+///
+/// Values below 2<sup>56</sup> are encoded same as [`VarUInt`] `u64` values, in up to 8 bytes.
+/// Larger values are encoded as zero byte, followed by number of value bytes `n` (8 to 16) and
+/// `n` value bytes in little-endian order, which takes up to 18 bytes. Note that unlike `u32` and `u64`
+/// encodings, 9-byte `u64` encodings can't be decoded with [`decode_u128()`].
+/// ```
+/// # use ordcode::{ varint::{ encode_u128, decode_u128 }, DeBytesReader };
+/// let mut buf = Vec::new();
+/// encode_u128(u128::from(u64::MAX) + 1, &mut buf).unwrap();
+/// assert_eq!(buf, [0, 9, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+/// assert_eq!(decode_u128(DeBytesReader::new(&buf)).unwrap(), u128::from(u64::MAX) + 1);
+/// ```
+#[cfg(not(no_i128))]
+pub fn encode_u128(value: u128, mut writer: impl WriteBytes) -> Result {
+    if let Ok(v) = u64::try_from(value) {
+        if v < 1 << 56 {
+            return v.varu_to_writer(writer);
+        }
+    }
+    let n = 16 - value.leading_zeros() / 8;
+    #[allow(clippy::cast_possible_truncation)]
+    writer.write(&[0, n as u8])?;
+    writer.write(&value.to_le_bytes()[..n as usize])
+}
+
+/// Decode `u128` encoded with [`encode_u128()`] from `reader`
+#[cfg(not(no_i128))]
+pub fn decode_u128(mut reader: impl ReadBytes) -> Result<u128> {
+    if reader.peek(1, |buf| Ok(buf[0]))? != 0 {
+        return u64::varu_from_reader(reader).map(u128::from);
+    }
+    let n = reader.read(2, |buf| Ok(usize::from(buf[1])))?;
+    if !(8..=16).contains(&n) {
+        return Err(Error::InvalidVarintEncoding);
+    }
+    reader.read(n, |buf| {
+        let mut bytes = [0_u8; 16];
+        bytes[..n].copy_from_slice(buf);
+        Ok(u128::from_le_bytes(bytes))
+    })
+}
+
+/// Length of varint-encoded `value` in bytes
+///
+/// Same as [`VarUInt::varu_encoded_len()`], as `usize` for buffer size calculations.
+/// ```
+/// # use ordcode::varint::encoded_len;
+/// assert_eq!(encoded_len(127), 1);
+/// assert_eq!(encoded_len(128), 2);
+/// assert_eq!(encoded_len(u64::MAX), 9);
+/// ```
+#[must_use]
+pub fn encoded_len(value: u64) -> usize {
+    usize::from(value.varu_encoded_len())
+}
+
 // Note the 32 and 64 bit versions below are binary compatible: 64-bit version can read
 // data written by 32-bit encoder, but not vice versa
 
@@ -209,6 +388,7 @@ pub struct VarIntTailLenEncoder;
 #[cfg(target_pointer_width = "64")]
 impl LengthEncoder for VarIntTailLenEncoder {
     type Value = usize;
+    const TAIL: bool = true;
 
     #[inline]
     fn calc_size(value: Self::Value) -> usize {
@@ -220,6 +400,11 @@ impl LengthEncoder for VarIntTailLenEncoder {
         <u64>::varu_from_reader(ReadFromTail(&mut reader)).map(|v| v as usize)
     }
     #[inline]
+    #[allow(clippy::cast_possible_truncation)] // can't happen because of cfg
+    fn read_strict(mut reader: impl TailReadBytes) -> Result<usize> {
+        <u64>::varu_from_reader_strict(ReadFromTail(&mut reader)).map(|v| v as usize)
+    }
+    #[inline]
     fn write(mut writer: impl TailWriteBytes, value: usize) -> Result {
         (value as u64).varu_to_writer(WriteToTail(&mut writer))
     }
@@ -229,6 +414,7 @@ impl LengthEncoder for VarIntTailLenEncoder {
 #[allow(clippy::cast_possible_truncation)] // can't happen because of cfg
 impl LengthEncoder for VarIntTailLenEncoder {
     type Value = usize;
+    const TAIL: bool = true;
 
     #[inline]
     fn calc_size(value: Self::Value) -> usize {
@@ -240,6 +426,10 @@ impl LengthEncoder for VarIntTailLenEncoder {
         <u32>::varu_from_reader(ReadFromTail(&mut reader)).map(|v| v as usize)
     }
     #[inline]
+    fn read_strict(mut reader: impl TailReadBytes) -> Result<usize> {
+        <u32>::varu_from_reader_strict(ReadFromTail(&mut reader)).map(|v| v as usize)
+    }
+    #[inline]
     fn write(mut writer: impl TailWriteBytes, value: usize) -> Result {
         (value as u32).varu_to_writer(WriteToTail(&mut writer))
     }
@@ -263,6 +453,11 @@ impl LengthEncoder for VarIntLenEncoder {
         <u64>::varu_from_reader(&mut reader).map(|v| v as usize)
     }
     #[inline]
+    #[allow(clippy::cast_possible_truncation)] // can't happen because of cfg
+    fn read_strict(mut reader: impl TailReadBytes) -> Result<usize> {
+        <u64>::varu_from_reader_strict(&mut reader).map(|v| v as usize)
+    }
+    #[inline]
     fn write(mut writer: impl TailWriteBytes, value: usize) -> Result {
         (value as u64).varu_to_writer(&mut writer)
     }
@@ -283,6 +478,10 @@ impl LengthEncoder for VarIntLenEncoder {
         <u32>::varu_from_reader(&mut reader).map(|v| v as usize)
     }
     #[inline]
+    fn read_strict(mut reader: impl TailReadBytes) -> Result<usize> {
+        <u32>::varu_from_reader_strict(&mut reader).map(|v| v as usize)
+    }
+    #[inline]
     fn write(mut writer: impl TailWriteBytes, value: usize) -> Result {
         (value as u32).varu_to_writer(&mut writer)
     }
@@ -303,6 +502,10 @@ impl LengthEncoder for VarIntDiscrEncoder {
         <u32>::varu_from_reader(reader)
     }
     #[inline]
+    fn read_strict(reader: impl TailReadBytes) -> Result<Self::Value> {
+        <u32>::varu_from_reader_strict(reader)
+    }
+    #[inline]
     fn write(writer: impl TailWriteBytes, value: Self::Value) -> Result {
         value.varu_to_writer(writer)
     }
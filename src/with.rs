@@ -0,0 +1,112 @@
+//! Modules for `#[serde(with = "...")]` attribute, which give ordered encodings for common types
+//!
+//! Each module has `serialize` and `deserialize` functions, so fields of foreign types can be
+//! serialized without wrapping them into [`DurationKey`], [`IpAddrKey`] etc.
+//!
+//! *Example*
+//! ```
+//! # use ordcode::{ Order, ser_to_vec_ordered, de_from_bytes_asc };
+//! # use std::{ net::IpAddr, time::Duration };
+//! #[derive(serde_derive::Serialize, serde_derive::Deserialize, Debug, PartialEq)]
+//! struct Event {
+//!     #[serde(with = "ordcode::with::ip_addr")]
+//!     source: IpAddr,
+//!     #[serde(with = "ordcode::with::duration_nanos")]
+//!     elapsed: Duration,
+//! }
+//! let event = Event { source: "10.0.0.1".parse().unwrap(), elapsed: Duration::from_millis(5) };
+//! let buf = ser_to_vec_ordered(&event, Order::Ascending).unwrap();
+//! assert_eq!(buf, [0, 10, 0, 0, 1, 0, 0, 0, 0, 0, 0x4c, 0x4b, 0x40]);
+//! assert_eq!(de_from_bytes_asc::<Event>(&buf).unwrap(), event);
+//! ```
+//!
+//! Enums with pinned discriminants, independent of declaration order of variants, are serialized
+//! with [`explicit_discriminant`].
+//!
+//! [`DurationKey`]: crate::primitives::DurationKey
+//! [`IpAddrKey`]: crate::primitives::IpAddrKey
+
+pub mod explicit_discriminant;
+
+/// `Duration` as whole seconds followed by subsecond nanoseconds, same as
+/// [`DurationKey`](crate::primitives::DurationKey)
+pub mod duration {
+    use crate::primitives::DurationKey;
+    use core::time::Duration;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        DurationKey(*value).serialize(serializer)
+    }
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        DurationKey::deserialize(deserializer).map(|k| k.0)
+    }
+}
+
+/// `Duration` as total number of nanoseconds (`u64`)
+///
+/// More compact than [`duration`], but limited to durations below 584 years;
+/// serializing longer duration returns [`Error::IntegerOverflow`](crate::Error::IntegerOverflow).
+pub mod duration_nanos {
+    use crate::Error;
+    use core::time::Duration;
+    use serde::{ser, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        value
+            .as_secs()
+            .checked_mul(1_000_000_000)
+            .and_then(|n| n.checked_add(u64::from(value.subsec_nanos())))
+            .ok_or_else(|| ser::Error::custom(Error::IntegerOverflow))?
+            .serialize(serializer)
+    }
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_nanos)
+    }
+}
+
+/// `IpAddr` as discriminant byte and address octets, same as
+/// [`IpAddrKey`](crate::primitives::IpAddrKey)
+#[cfg(feature = "std")]
+pub mod ip_addr {
+    use crate::primitives::IpAddrKey;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::net::IpAddr;
+
+    pub fn serialize<S: Serializer>(value: &IpAddr, serializer: S) -> Result<S::Ok, S::Error> {
+        IpAddrKey(*value).serialize(serializer)
+    }
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<IpAddr, D::Error> {
+        IpAddrKey::deserialize(deserializer).map(|k| k.0)
+    }
+}
+
+/// `Ipv4Addr` as address octets, same as [`Ipv4AddrKey`](crate::primitives::Ipv4AddrKey)
+#[cfg(feature = "std")]
+pub mod ipv4_addr {
+    use crate::primitives::Ipv4AddrKey;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::net::Ipv4Addr;
+
+    pub fn serialize<S: Serializer>(value: &Ipv4Addr, serializer: S) -> Result<S::Ok, S::Error> {
+        Ipv4AddrKey(*value).serialize(serializer)
+    }
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Ipv4Addr, D::Error> {
+        Ipv4AddrKey::deserialize(deserializer).map(|k| k.0)
+    }
+}
+
+/// `Ipv6Addr` as address octets, same as [`Ipv6AddrKey`](crate::primitives::Ipv6AddrKey)
+#[cfg(feature = "std")]
+pub mod ipv6_addr {
+    use crate::primitives::Ipv6AddrKey;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::net::Ipv6Addr;
+
+    pub fn serialize<S: Serializer>(value: &Ipv6Addr, serializer: S) -> Result<S::Ok, S::Error> {
+        Ipv6AddrKey(*value).serialize(serializer)
+    }
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Ipv6Addr, D::Error> {
+        Ipv6AddrKey::deserialize(deserializer).map(|k| k.0)
+    }
+}
@@ -0,0 +1,357 @@
+//! Enum serialized with explicit discriminants instead of variant declaration indexes
+//!
+//! By default, enum variant is serialized as its index in declaration order, so reordering
+//! variants in source changes the encoding. Implement [`ExplicitDiscriminant`] for the enum to
+//! pin discriminant of each variant, and use this module with `#[serde(with = "...")]` attribute.
+//! Variants are looked up by their _serde_ names (honoring `#[serde(rename)]`), so the mapping
+//! does not depend on declaration order. Ordering of serialized values follows discriminants.
+//!
+//! *Example*
+//! ```
+//! # use ordcode::{ with::explicit_discriminant::ExplicitDiscriminant, ser_to_vec_ordered,
+//! #   de_from_bytes_asc, Order };
+//! #[derive(serde_derive::Serialize, serde_derive::Deserialize, Debug, PartialEq)]
+//! enum Kind {
+//!     Text(String),
+//!     Binary,
+//! }
+//! impl ExplicitDiscriminant for Kind {
+//!     const DISCRIMINANTS: &'static [(&'static str, u32)] = &[("Binary", 1), ("Text", 2)];
+//! }
+//! #[derive(serde_derive::Serialize, serde_derive::Deserialize, Debug, PartialEq)]
+//! struct Record {
+//!     #[serde(with = "ordcode::with::explicit_discriminant")]
+//!     kind: Kind,
+//! }
+//! let rec = Record { kind: Kind::Binary };
+//! let buf = ser_to_vec_ordered(&rec, Order::Ascending).unwrap();
+//! assert_eq!(buf, [3]); // varint-encoded discriminant 1
+//! assert_eq!(de_from_bytes_asc::<Record>(&buf).unwrap(), rec);
+//! ```
+use crate::Error;
+use core::marker::PhantomData;
+use serde::{
+    de::{self, IntoDeserializer},
+    ser, Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// Mapping of enum variants to explicit discriminants
+pub trait ExplicitDiscriminant {
+    /// Pairs of variant name and its discriminant
+    ///
+    /// Serializing variant which is not listed, or deserializing unknown discriminant, returns
+    /// an error. Discriminants must be unique.
+    const DISCRIMINANTS: &'static [(&'static str, u32)];
+
+    /// Discriminant of the variant named `variant`
+    #[must_use]
+    fn discriminant(variant: &str) -> Option<u32> {
+        Self::DISCRIMINANTS
+            .iter()
+            .find(|(name, _)| *name == variant)
+            .map(|(_, discr)| *discr)
+    }
+    /// Name of the variant with discriminant `discr`
+    #[must_use]
+    fn variant(discr: u32) -> Option<&'static str> {
+        Self::DISCRIMINANTS
+            .iter()
+            .find(|(_, d)| *d == discr)
+            .map(|(name, _)| *name)
+    }
+}
+
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: ?Sized + ExplicitDiscriminant + Serialize,
+    S: Serializer,
+{
+    value.serialize(DiscrSerializer {
+        inner: serializer,
+        _marker: PhantomData::<fn(&T)>,
+    })
+}
+
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: ExplicitDiscriminant + Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(DiscrDeserializer {
+        inner: deserializer,
+        _marker: PhantomData::<fn() -> T>,
+    })
+}
+
+// Serializer which replaces variant index with explicit discriminant of `T`,
+// other values are passed to inner serializer as is
+struct DiscrSerializer<S, M> {
+    inner: S,
+    _marker: PhantomData<M>,
+}
+
+impl<S, T> DiscrSerializer<S, fn(&T)>
+where
+    S: Serializer,
+    T: ?Sized + ExplicitDiscriminant,
+{
+    fn discr(variant_index: u32, variant: &str) -> Result<u32, S::Error> {
+        T::discriminant(variant)
+            .ok_or_else(|| ser::Error::custom(Error::InvalidVariantIndex(variant_index)))
+    }
+}
+
+macro_rules! forward_ser {
+    ($($fn:ident($t:ty)),*) => {
+        $(
+        fn $fn(self, v: $t) -> Result<S::Ok, S::Error> {
+            self.inner.$fn(v)
+        }
+        )*
+    };
+}
+
+impl<S, T> Serializer for DiscrSerializer<S, fn(&T)>
+where
+    S: Serializer,
+    T: ?Sized + ExplicitDiscriminant,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = S::SerializeSeq;
+    type SerializeTuple = S::SerializeTuple;
+    type SerializeTupleStruct = S::SerializeTupleStruct;
+    type SerializeTupleVariant = S::SerializeTupleVariant;
+    type SerializeMap = S::SerializeMap;
+    type SerializeStruct = S::SerializeStruct;
+    type SerializeStructVariant = S::SerializeStructVariant;
+
+    forward_ser!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_unit_struct(&'static str)
+    );
+    fn serialize_none(self) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_none()
+    }
+    fn serialize_some<V>(self, value: &V) -> Result<S::Ok, S::Error>
+    where
+        V: ?Sized + Serialize,
+    {
+        self.inner.serialize_some(value)
+    }
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_unit()
+    }
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<S::Ok, S::Error> {
+        let discr = Self::discr(variant_index, variant)?;
+        self.inner.serialize_unit_variant(name, discr, variant)
+    }
+    fn serialize_newtype_struct<V>(self, name: &'static str, value: &V) -> Result<S::Ok, S::Error>
+    where
+        V: ?Sized + Serialize,
+    {
+        self.inner.serialize_newtype_struct(name, value)
+    }
+    fn serialize_newtype_variant<V>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &V,
+    ) -> Result<S::Ok, S::Error>
+    where
+        V: ?Sized + Serialize,
+    {
+        let discr = Self::discr(variant_index, variant)?;
+        self.inner
+            .serialize_newtype_variant(name, discr, variant, value)
+    }
+    fn serialize_seq(self, len: Option<usize>) -> Result<S::SerializeSeq, S::Error> {
+        self.inner.serialize_seq(len)
+    }
+    fn serialize_tuple(self, len: usize) -> Result<S::SerializeTuple, S::Error> {
+        self.inner.serialize_tuple(len)
+    }
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<S::SerializeTupleStruct, S::Error> {
+        self.inner.serialize_tuple_struct(name, len)
+    }
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<S::SerializeTupleVariant, S::Error> {
+        let discr = Self::discr(variant_index, variant)?;
+        self.inner
+            .serialize_tuple_variant(name, discr, variant, len)
+    }
+    fn serialize_map(self, len: Option<usize>) -> Result<S::SerializeMap, S::Error> {
+        self.inner.serialize_map(len)
+    }
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<S::SerializeStruct, S::Error> {
+        self.inner.serialize_struct(name, len)
+    }
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<S::SerializeStructVariant, S::Error> {
+        let discr = Self::discr(variant_index, variant)?;
+        self.inner
+            .serialize_struct_variant(name, discr, variant, len)
+    }
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+// Deserializer which maps explicit discriminant of `T` to variant name,
+// other values are deserialized by inner deserializer
+struct DiscrDeserializer<D, M> {
+    inner: D,
+    _marker: PhantomData<M>,
+}
+
+impl<'de, D, T> Deserializer<'de> for DiscrDeserializer<D, fn() -> T>
+where
+    D: Deserializer<'de>,
+    T: ExplicitDiscriminant,
+{
+    type Error = D::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, D::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.inner.deserialize_any(visitor)
+    }
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, D::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.inner.deserialize_enum(
+            name,
+            variants,
+            DiscrVisitor {
+                inner: visitor,
+                _marker: PhantomData::<fn() -> T>,
+            },
+        )
+    }
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+// Wraps enum visitor, so that variant is read with `DiscrSeed`
+struct DiscrVisitor<V, M> {
+    inner: V,
+    _marker: PhantomData<M>,
+}
+
+impl<'de, V, T> de::Visitor<'de> for DiscrVisitor<V, fn() -> T>
+where
+    V: de::Visitor<'de>,
+    T: ExplicitDiscriminant,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.inner.expecting(f)
+    }
+    fn visit_enum<A>(self, data: A) -> Result<V::Value, A::Error>
+    where
+        A: de::EnumAccess<'de>,
+    {
+        self.inner.visit_enum(DiscrEnumAccess {
+            inner: data,
+            _marker: PhantomData::<fn() -> T>,
+        })
+    }
+}
+
+struct DiscrEnumAccess<A, M> {
+    inner: A,
+    _marker: PhantomData<M>,
+}
+
+impl<'de, A, T> de::EnumAccess<'de> for DiscrEnumAccess<A, fn() -> T>
+where
+    A: de::EnumAccess<'de>,
+    T: ExplicitDiscriminant,
+{
+    type Error = A::Error;
+    type Variant = A::Variant;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, A::Variant), A::Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        self.inner.variant_seed(DiscrSeed {
+            inner: seed,
+            _marker: PhantomData::<fn() -> T>,
+        })
+    }
+}
+
+// Reads discriminant and passes variant name to the variant identifier seed
+struct DiscrSeed<S, M> {
+    inner: S,
+    _marker: PhantomData<M>,
+}
+
+impl<'de, S, T> de::DeserializeSeed<'de> for DiscrSeed<S, fn() -> T>
+where
+    S: de::DeserializeSeed<'de>,
+    T: ExplicitDiscriminant,
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<S::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let discr = u32::deserialize(deserializer)?;
+        let variant = T::variant(discr)
+            .ok_or_else(|| de::Error::custom(Error::InvalidVariantIndex(discr)))?;
+        self.inner.deserialize(variant.into_deserializer())
+    }
+}
@@ -0,0 +1,217 @@
+#![cfg(feature = "serde")]
+
+// Tests which depend on heap allocation behaviour: zero-copy deserialization, capacity reservation
+
+#[macro_use]
+extern crate serde_derive;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use ordcode::*;
+
+// Counts allocations made by the current thread, so that tests running in parallel don't interfere
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCS.try_with(|c| c.set(c.get() + 1));
+        System.alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCS.try_with(|c| c.set(c.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+// Returns result of `f` and number of allocations it made
+fn count_allocs<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = ALLOCS.with(Cell::get);
+    let r = f();
+    (r, ALLOCS.with(Cell::get) - before)
+}
+
+fn within(inner: &[u8], outer: &[u8]) -> bool {
+    let range = outer.as_ptr_range();
+    range.contains(&inner.as_ptr()) && inner.as_ptr_range().end <= range.end
+}
+
+#[test]
+fn borrowed_str() {
+    #[derive(Serialize, Deserialize)]
+    struct Row<'a> {
+        id: u32,
+        k: &'a str,
+    }
+    let buf = ser_to_vec_ordered(&Row { id: 1, k: "hello" }, Order::Ascending).unwrap();
+    let (row, allocs) = count_allocs(|| de_from_bytes_asc::<Row>(&buf).unwrap());
+    assert_eq!(allocs, 0);
+    assert_eq!(row.id, 1);
+    assert_eq!(row.k, "hello");
+    assert!(within(row.k.as_bytes(), &buf));
+}
+
+#[test]
+fn borrowed_bytes() {
+    #[derive(Serialize, Deserialize)]
+    struct Row<'a> {
+        #[serde(with = "serde_bytes")]
+        k: &'a [u8],
+        v: &'a str,
+    }
+    let buf = ser_to_vec_ordered(
+        &Row {
+            k: &[1, 2, 3],
+            v: "v",
+        },
+        Order::Ascending,
+    )
+    .unwrap();
+    let (row, allocs) = count_allocs(|| de_from_bytes_asc::<Row>(&buf).unwrap());
+    assert_eq!(allocs, 0);
+    assert_eq!(row.k, &[1, 2, 3]);
+    assert_eq!(row.v, "v");
+    assert!(within(row.k, &buf));
+}
+
+#[test]
+fn borrowed_serde_bytes() {
+    use serde_bytes::Bytes;
+
+    #[derive(Serialize, Deserialize)]
+    struct Blob<'a> {
+        id: u16,
+        #[serde(borrow)]
+        data: &'a Bytes,
+    }
+    let buf = ser_to_vec_ordered(
+        &Blob {
+            id: 3,
+            data: Bytes::new(&[0, 0xFF, 7, 0xF8]),
+        },
+        Order::Ascending,
+    )
+    .unwrap();
+    let (blob, allocs) = count_allocs(|| de_from_bytes_asc::<Blob>(&buf).unwrap());
+    assert_eq!(allocs, 0);
+    assert_eq!(blob.id, 3);
+    assert_eq!(blob.data.as_ref(), &[0, 0xFF, 7, 0xF8]);
+    assert!(within(blob.data, &buf));
+}
+
+#[test]
+fn reusable_buffer() {
+    let long = "x".repeat(100);
+    let values: Vec<(u32, &str)> = vec![(1, "abc"), (2, &long), (3, ""), (4, "de")];
+    let mut buf = ReusableBuffer::with_capacity(16);
+    for order in &[Order::Ascending, Order::Descending] {
+        for v in &values {
+            let expected = ser_to_vec_ordered(v, *order).unwrap();
+            assert_eq!(buf.ser_into(v, *order).unwrap(), expected.as_slice());
+        }
+    }
+    // once grown to fit the largest value, no more allocations
+    let (_, allocs) = count_allocs(|| {
+        for v in &values {
+            buf.ser_into(v, Order::Descending).unwrap();
+        }
+    });
+    assert_eq!(allocs, 0);
+}
+
+#[test]
+fn vec_reserves_exact_capacity() {
+    let v: Vec<u32> = (0..100_000).collect();
+    let buf = ser_to_vec_ordered(&v, Order::Ascending).unwrap();
+    // sequence length from the tail is passed as size hint, so `Vec` allocates only once
+    let (v2, allocs) = count_allocs(|| de_from_bytes_asc::<Vec<u32>>(&buf).unwrap());
+    assert_eq!(allocs, 1);
+    assert_eq!(v2.capacity(), v.len());
+    assert_eq!(v2, v);
+}
+
+#[test]
+fn hashmap_reserves_capacity() {
+    use std::collections::HashMap;
+
+    let map: HashMap<u32, u32> = (0..10_000).map(|i| (i, i * 2)).collect();
+    let buf = ser_to_vec_ordered(&map, Order::Ascending).unwrap();
+    // entry count from the tail is passed as size hint, so the table is allocated once, without rehashing
+    let (map2, allocs) = count_allocs(|| de_from_bytes_asc::<HashMap<u32, u32>>(&buf).unwrap());
+    assert_eq!(allocs, 1);
+    assert!(map2.capacity() >= map.len());
+    assert_eq!(map2, map);
+}
+
+#[test]
+fn borrowed_map() {
+    use serde::Deserialize;
+    use serde_bytes::Bytes;
+    use std::collections::BTreeMap;
+
+    let mut map = BTreeMap::new();
+    map.insert("a", Bytes::new(&[1, 2]));
+    map.insert("bcd", Bytes::new(&[]));
+    map.insert("ef", Bytes::new(&[0xF8, 0, 0xFF]));
+    let buf = ser_to_vec_ordered(&map, Order::Ascending).unwrap();
+
+    let mut reader = DeBytesReader::from_slice(&buf);
+    let mut de = new_de_asc_borrowed(&mut reader);
+    let decoded = BTreeMap::<&str, &Bytes>::deserialize(&mut de).unwrap();
+    assert_eq!(decoded, map);
+    for (k, v) in &decoded {
+        assert!(within(k.as_bytes(), &buf));
+        assert!(within(v, &buf));
+    }
+}
+
+#[test]
+fn borrowed_cow() {
+    use serde::Deserialize;
+    use std::borrow::Cow;
+
+    #[derive(Serialize, Deserialize)]
+    struct Key<'a> {
+        id: u32,
+        #[serde(
+            borrow,
+            serialize_with = "ordcode::primitives::serialize_cow",
+            deserialize_with = "ordcode::primitives::deserialize_cow"
+        )]
+        name: Cow<'a, str>,
+    }
+    let buf = ser_to_vec_ordered(
+        &Key {
+            id: 7,
+            name: Cow::Owned("hello".to_string()),
+        },
+        Order::Ascending,
+    )
+    .unwrap();
+    let (key, allocs) = count_allocs(|| de_from_bytes_asc::<Key>(&buf).unwrap());
+    assert_eq!(allocs, 0);
+    assert_eq!(key.id, 7);
+    match key.name {
+        Cow::Borrowed(s) => {
+            assert_eq!(s, "hello");
+            assert!(within(s.as_bytes(), &buf));
+        }
+        Cow::Owned(_) => panic!("expected borrowed Cow"),
+    }
+    // streaming reader can't lend data, falls back to owned value
+    let mut cursor = std::io::Cursor::new(&buf);
+    let reader = buf::IoReader::new(&mut cursor, buf.len());
+    let mut de = Deserializer::new(reader, params::AscendingOrder);
+    let key = Key::deserialize(&mut de).unwrap();
+    assert!(matches!(key.name, Cow::Owned(ref s) if s == "hello"));
+}
@@ -0,0 +1,111 @@
+#![cfg(feature = "collation")]
+
+extern crate ordcode;
+#[macro_use]
+extern crate serde_derive;
+
+use ordcode::primitives::Collation;
+use ordcode::{de_from_bytes_asc, de_from_bytes_ordered, ser_to_vec_ordered, Order};
+
+// German phonebook order: umlauts sort as vowel followed by 'e', case-insensitive
+struct Phonebook;
+
+impl Collation for Phonebook {
+    fn sort_key(value: &str) -> Vec<u8> {
+        let mut key = String::new();
+        for c in value.chars().flat_map(char::to_lowercase) {
+            match c {
+                'ä' => key.push_str("ae"),
+                'ö' => key.push_str("oe"),
+                'ü' => key.push_str("ue"),
+                'ß' => key.push_str("ss"),
+                c => key.push(c),
+            }
+        }
+        key.into_bytes()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Entry {
+    #[serde(
+        serialize_with = "ordcode::primitives::serialize_collated::<Phonebook, _>",
+        deserialize_with = "ordcode::primitives::deserialize_collated"
+    )]
+    name: String,
+    id: u32,
+}
+
+#[derive(Serialize)]
+struct KeyOnly<'a> {
+    #[serde(serialize_with = "ordcode::primitives::serialize_collation_key::<Phonebook, _>")]
+    name: &'a str,
+}
+
+fn entry(name: &str) -> Entry {
+    Entry {
+        name: name.to_string(),
+        id: 1,
+    }
+}
+
+#[test]
+fn collated_order() {
+    // byte order: "Muller" < "Mzz" < "Müller"; phonebook order: "Müller" < "Muller" < "Mzz"
+    let names = ["Müller", "Muller", "Mzz"];
+    assert!(names[0].as_bytes() > names[2].as_bytes());
+
+    for order in &[Order::Ascending, Order::Descending] {
+        let encoded: Vec<_> = names
+            .iter()
+            .map(|n| ser_to_vec_ordered(&entry(n), *order).unwrap())
+            .collect();
+        for w in encoded.windows(2) {
+            match order {
+                Order::Ascending => assert!(w[0] < w[1]),
+                _ => assert!(w[0] > w[1]),
+            }
+        }
+        for (name, buf) in names.iter().zip(&encoded) {
+            let decoded: Entry = de_from_bytes_ordered(&mut buf.clone(), *order).unwrap();
+            assert_eq!(decoded, entry(name));
+        }
+    }
+}
+
+#[test]
+fn collated_ties() {
+    // equal sort keys, ordered by original strings
+    let a = ser_to_vec_ordered(&entry("MUELLER"), Order::Ascending).unwrap();
+    let b = ser_to_vec_ordered(&entry("Müller"), Order::Ascending).unwrap();
+    assert!(a < b);
+    assert_eq!(de_from_bytes_asc::<Entry>(&b).unwrap(), entry("Müller"));
+}
+
+#[test]
+fn collated_prefix_key() {
+    // sort key of "mü" is "mue", a prefix of "muea"; string bytes which follow the sort key
+    // must not affect ordering
+    let names = ["mü", "muea"];
+    for order in &[Order::Ascending, Order::Descending] {
+        let a = ser_to_vec_ordered(&entry(names[0]), *order).unwrap();
+        let b = ser_to_vec_ordered(&entry(names[1]), *order).unwrap();
+        match order {
+            Order::Ascending => assert!(a < b),
+            _ => assert!(a > b),
+        }
+        let decoded: Entry = de_from_bytes_ordered(&mut a.clone(), *order).unwrap();
+        assert_eq!(decoded, entry(names[0]));
+    }
+}
+
+#[test]
+fn collation_key_only() {
+    let a = ser_to_vec_ordered(&KeyOnly { name: "Müller" }, Order::Ascending).unwrap();
+    let b = ser_to_vec_ordered(&KeyOnly { name: "mueller" }, Order::Ascending).unwrap();
+    let c = ser_to_vec_ordered(&KeyOnly { name: "Mzz" }, Order::Ascending).unwrap();
+    assert_eq!(a, b);
+    assert!(a < c);
+    let key: serde_bytes::ByteBuf = de_from_bytes_asc(&a).unwrap();
+    assert_eq!(key.into_vec(), b"mueller");
+}
@@ -33,6 +33,31 @@ fn deserialize_desc<T: DeserializeOwned>(b: &mut [u8]) -> Result<T> {
     de_from_bytes_ordered(b, Order::Descending)
 }
 
+// serialize with custom parameters into buffer of calculated size, which must be filled exactly
+fn ser_with<T, P>(v: &T, params: P) -> Result<Vec<u8>>
+where
+    T: Serialize + ?Sized,
+    P: params::SerializerParams + Copy,
+{
+    let mut buf = vec![0_u8; calc_size(v, params)?];
+    let mut writer = DeBytesWriter::new(&mut buf);
+    v.serialize(&mut Serializer::new(&mut writer, params))?;
+    writer.is_complete()?;
+    Ok(buf)
+}
+
+// deserialize with custom parameters, requiring that the whole input is consumed
+fn de_with<T, P>(buf: &[u8], params: P) -> Result<T>
+where
+    T: DeserializeOwned,
+    P: params::SerializerParams,
+{
+    let mut deser = Deserializer::new(DeBytesReader::new(buf), params);
+    let v = T::deserialize(&mut deser)?;
+    buf::ReadBytes::is_complete(&mut deser.into_reader())?;
+    Ok(v)
+}
+
 // Basic tests mostly adapted from 'bincode' crate
 fn the_same<V>(element: V)
 where
@@ -397,3 +422,2259 @@ fn test_multiseq() {
         serialize_asc(&a).unwrap() < serialize_asc(&b).unwrap()
     )
 }
+
+#[test]
+fn test_exact_input() {
+    let mut buf = serialize_asc(&(1u16, 2u32)).unwrap();
+    assert_eq!(de_from_bytes_asc_exact::<(u16, u32)>(&buf).unwrap(), (1, 2));
+    // too long
+    buf.push(0);
+    assert!(matches!(
+        de_from_bytes_asc_exact::<(u16, u32)>(&buf),
+        Err(Error::TrailingBytes)
+    ));
+    assert_eq!(de_from_bytes_asc::<(u16, u32)>(&buf).unwrap(), (1, 2));
+    // too short
+    buf.truncate(5);
+    assert!(matches!(
+        de_from_bytes_asc_exact::<(u16, u32)>(&buf),
+        Err(Error::PrematureEndOfInput)
+    ));
+}
+
+#[test]
+fn test_exact_input_ordered() {
+    let mut buf = serialize_desc(&(1u16, "abc")).unwrap();
+    let v: (u16, String) =
+        de_from_bytes_ordered_exact(&mut buf.clone(), Order::Descending).unwrap();
+    assert_eq!(v, (1, "abc".to_string()));
+    buf.insert(2, 0xFF);
+    assert!(matches!(
+        de_from_bytes_ordered_exact::<(u16, String)>(&mut buf, Order::Descending),
+        Err(Error::TrailingBytes)
+    ));
+}
+
+#[test]
+fn test_ser_to_vec_grow() {
+    #[derive(Serialize)]
+    struct Nested {
+        a: Vec<(String, Vec<u8>)>,
+        b: Option<HashMap<u8, String>>,
+        c: (i32, f64),
+    }
+    let mut m = HashMap::new();
+    m.insert(1, "one".to_string());
+    let v = Nested {
+        a: vec![
+            ("x".into(), vec![1, 2, 3]),
+            ("".into(), vec![]),
+            ("yz".into(), vec![0xF8; 200]),
+        ],
+        b: Some(m),
+        c: (-7, 1.5),
+    };
+    for order in &[Order::Ascending, Order::Descending] {
+        assert_eq!(
+            ser_to_vec_grow(&v, *order).unwrap(),
+            ser_to_vec_ordered(&v, *order).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_self_describing() {
+    use ordcode::params::SelfDescribing;
+    use serde::de::{Deserialize, Deserializer as _, MapAccess, SeqAccess, Visitor};
+
+    // untyped value, deserialized with `deserialize_any()`
+    #[derive(Debug, PartialEq)]
+    enum Any {
+        Unit,
+        Bool(bool),
+        U64(u64),
+        I64(i64),
+        F64(f64),
+        Str(String),
+        None,
+        Some(Box<Any>),
+        Seq(Vec<Any>),
+        Map(Vec<(Any, Any)>),
+    }
+    struct AnyVisitor;
+    impl<'de> Visitor<'de> for AnyVisitor {
+        type Value = Any;
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("any value")
+        }
+        fn visit_unit<E>(self) -> std::result::Result<Any, E> {
+            Ok(Any::Unit)
+        }
+        fn visit_bool<E>(self, v: bool) -> std::result::Result<Any, E> {
+            Ok(Any::Bool(v))
+        }
+        fn visit_u64<E>(self, v: u64) -> std::result::Result<Any, E> {
+            Ok(Any::U64(v))
+        }
+        fn visit_i64<E>(self, v: i64) -> std::result::Result<Any, E> {
+            Ok(Any::I64(v))
+        }
+        fn visit_f64<E>(self, v: f64) -> std::result::Result<Any, E> {
+            Ok(Any::F64(v))
+        }
+        fn visit_str<E>(self, v: &str) -> std::result::Result<Any, E> {
+            Ok(Any::Str(v.to_string()))
+        }
+        fn visit_none<E>(self) -> std::result::Result<Any, E> {
+            Ok(Any::None)
+        }
+        fn visit_some<D: serde::Deserializer<'de>>(
+            self,
+            d: D,
+        ) -> std::result::Result<Any, D::Error> {
+            Ok(Any::Some(Box::new(Any::deserialize(d)?)))
+        }
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<Any, A::Error> {
+            let mut v = vec![];
+            while let Some(e) = seq.next_element()? {
+                v.push(e);
+            }
+            Ok(Any::Seq(v))
+        }
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<Any, A::Error> {
+            let mut v = vec![];
+            while let Some(e) = map.next_entry()? {
+                v.push(e);
+            }
+            Ok(Any::Map(v))
+        }
+    }
+    impl<'de> Deserialize<'de> for Any {
+        fn deserialize<D: serde::Deserializer<'de>>(d: D) -> std::result::Result<Any, D::Error> {
+            d.deserialize_any(AnyVisitor)
+        }
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum E {
+        A,
+        B(u8),
+        C { x: i8, y: f32 },
+    }
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Rec {
+        id: u32,
+        name: String,
+        tags: Vec<u8>,
+        opt: Option<i16>,
+        none: Option<bool>,
+        e: (E, E, E),
+        unit: (),
+    }
+    let rec = Rec {
+        id: 1,
+        name: "abc".into(),
+        tags: vec![1, 2],
+        opt: Some(-3),
+        none: None,
+        e: (E::A, E::B(5), E::C { x: -1, y: 0.5 }),
+        unit: (),
+    };
+    let size = calc_size(&rec, SelfDescribing).unwrap();
+    let mut buf = vec![0_u8; size];
+    let mut writer = DeBytesWriter::new(&mut buf);
+    rec.serialize(&mut Serializer::new(&mut writer, SelfDescribing))
+        .unwrap();
+    writer.is_complete().unwrap();
+
+    let mut reader = DeBytesReader::new(&buf);
+    let any = Any::deserialize(&mut Deserializer::new(&mut reader, SelfDescribing)).unwrap();
+    assert_eq!(
+        any,
+        Any::Seq(vec![
+            Any::U64(1),
+            Any::Str("abc".into()),
+            Any::Seq(vec![Any::U64(1), Any::U64(2)]),
+            Any::Some(Box::new(Any::I64(-3))),
+            Any::None,
+            Any::Seq(vec![
+                Any::Map(vec![(Any::U64(0), Any::Unit)]),
+                Any::Map(vec![(Any::U64(1), Any::U64(5))]),
+                Any::Map(vec![(
+                    Any::U64(2),
+                    Any::Seq(vec![Any::I64(-1), Any::F64(0.5)])
+                )]),
+            ]),
+            Any::Unit,
+        ])
+    );
+
+    // typed round trip
+    let mut reader = DeBytesReader::new(&buf);
+    let decoded = Rec::deserialize(&mut Deserializer::new(&mut reader, SelfDescribing)).unwrap();
+    assert_eq!(decoded, rec);
+
+    // default format is not self-describing
+    let buf = serialize_asc(&rec).unwrap();
+    let mut reader = DeBytesReader::new(&buf);
+    assert!(matches!(
+        (&mut new_de_asc(&mut reader)).deserialize_any(AnyVisitor),
+        Err(Error::DeserializeAnyNotSupported)
+    ));
+}
+
+#[test]
+fn test_reverse_field() {
+    use ordcode::params::Reverse;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Key {
+        user: u32,
+        ts: Reverse<u64>,
+        seq: u16,
+    }
+    let key = |user, ts, seq| Key {
+        user,
+        ts: Reverse(ts),
+        seq,
+    };
+    let keys = [
+        key(1, 100, 1),
+        key(1, 50, 0),
+        key(1, 50, 1),
+        key(2, 1000, 0),
+        key(2, 1, 0),
+    ];
+    for order in &[Order::Ascending, Order::Descending] {
+        let enc: Vec<_> = keys
+            .iter()
+            .map(|k| ser_to_vec_ordered(k, *order).unwrap())
+            .collect();
+        for w in enc.windows(2) {
+            match order {
+                Order::Descending => assert!(w[0] > w[1]),
+                _ => assert!(w[0] < w[1]),
+            }
+        }
+        for (k, mut e) in keys.iter().zip(enc) {
+            assert_eq!(&de_from_bytes_ordered::<Key>(&mut e, *order).unwrap(), k);
+        }
+    }
+    // wrapped strings are escaped, so a string which is a prefix of another one sorts after it,
+    // regardless of the following fields
+    let pairs = [
+        ("ab", "abc"),
+        ("ab", "ab\u{1}"),
+        ("", "\0"),
+        ("", "a"),
+        ("a\0", "a\0\0"),
+    ];
+    for (short, long) in &pairs {
+        for order in &[Order::Ascending, Order::Descending] {
+            let a = ser_to_vec_ordered(&(Reverse(*short), 0u8), *order).unwrap();
+            let b = ser_to_vec_ordered(&(Reverse(*long), 255u8), *order).unwrap();
+            match order {
+                Order::Descending => assert!(a < b, "{:?} {:?}", short, long),
+                _ => assert!(a > b, "{:?} {:?}", short, long),
+            }
+            let v: (Reverse<String>, u8) = de_from_bytes_ordered(&mut a.clone(), *order).unwrap();
+            assert_eq!(v, (Reverse(short.to_string()), 0));
+        }
+        // same for byte sequences
+        let a = serialize_asc(&Reverse(serde_bytes::Bytes::new(short.as_bytes()))).unwrap();
+        let b = serialize_asc(&Reverse(serde_bytes::Bytes::new(long.as_bytes()))).unwrap();
+        assert!(a > b);
+    }
+}
+
+#[derive(Copy, Clone, Default)]
+struct EscapedBytes;
+
+impl params::EncodingParams for EscapedBytes {
+    const ORDER: Order = Order::Ascending;
+    const ENDIANNESS: params::Endianness = params::Endianness::Big;
+}
+
+impl params::SerializerParams for EscapedBytes {
+    type SeqLenEncoder = varint::VarIntTailLenEncoder;
+    type DiscriminantEncoder = varint::VarIntDiscrEncoder;
+    const ESCAPED_BYTES: bool = true;
+}
+
+#[test]
+fn test_escaped_bytes() {
+    let buf = ser_with(&("a\0", 1u8), EscapedBytes).unwrap();
+    assert_eq!(buf, [b'a', 0, 0xFF, 0, 1, 1]);
+    assert_eq!(
+        de_with::<(String, u8), _>(&buf, EscapedBytes).unwrap(),
+        ("a\0".to_string(), 1)
+    );
+    // prefix is ordered first regardless of following data, unlike lengths in the tail
+    use serde_bytes::ByteBuf;
+    let a = ser_with(&(ByteBuf::from(vec![0xFF]), "z"), EscapedBytes).unwrap();
+    let b = ser_with(&(ByteBuf::from(vec![0xFF, 0]), ""), EscapedBytes).unwrap();
+    assert!(a < b);
+    let v: (ByteBuf, String) = de_with(&b, EscapedBytes).unwrap();
+    assert_eq!(v, (ByteBuf::from(vec![0xFF, 0]), String::new()));
+}
+
+#[test]
+fn test_reader_positions() {
+    use ordcode::primitives::SerializableValue;
+    let buf = serialize_asc(&(7u32, "abcde")).unwrap();
+    let mut reader = DeBytesReader::new(&buf);
+    assert_eq!(
+        u32::from_reader(&mut reader, params::AscendingOrder).unwrap(),
+        7
+    );
+    assert_eq!((reader.head_pos(), reader.tail_pos()), (4, 10));
+    let s: &str = serde::Deserialize::deserialize(&mut new_de_asc_borrowed(&mut reader)).unwrap();
+    assert_eq!(s, "abcde");
+    assert_eq!((reader.head_pos(), reader.tail_pos()), (9, 9));
+    assert_eq!(reader.remaining(), 0);
+}
+
+#[test]
+fn test_reader_reuse_after_deserialize() {
+    use ordcode::buf::ReadBytes;
+    // generic over `'de`, reader is borrowed only for deserialization
+    fn parse<'de, T: serde::Deserialize<'de>>(buf: &'de [u8]) -> (T, usize) {
+        let mut r = DeBytesReader::new(buf);
+        let value = T::deserialize(&mut new_de_asc(&mut r)).unwrap();
+        (value, r.remaining_buffer().len())
+    }
+    let buf = serialize_asc(&(7u32, "abcde")).unwrap();
+    assert_eq!(parse::<u32>(&buf), (7, 6));
+    assert_eq!(parse::<(u32, String)>(&buf), ((7, "abcde".into()), 0));
+}
+
+#[test]
+fn test_io_writer() {
+    #[derive(Serialize)]
+    struct Foo {
+        a: Vec<String>,
+        b: u32,
+        c: Option<Vec<u8>>,
+    }
+    let foo = Foo {
+        a: vec!["x".into(), "yz".into()],
+        b: 77,
+        c: Some(vec![1, 2, 3]),
+    };
+    let mut writer = buf::IoWriter::new(std::io::Cursor::new(Vec::new()));
+    foo.serialize(&mut new_ser_asc(&mut writer)).unwrap();
+    let out = writer.finalize().unwrap().into_inner();
+    assert_eq!(out, serialize_asc(&foo).unwrap());
+}
+
+#[test]
+fn test_ser_many_to_vec() {
+    let keys: Vec<(String, u32)> = vec![
+        ("a".into(), 1),
+        ("bcd".into(), 2),
+        (String::new(), 3),
+        ("efghij".into(), u32::MAX),
+    ];
+    for order in &[Order::Ascending, Order::Descending] {
+        let (mut buf, offsets) = ser_many_to_vec(&keys, *order).unwrap();
+        assert_eq!(offsets.len(), keys.len() + 1);
+        assert_eq!(*offsets.last().unwrap(), buf.len());
+        for (i, key) in keys.iter().enumerate() {
+            let part = &mut buf[offsets[i]..offsets[i + 1]];
+            assert_eq!(part, ser_to_vec_ordered(key, *order).unwrap().as_slice());
+            let k: (String, u32) = de_from_bytes_ordered_exact(part, *order).unwrap();
+            assert_eq!(&k, key);
+        }
+    }
+    let (buf, offsets) = ser_many_to_vec::<u8>(&[], Order::Ascending).unwrap();
+    assert!(buf.is_empty());
+    assert_eq!(offsets, [0]);
+}
+
+#[test]
+fn test_bytes_len_in_tail() {
+    let bytes = serde_bytes::ByteBuf::from(vec![0_u8, 0xFF, 1, 2, 0]);
+    let buf = ser_to_vec_ordered(&bytes, Order::Ascending).unwrap();
+    // raw bytes in the head, no escaping; varint length (5) in the tail
+    assert_eq!(buf, [0, 0xFF, 1, 2, 0, 11]);
+    assert_eq!(calc_size_asc(&bytes).unwrap(), buf.len());
+    let b: serde_bytes::ByteBuf = de_from_bytes_asc_exact(&buf).unwrap();
+    assert_eq!(b, bytes);
+}
+
+#[derive(Copy, Clone, Default)]
+struct NullsLast;
+
+impl params::EncodingParams for NullsLast {
+    const ORDER: Order = Order::Ascending;
+    const ENDIANNESS: params::Endianness = params::Endianness::Big;
+}
+
+impl params::SerializerParams for NullsLast {
+    type SeqLenEncoder = varint::VarIntTailLenEncoder;
+    type DiscriminantEncoder = varint::VarIntDiscrEncoder;
+    const OPTION_NULLS_LAST: bool = true;
+}
+
+#[test]
+fn test_option_nulls_last() {
+    let ser = |v: &Option<i32>| ser_with(v, NullsLast).unwrap();
+    let de = |buf: &[u8]| de_with::<Option<i32>, _>(buf, NullsLast).unwrap();
+    let values = [Some(i32::MIN), Some(-1), Some(0), Some(i32::MAX), None];
+    for w in values.windows(2) {
+        assert!(ser(&w[0]) < ser(&w[1]));
+    }
+    for v in &values {
+        assert_eq!(de(&ser(v)), *v);
+    }
+    assert!(ser(&Some(i32::MAX)) < ser(&None));
+    // default remains nulls first
+    assert!(serialize_asc(&None::<i32>).unwrap() < serialize_asc(&Some(i32::MIN)).unwrap());
+}
+
+#[test]
+fn test_versioned() {
+    let value = (7_u32, "key".to_string(), vec![1_i8, -1]);
+    for order in &[Order::Ascending, Order::Descending] {
+        let mut buf = ser_to_vec_versioned(&value, *order).unwrap();
+        let plain = ser_to_vec_ordered(&value, *order).unwrap();
+        assert_eq!(buf[1..], plain[..]);
+        let v: (u32, String, Vec<i8>) = de_from_bytes_versioned(&mut buf, *order).unwrap();
+        assert_eq!(v, value);
+
+        // buffer written with an older format version is rejected, not mis-decoded
+        let mut old = vec![];
+        varint::VarUInt::varu_to_writer(&0_u32, &mut old).unwrap();
+        old.extend_from_slice(&plain);
+        let r: Result<(u32, String, Vec<i8>)> = de_from_bytes_versioned(&mut old, *order);
+        assert!(matches!(
+            r,
+            Err(Error::VersionMismatch {
+                expected: 1,
+                found: 0
+            })
+        ));
+    }
+    let r: Result<u8> = de_from_bytes_versioned(&mut [], Order::Ascending);
+    assert!(matches!(r, Err(Error::PrematureEndOfInput)));
+}
+
+#[test]
+fn test_stream_serializer() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Rec {
+        id: u32,
+        name: String,
+        tags: Vec<u16>,
+    }
+    let recs = vec![
+        Rec {
+            id: 1,
+            name: "a".into(),
+            tags: vec![],
+        },
+        Rec {
+            id: 2,
+            name: "bcd".into(),
+            tags: vec![1, 2, 3],
+        },
+        Rec {
+            id: 3,
+            name: String::new(),
+            tags: vec![u16::MAX],
+        },
+    ];
+    for order in &[Order::Ascending, Order::Descending] {
+        let mut stream = StreamSerializer::new(Vec::new(), *order);
+        let mut lens = vec![];
+        for (i, r) in recs.iter().enumerate() {
+            let len = stream.write_value(r).unwrap();
+            lens.push(len);
+            assert_eq!(stream.total_written(), lens.iter().sum::<usize>());
+            assert_eq!(len, calc_size_asc(&recs[i]).unwrap());
+        }
+        let mut buf = stream.into_inner();
+        assert_eq!(buf.len(), lens.iter().sum::<usize>());
+        let mut rest = buf.as_mut_slice();
+        for (r, len) in recs.iter().zip(&lens) {
+            let (rec, tail) = rest.split_at_mut(*len);
+            let decoded: Rec = de_from_bytes_ordered_exact(rec, *order).unwrap();
+            assert_eq!(&decoded, r);
+            rest = tail;
+        }
+    }
+}
+
+#[derive(Copy, Clone, Default)]
+struct FixedLen;
+
+impl params::EncodingParams for FixedLen {
+    const ORDER: Order = Order::Ascending;
+    const ENDIANNESS: params::Endianness = params::Endianness::Big;
+}
+
+impl params::SerializerParams for FixedLen {
+    type SeqLenEncoder = params::FixedTailLenEncoder;
+    type DiscriminantEncoder = varint::VarIntDiscrEncoder;
+}
+
+#[test]
+fn test_fixed_width_len() {
+    fn ser<T: Serialize>(v: &T) -> Vec<u8> {
+        ser_with(v, FixedLen).unwrap()
+    }
+    fn de<T: DeserializeOwned>(buf: &[u8]) -> T {
+        de_with(buf, FixedLen).unwrap()
+    }
+    let s1: String = "a".repeat(200);
+    let s2: String = "b".repeat(200);
+    assert_eq!(ser(&s1).len(), ser(&s2).len());
+    assert_eq!(ser(&s1).len(), 204);
+    // length is a 4-byte big-endian value at the end of the buffer
+    assert_eq!(ser(&"abc")[3..], [0, 0, 0, 3]);
+    // same-size records for same-length sequences, regardless of length value magnitudes
+    let r1 = (vec![1_u8; 3], "x".repeat(130));
+    let r2 = (vec![2_u8; 3], "y".repeat(130));
+    assert_eq!(ser(&r1).len(), ser(&r2).len());
+    assert_eq!(de::<(Vec<u8>, String)>(&ser(&r1)), r1);
+    assert_eq!(de::<String>(&ser(&s1)), s1);
+    let v = vec![vec![1_u16], vec![], vec![2, 3]];
+    assert_eq!(de::<Vec<Vec<u16>>>(&ser(&v)), v);
+}
+
+#[derive(Copy, Clone, Default)]
+struct ByteLen;
+
+impl params::EncodingParams for ByteLen {
+    const ORDER: Order = Order::Ascending;
+    const ENDIANNESS: params::Endianness = params::Endianness::Big;
+}
+
+impl params::SerializerParams for ByteLen {
+    type SeqLenEncoder = params::ByteTailLenEncoder;
+    type DiscriminantEncoder = varint::VarIntDiscrEncoder;
+}
+
+#[test]
+fn test_byte_len() {
+    fn ser<T: Serialize>(v: &T) -> Result<Vec<u8>> {
+        ser_with(v, ByteLen)
+    }
+    fn de<T: DeserializeOwned>(buf: &[u8]) -> T {
+        de_with(buf, ByteLen).unwrap()
+    }
+    // length is a raw byte at the end of the buffer
+    assert_eq!(ser(&"abc").unwrap(), b"abc\x03");
+    let s: String = "a".repeat(255);
+    assert_eq!(ser(&s).unwrap().len(), 256);
+    assert_eq!(ser(&s).unwrap()[255], 255);
+    assert_eq!(de::<String>(&ser(&s).unwrap()), s);
+    let v = (vec![vec![1_u16], vec![], vec![2, 3]], "xyz".to_string());
+    assert_eq!(de::<(Vec<Vec<u16>>, String)>(&ser(&v).unwrap()), v);
+
+    let long: String = "a".repeat(256);
+    assert!(matches!(
+        calc_size(&long, ByteLen).and_then(|_| ser(&long)),
+        Err(Error::IntegerOverflow)
+    ));
+    let mut buf = vec![0_u8; 300];
+    let mut writer = DeBytesWriter::new(&mut buf);
+    assert!(matches!(
+        long.serialize(&mut Serializer::new(&mut writer, ByteLen)),
+        Err(Error::IntegerOverflow)
+    ));
+}
+
+#[derive(Copy, Clone, Default)]
+struct U16Discr;
+
+impl params::EncodingParams for U16Discr {
+    const ORDER: Order = Order::Ascending;
+    const ENDIANNESS: params::Endianness = params::Endianness::Big;
+}
+
+impl params::SerializerParams for U16Discr {
+    type SeqLenEncoder = varint::VarIntTailLenEncoder;
+    type DiscriminantEncoder = params::U16DiscrEncoder;
+}
+
+#[test]
+fn test_fixed_width_discr() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq, PartialOrd)]
+    enum Msg {
+        Ping,
+        Data(u32),
+        Text(String),
+    }
+    fn ser<T: Serialize>(v: &T, order: Order) -> Vec<u8> {
+        let mut buf = ser_with(v, U16Discr).unwrap();
+        if let Order::Descending = order {
+            primitives::invert_buffer(&mut buf);
+        }
+        buf
+    }
+    fn de<T: DeserializeOwned>(buf: &[u8]) -> T {
+        de_with(buf, U16Discr).unwrap()
+    }
+    // discriminant is a 2-byte big-endian value at the start of the buffer
+    assert_eq!(ser(&Msg::Ping, Order::Ascending), [0, 0]);
+    assert_eq!(ser(&Msg::Data(7), Order::Ascending), [0, 1, 0, 0, 0, 7]);
+    assert_eq!(ser(&Msg::Text("ab".into()), Order::Ascending)[..2], [0, 2]);
+
+    let values = vec![
+        Msg::Ping,
+        Msg::Data(0),
+        Msg::Data(u32::MAX),
+        Msg::Text(String::new()),
+        Msg::Text("a".into()),
+    ];
+    for v in &values {
+        assert_eq!(&de::<Msg>(&ser(v, Order::Ascending)), v);
+    }
+    for w in values.windows(2) {
+        assert!(ser(&w[0], Order::Ascending) < ser(&w[1], Order::Ascending));
+        assert!(ser(&w[0], Order::Descending) > ser(&w[1], Order::Descending));
+    }
+    assert_eq!(calc_size(&Msg::Data(1), U16Discr).unwrap(), 6);
+
+    use ordcode::params::LengthEncoder;
+    let mut buf = Vec::new();
+    assert!(matches!(
+        params::U16DiscrEncoder::write(&mut buf, 0x1_0000),
+        Err(Error::IntegerOverflow)
+    ));
+    assert!(matches!(
+        params::U8DiscrEncoder::write(&mut buf, 256),
+        Err(Error::IntegerOverflow)
+    ));
+    params::U32DiscrEncoder::write(&mut buf, 0x0102_0304).unwrap();
+    assert_eq!(buf, [1, 2, 3, 4]);
+}
+
+#[derive(Copy, Clone, Default)]
+struct StrictVarint;
+
+impl params::EncodingParams for StrictVarint {
+    const ORDER: Order = Order::Ascending;
+    const ENDIANNESS: params::Endianness = params::Endianness::Big;
+}
+
+impl params::SerializerParams for StrictVarint {
+    type SeqLenEncoder = varint::VarIntTailLenEncoder;
+    type DiscriminantEncoder = varint::VarIntDiscrEncoder;
+    const STRICT_VARINT: bool = true;
+}
+
+#[test]
+fn test_strict_varint() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum E {
+        A,
+        B(String),
+    }
+    let value = E::B("ab".to_string());
+    let buf = serialize_asc(&value).unwrap();
+    assert_eq!(buf, [3, b'a', b'b', 5]);
+    assert_eq!(de_with::<E, _>(&buf, StrictVarint).unwrap(), value);
+
+    // discriminant 1 encoded with 2 bytes
+    let overlong_discr = [6, 0, b'a', b'b', 5];
+    // lenient decoding accepts it, except in debug builds
+    #[cfg(not(debug_assertions))]
+    assert_eq!(
+        de_with::<E, _>(&overlong_discr, params::AscendingOrder).unwrap(),
+        value
+    );
+    assert!(matches!(
+        de_with::<E, _>(&overlong_discr, StrictVarint).unwrap_err(),
+        Error::InvalidVarintEncoding
+    ));
+    // string length 2 encoded with 2 bytes, in the tail: first byte is the last one
+    let overlong_len = [3, b'a', b'b', 0, 10];
+    #[cfg(not(debug_assertions))]
+    assert_eq!(
+        de_with::<E, _>(&overlong_len, params::AscendingOrder).unwrap(),
+        value
+    );
+    assert!(matches!(
+        de_with::<E, _>(&overlong_len, StrictVarint).unwrap_err(),
+        Error::InvalidVarintEncoding
+    ));
+    let seq = [1_u8, 2, 0, 10];
+    #[cfg(not(debug_assertions))]
+    assert_eq!(
+        de_with::<Vec<u8>, _>(&seq, params::AscendingOrder).unwrap(),
+        [1, 2]
+    );
+    assert!(de_with::<Vec<u8>, _>(&seq, StrictVarint).is_err());
+}
+
+#[test]
+fn test_explicit_discriminant() {
+    use ordcode::with::explicit_discriminant::ExplicitDiscriminant;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+    enum V1 {
+        Empty,
+        Num(u32),
+        Pair(u8, u8),
+        #[serde(rename = "Named")]
+        Rec {
+            a: u16,
+        },
+    }
+    // same variants, reordered in source
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum V2 {
+        #[serde(rename = "Named")]
+        Rec {
+            a: u16,
+        },
+        Pair(u8, u8),
+        Num(u32),
+        Empty,
+    }
+    const DISCRIMINANTS: &[(&str, u32)] =
+        &[("Empty", 10), ("Num", 20), ("Pair", 30), ("Named", 300)];
+    impl ExplicitDiscriminant for V1 {
+        const DISCRIMINANTS: &'static [(&'static str, u32)] = DISCRIMINANTS;
+    }
+    impl ExplicitDiscriminant for V2 {
+        const DISCRIMINANTS: &'static [(&'static str, u32)] = DISCRIMINANTS;
+    }
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct R1(
+        #[serde(with = "ordcode::with::explicit_discriminant")] V1,
+        u8,
+    );
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct R2(
+        #[serde(with = "ordcode::with::explicit_discriminant")] V2,
+        u8,
+    );
+
+    let pairs = vec![
+        (V1::Empty, V2::Empty),
+        (V1::Num(7), V2::Num(7)),
+        (V1::Pair(1, 2), V2::Pair(1, 2)),
+        (V1::Rec { a: 5 }, V2::Rec { a: 5 }),
+    ];
+    let mut prev: Option<Vec<u8>> = None;
+    for (v1, v2) in pairs {
+        let b1 = ser_to_vec_ordered(&R1(v1.clone(), 9), Order::Ascending).unwrap();
+        let r2 = R2(v2, 9);
+        let b2 = ser_to_vec_ordered(&r2, Order::Ascending).unwrap();
+        assert_eq!(b1, b2);
+        assert_eq!(de_from_bytes_asc::<R1>(&b2).unwrap(), R1(v1.clone(), 9));
+        assert_eq!(de_from_bytes_asc::<R2>(&b1).unwrap(), r2);
+        // ordered by explicit discriminants
+        if let Some(p) = &prev {
+            assert!(p < &b1);
+        }
+        let desc = ser_to_vec_ordered(&R1(v1.clone(), 9), Order::Descending).unwrap();
+        assert_eq!(
+            de_from_bytes_ordered::<R1>(&mut desc.clone(), Order::Descending).unwrap(),
+            R1(v1, 9)
+        );
+        prev = Some(b1);
+    }
+    let empty = ser_to_vec_ordered(&R1(V1::Empty, 0), Order::Ascending).unwrap();
+    assert_eq!(empty, [21, 0]); // varint-encoded 10
+                                // varint discriminant 300, followed by `a`, then tuple field
+    let named = ser_to_vec_ordered(&R1(V1::Rec { a: 1 }, 0), Order::Ascending).unwrap();
+    let mut expected = vec![];
+    varint::VarUInt::varu_to_writer(&300_u32, &mut expected).unwrap();
+    expected.extend_from_slice(&[0, 1, 0]);
+    assert_eq!(named, expected);
+
+    // unknown discriminant, and variant without discriminant
+    assert!(de_from_bytes_asc::<R1>(&[23, 0]).is_err());
+    #[derive(Serialize)]
+    enum Partial {
+        A,
+        B,
+    }
+    impl ExplicitDiscriminant for Partial {
+        const DISCRIMINANTS: &'static [(&'static str, u32)] = &[("A", 1)];
+    }
+    #[derive(Serialize)]
+    struct P(#[serde(with = "ordcode::with::explicit_discriminant")] Partial);
+    assert!(ser_to_vec_ordered(&P(Partial::A), Order::Ascending).is_ok());
+    assert!(ser_to_vec_ordered(&P(Partial::B), Order::Ascending).is_err());
+}
+
+#[test]
+fn test_sink_writer() {
+    struct ArraySink {
+        buf: [u8; 64],
+        len: usize,
+    }
+    impl buf::ByteSink for ArraySink {
+        fn push(&mut self, byte: u8) -> Result {
+            *self.buf.get_mut(self.len).ok_or(Error::BufferOverflow {
+                needed: self.len + 1,
+                available: 64,
+            })? = byte;
+            self.len += 1;
+            Ok(())
+        }
+    }
+    let value = (vec!["a".to_string(), "bc".to_string()], 7_u32, Some("def"));
+    let mut tail_buf = [0_u8; 8];
+    let sink = ArraySink {
+        buf: [0; 64],
+        len: 0,
+    };
+    let mut writer = buf::SinkWriter::new(sink, &mut tail_buf);
+    value.serialize(&mut new_ser_asc(&mut writer)).unwrap();
+    let sink = writer.finalize().unwrap();
+    assert_eq!(sink.buf[..sink.len], serialize_asc(&value).unwrap()[..]);
+
+    // tail buffer too small for all sequence lengths
+    let mut tail_buf = [0_u8; 2];
+    let sink = ArraySink {
+        buf: [0; 64],
+        len: 0,
+    };
+    let mut writer = buf::SinkWriter::new(sink, &mut tail_buf);
+    assert!(matches!(
+        value.serialize(&mut new_ser_asc(&mut writer)),
+        Err(Error::BufferOverflow {
+            needed: 3,
+            available: 2
+        })
+    ));
+}
+
+#[derive(Copy, Clone, Default)]
+struct MaxLen;
+
+impl params::EncodingParams for MaxLen {
+    const ORDER: Order = Order::Ascending;
+    const ENDIANNESS: params::Endianness = params::Endianness::Big;
+}
+
+impl params::SerializerParams for MaxLen {
+    type SeqLenEncoder = varint::VarIntTailLenEncoder;
+    type DiscriminantEncoder = varint::VarIntDiscrEncoder;
+    const MAX_SEQ_LEN: u64 = 1000;
+}
+
+#[test]
+fn test_max_seq_len() {
+    use ordcode::params::LengthEncoder;
+    use std::collections::BTreeMap;
+
+    fn de<T: DeserializeOwned>(buf: &[u8]) -> Result<T> {
+        de_with(buf, MaxLen)
+    }
+    // crafted buffer: tail claims 3 billion elements, no element data
+    let claimed = 3_000_000_000;
+    let mut buf = vec![0_u8; varint::VarIntTailLenEncoder::calc_size(claimed)];
+    let mut writer = DeBytesWriter::new(&mut buf);
+    varint::VarIntTailLenEncoder::write(&mut writer, claimed).unwrap();
+    writer.is_complete().unwrap();
+
+    assert!(matches!(de::<Vec<u64>>(&buf), Err(Error::SequenceTooLong)));
+    assert!(matches!(
+        de::<BTreeMap<u8, u8>>(&buf),
+        Err(Error::SequenceTooLong)
+    ));
+    // no limit by default, fails on premature end of input instead
+    assert!(de_from_bytes_asc::<Vec<u64>>(&buf).is_err());
+
+    // sequences within the limit are fine
+    let v: Vec<u16> = (0..1000).collect();
+    assert_eq!(de::<Vec<u16>>(&serialize_asc(&v).unwrap()).unwrap(), v);
+    let v: Vec<u16> = (0..1001).collect();
+    assert!(matches!(
+        de::<Vec<u16>>(&serialize_asc(&v).unwrap()),
+        Err(Error::SequenceTooLong)
+    ));
+}
+
+#[derive(Copy, Clone, Default)]
+struct MaxStrLen;
+
+impl params::EncodingParams for MaxStrLen {
+    const ORDER: Order = Order::Ascending;
+    const ENDIANNESS: params::Endianness = params::Endianness::Big;
+}
+
+impl params::SerializerParams for MaxStrLen {
+    type SeqLenEncoder = varint::VarIntTailLenEncoder;
+    type DiscriminantEncoder = varint::VarIntDiscrEncoder;
+    const MAX_STR_LEN: usize = 8;
+}
+
+#[test]
+fn test_max_str_len() {
+    fn ser<T: Serialize>(value: &T) -> Result<Vec<u8>> {
+        ser_with(value, MaxStrLen)
+    }
+    let short = (1_u8, "12345678");
+    assert_eq!(ser(&short).unwrap(), serialize_asc(&short).unwrap());
+    assert!(matches!(
+        ser(&(1_u8, "123456789")),
+        Err(Error::StringTooLong)
+    ));
+    let bytes = serde_bytes::Bytes::new(&[0; 9]);
+    assert!(matches!(ser(&bytes), Err(Error::StringTooLong)));
+
+    // nothing is written after the limit is exceeded
+    let mut buf = Vec::new();
+    let mut ser = Serializer::new(&mut buf, MaxStrLen);
+    assert!(matches!(
+        "a long string".serialize(&mut ser),
+        Err(Error::StringTooLong)
+    ));
+    assert!(buf.is_empty());
+
+    // no limit by default
+    assert!(serialize_asc(&"x".repeat(10_000)).is_ok());
+}
+
+#[test]
+fn test_error_offset() {
+    #[derive(Serialize, Deserialize, Debug)]
+    enum E {
+        A(u8),
+        B,
+    }
+    let mut buf = serialize_asc(&(7_u16, E::A(1))).unwrap();
+    assert_eq!(buf, [0, 7, 1, 1]);
+    // corrupted discriminant varint (0 prefix denotes 9-byte varint, too long for `u32`)
+    buf[2] = 0;
+    let err = de_from_bytes_asc_at::<(u16, E)>(&buf).unwrap_err();
+    assert!(matches!(err.error, Error::InvalidVarintEncoding));
+    assert_eq!(err.offset, 2);
+    assert_eq!(err.to_string(), "invalid varint encoding at offset 2");
+    // error itself is the same as without offset
+    assert!(matches!(
+        de_from_bytes_asc::<(u16, E)>(&buf),
+        Err(Error::InvalidVarintEncoding)
+    ));
+
+    // corrupted string length in the tail: claims 9-byte varint, only 5 bytes remain
+    let mut buf = serialize_asc(&(7_u16, "abc", 1_u8)).unwrap();
+    assert_eq!(buf, [0, 7, b'a', b'b', b'c', 1, 7]);
+    *buf.last_mut().unwrap() = 0;
+    let err = de_from_bytes_asc_at::<(u16, String, u8)>(&buf).unwrap_err();
+    assert!(matches!(err.error, Error::PrematureEndOfInput));
+    assert_eq!(err.offset, 6);
+
+    // premature end of input in the head, after u16 and string
+    let buf = serialize_asc(&(7_u16, "abc")).unwrap();
+    let err = de_from_bytes_asc_at::<(u16, String, u32)>(&buf).unwrap_err();
+    assert!(matches!(err.error, Error::PrematureEndOfInput));
+    assert_eq!(err.offset, 5);
+
+    // errors not related to reading input are reported at head position
+    let buf = [0, 7, 5]; // varint discriminant 2
+    let err = de_from_bytes_asc_at::<(u16, E)>(&buf).unwrap_err();
+    assert!(matches!(err.error, Error::InvalidVariantIndex(2)));
+    assert_eq!(err.offset, 3);
+    assert!(matches!(Error::from(err), Error::InvalidVariantIndex(2)));
+}
+
+#[test]
+fn test_de_from_reader() {
+    use std::io::Cursor;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Rec {
+        id: u32,
+        name: String,
+        tags: Vec<u16>,
+    }
+    let recs = vec![
+        Rec {
+            id: 1,
+            name: "abc".into(),
+            tags: vec![1, 2],
+        },
+        Rec {
+            id: 2,
+            name: String::new(),
+            tags: vec![],
+        },
+    ];
+    // length-prefixed frames, as received from a socket
+    let mut stream = vec![];
+    for r in &recs {
+        let frame = serialize_asc(r).unwrap();
+        stream.push(frame.len() as u8);
+        stream.extend_from_slice(&frame);
+    }
+    let mut cursor = Cursor::new(stream);
+    for r in &recs {
+        let mut len = [0_u8];
+        std::io::Read::read_exact(&mut cursor, &mut len).unwrap();
+        let decoded: Rec = de_from_reader(&mut cursor, len[0] as usize).unwrap();
+        assert_eq!(&decoded, r);
+    }
+    assert_eq!(cursor.position() as usize, cursor.get_ref().len());
+
+    // fixed-size values are streamed without reading the rest of the frame
+    let data = serialize_asc(&(1_u16, 2_u32, "xyz")).unwrap();
+    let mut cursor = Cursor::new(&data);
+    let mut reader = buf::IoReader::new(&mut cursor, data.len());
+    let v: (u16, u32) = serde::Deserialize::deserialize(&mut new_de_asc(&mut reader)).unwrap();
+    assert_eq!(v, (1, 2));
+    assert_eq!(reader.remaining(), 4);
+    reader.into_inner().unwrap();
+    assert_eq!(cursor.position() as usize, data.len());
+
+    // frame is not fully consumed
+    let r: Result<u16> = de_from_reader(&data[..], data.len());
+    assert!(matches!(r, Err(Error::TrailingBytes)));
+    // stream is shorter than frame
+    let r: Result<(u16, u32, String)> = de_from_reader(&data[..5], data.len());
+    assert!(matches!(r.unwrap_err(), Error::PrematureEndOfInput));
+    // failed read leaves no padding in the buffer, which could be taken for frame data
+    let mut reader = buf::IoReader::new(&data[..5], data.len());
+    assert!(matches!(
+        buf::ReadBytes::peek(&mut reader, 8, |_| Ok(())),
+        Err(Error::PrematureEndOfInput)
+    ));
+    assert!(buf::ReadBytes::remaining_buffer(&mut reader).is_empty());
+    assert!(buf::ReadBytes::peek(&mut reader, 1, |_| Ok(())).is_err());
+}
+
+#[test]
+fn test_const_size() {
+    #[derive(Serialize)]
+    struct Rec {
+        a: u16,
+        b: (i64, char),
+        c: [f32; 3],
+    }
+    impl ConstSize for Rec {
+        const SIZE: usize = u16::SIZE + <(i64, char)>::SIZE + <[f32; 3]>::SIZE;
+    }
+    fn check<T: ConstSize>(v: &T) {
+        let size = calc_size_const(v, params::AscendingOrder).unwrap();
+        assert_eq!(size, calc_size_asc(v).unwrap());
+        assert_eq!(size, ser_to_vec_ordered(v, Order::Ascending).unwrap().len());
+        assert_eq!(
+            calc_size_const(v, params::SelfDescribing).unwrap(),
+            calc_size(v, params::SelfDescribing).unwrap()
+        );
+    }
+    assert_eq!(<(u32, u64, [u8; 4])>::SIZE, 16);
+    check(&(1u32, 2u64, [3u8; 4]));
+    check(&Rec {
+        a: 1,
+        b: (-2, 'x'),
+        c: [0.5; 3],
+    });
+    check(&(true, (), 1u128, [[0i8; 2]; 3]));
+}
+
+#[test]
+fn test_impl_const_size() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Key {
+        id: u64,
+        pos: (u16, i32),
+        tag: [u8; 3],
+        weight: primitives::NonNanF64,
+    }
+    impl_const_size!(Key {
+        id: u64,
+        pos: (u16, i32),
+        tag: [u8; 3],
+        weight: primitives::NonNanF64,
+    });
+    #[derive(Serialize)]
+    struct Pair(Key, bool);
+    impl_const_size!(Pair(Key, bool));
+
+    const KEY_LEN: usize = Key::SIZE;
+    assert_eq!(KEY_LEN, 8 + 2 + 4 + 3 + 8);
+    assert_eq!(Pair::SIZE, KEY_LEN + 1);
+
+    let key = Key {
+        id: 7,
+        pos: (1, -1),
+        tag: *b"abc",
+        weight: std::convert::TryFrom::try_from(0.5).unwrap(),
+    };
+    let mut buf = [0_u8; Key::SIZE];
+    ser_to_buf_asc_exact(&mut buf, &key).unwrap();
+    assert_eq!(de_from_bytes_asc_exact::<Key>(&buf).unwrap(), key);
+    assert_eq!(
+        calc_size_asc(&Pair(key, true)).unwrap(),
+        [0_u8; Pair::SIZE].len()
+    );
+}
+
+#[test]
+fn test_count_writer() {
+    use ordcode::buf::CountWriter;
+    use ordcode::params::SerializerParams;
+
+    #[derive(Serialize)]
+    enum E {
+        Unit,
+        New(u16),
+        Tuple(i8, String),
+        Struct { a: Option<u32>, b: Vec<u8> },
+    }
+    #[derive(Serialize)]
+    struct Rec<'a> {
+        id: u64,
+        name: &'a str,
+        tags: Vec<String>,
+        kind: E,
+    }
+    fn check<T: Serialize, P: SerializerParams>(v: &T, params: P) {
+        let mut counter = CountWriter::new();
+        v.serialize(&mut Serializer::new(&mut counter, params))
+            .unwrap();
+        assert_eq!(counter.count(), calc_size(v, params).unwrap());
+    }
+    fn check_all<T: Serialize>(v: &T) {
+        check(v, params::AscendingOrder);
+        check(v, params::PortableBinary);
+        check(v, params::SelfDescribing);
+        check(v, FixedLen);
+    }
+    check_all(&0_u8);
+    check_all(&-1_i64);
+    check_all(&1.5_f64);
+    check_all(&'x');
+    check_all(&"");
+    check_all(&"a".repeat(300));
+    check_all(&Some(1_u32));
+    check_all(&None::<u32>);
+    check_all(&vec![1_u16; 200]);
+    check_all(&(1_u8, "ab", [3_u8; 4]));
+    check_all(&vec![E::Unit, E::New(1), E::Tuple(-1, "x".into())]);
+    let mut map = HashMap::new();
+    map.insert("k".to_string(), vec![Some(1_i32), None]);
+    check_all(&map);
+    check_all(&Rec {
+        id: 1,
+        name: "rec",
+        tags: vec!["a".into(), "bc".into()],
+        kind: E::Struct {
+            a: Some(2),
+            b: vec![0; 130],
+        },
+    });
+}
+
+#[derive(Copy, Clone, Default)]
+struct Shallow;
+
+impl params::EncodingParams for Shallow {
+    const ORDER: Order = Order::Ascending;
+    const ENDIANNESS: params::Endianness = params::Endianness::Big;
+}
+
+impl params::SerializerParams for Shallow {
+    type SeqLenEncoder = varint::VarIntTailLenEncoder;
+    type DiscriminantEncoder = varint::VarIntDiscrEncoder;
+    const MAX_DEPTH: usize = 8;
+}
+
+#[test]
+fn test_max_depth() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Chain(Option<Box<Chain>>);
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Nest {
+        Leaf,
+        Node(Box<Nest>),
+    }
+    // crafted input: each `Some` byte adds two nesting levels, would overflow stack without limit
+    let mut buf = vec![1_u8; 1_000_000];
+    buf.push(0);
+    let err = de_with::<Chain, _>(&buf, params::AscendingOrder).unwrap_err();
+    assert!(matches!(err, Error::RecursionLimitExceeded));
+
+    // each `Node` variant adds a nesting level
+    let leaf = serialize_asc(&Nest::Leaf).unwrap();
+    let node = serialize_asc(&Nest::Node(Box::new(Nest::Leaf))).unwrap();
+    let prefix = &node[..node.len() - leaf.len()];
+    let mut buf = prefix.repeat(1_000_000);
+    buf.extend_from_slice(&leaf);
+    let err = de_with::<Nest, _>(&buf, params::AscendingOrder).unwrap_err();
+    assert!(matches!(err, Error::RecursionLimitExceeded));
+
+    let mut chain = Chain(None);
+    for _ in 0..60 {
+        chain = Chain(Some(Box::new(chain)));
+    }
+    let buf = serialize_asc(&chain).unwrap();
+    assert_eq!(
+        de_with::<Chain, _>(&buf, params::AscendingOrder).unwrap(),
+        chain
+    );
+
+    let buf = serialize_asc(&vec![vec![vec![1_u8]]]).unwrap();
+    let v: Vec<Vec<Vec<u8>>> = de_with(&buf, Shallow).unwrap();
+    assert_eq!(v, vec![vec![vec![1_u8]]]);
+    let buf = serialize_asc(&(((((((((1_u8,),),),),),),),),)).unwrap();
+    let err = de_with::<(((((((((u8,),),),),),),),),), _>(&buf, Shallow).unwrap_err();
+    assert!(matches!(err, Error::RecursionLimitExceeded));
+}
+
+#[test]
+fn test_into_remaining() {
+    use serde::Deserialize;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Rec {
+        id: u32,
+        name: String,
+        tags: Vec<String>,
+    }
+    let r1 = Rec {
+        id: 1,
+        name: "first".into(),
+        tags: vec!["a".into(), "bc".into()],
+    };
+    let r2 = Rec {
+        id: 2,
+        name: "second".into(),
+        tags: vec![],
+    };
+    // two values sharing one double-ended buffer
+    let mut buf = vec![0_u8; calc_size_asc(&r1).unwrap() + calc_size_asc(&r2).unwrap()];
+    let mut writer = DeBytesWriter::new(&mut buf);
+    r1.serialize(&mut new_ser_asc(&mut writer)).unwrap();
+    r2.serialize(&mut new_ser_asc(&mut writer)).unwrap();
+    writer.finalize().unwrap();
+
+    let mut de = new_de_asc(DeBytesReader::new(&buf));
+    assert_eq!(Rec::deserialize(&mut de).unwrap(), r1);
+    let rest = de.into_remaining();
+    assert_eq!(rest.len(), calc_size_asc(&r2).unwrap());
+    let mut de = new_de_asc(DeBytesReader::new(rest));
+    assert_eq!(Rec::deserialize(&mut de).unwrap(), r2);
+    assert!(de.into_remaining().is_empty());
+
+    // values concatenated with portable binary encoding
+    let mut buf = vec![];
+    r1.serialize(&mut Serializer::new(&mut buf, params::PortableBinary))
+        .unwrap();
+    r2.serialize(&mut Serializer::new(&mut buf, params::PortableBinary))
+        .unwrap();
+    let mut reader = DeBytesReader::new(&buf);
+    let v1 = Rec::deserialize(&mut Deserializer::new(&mut reader, params::PortableBinary)).unwrap();
+    let mut reader = DeBytesReader::new(reader.remaining_slice());
+    let v2 = Rec::deserialize(&mut Deserializer::new(&mut reader, params::PortableBinary)).unwrap();
+    assert_eq!((v1, v2), (r1, r2));
+    assert_eq!(reader.remaining(), 0);
+}
+
+#[test]
+fn test_ser_prefix() {
+    #[derive(Serialize, Clone)]
+    struct Key {
+        tenant: u32,
+        table: String,
+        id: Vec<u8>,
+        ts: i64,
+    }
+    #[derive(Serialize)]
+    struct Wrapped(Key);
+
+    let keys = vec![
+        Key {
+            tenant: 1,
+            table: "users".into(),
+            id: vec![1, 2, 3],
+            ts: -5,
+        },
+        Key {
+            tenant: 1,
+            table: "".into(),
+            id: vec![],
+            ts: 0,
+        },
+        Key {
+            tenant: 70_000,
+            table: "orders".into(),
+            id: vec![0xFF; 200],
+            ts: i64::MAX,
+        },
+    ];
+    for key in &keys {
+        for order in &[Order::Ascending, Order::Descending] {
+            let full = ser_to_vec_ordered(key, *order).unwrap();
+            let mut prev_len = 0;
+            for fields in 0..=5 {
+                let prefix = ser_prefix(key, fields, *order).unwrap();
+                assert!(full.starts_with(&prefix), "fields={}", fields);
+                assert!(prefix.len() >= prev_len);
+                prev_len = prefix.len();
+            }
+            assert_eq!(ser_prefix(key, 0, *order).unwrap(), []);
+            assert_eq!(ser_prefix(key, 1, *order).unwrap().len(), 4);
+            assert_eq!(
+                ser_prefix(key, 2, *order).unwrap().len(),
+                4 + key.table.len()
+            );
+            assert_eq!(
+                ser_prefix(&Wrapped(key.clone()), 3, *order).unwrap(),
+                ser_prefix(key, 3, *order).unwrap()
+            );
+            // tuple with the same leading fields gives the same prefix
+            let tuple = (key.tenant, &key.table, &key.id, key.ts);
+            assert_eq!(
+                ser_prefix(&tuple, 3, *order).unwrap(),
+                ser_prefix(key, 3, *order).unwrap()
+            );
+            assert!(ser_to_vec_ordered(&tuple, *order)
+                .unwrap()
+                .starts_with(&ser_prefix(key, 2, *order).unwrap()));
+        }
+    }
+    assert!(matches!(
+        ser_prefix(&1_u32, 1, Order::Ascending),
+        Err(Error::PrefixOfNonCompoundValue)
+    ));
+    assert!(matches!(
+        ser_prefix(&vec![1_u32, 2], 1, Order::Ascending),
+        Err(Error::PrefixOfNonCompoundValue)
+    ));
+}
+
+#[test]
+fn test_scan_bounds() {
+    #[derive(Serialize)]
+    struct Key<'a> {
+        tenant: u16,
+        id: u32,
+        name: &'a str,
+    }
+    let mut keys = Vec::new();
+    for tenant in 0..5_u16 {
+        for id in &[0, 1, 255, u32::MAX] {
+            for name in &["", "a", "zz"] {
+                keys.push((tenant, *id, *name));
+            }
+        }
+    }
+    let encode = |k: &(u16, u32, &str), order| {
+        ser_to_vec_ordered(
+            &Key {
+                tenant: k.0,
+                id: k.1,
+                name: k.2,
+            },
+            order,
+        )
+        .unwrap()
+    };
+    // checks that range selects exactly keys matching the predicate
+    let check = |range: KeyRange, order, pred: &dyn Fn(&(u16, u32, &str)) -> bool| {
+        for k in &keys {
+            assert_eq!(range.contains(&encode(k, order)), pred(k), "{k:?}");
+        }
+    };
+    for &order in &[Order::Ascending, Order::Descending] {
+        let bounds = ScanBounds::new(order).fields(1);
+        check(bounds.prefix(&(2_u16,)).unwrap(), order, &|k| k.0 == 2);
+        check(bounds.ge(&(2_u16,)).unwrap(), order, &|k| k.0 >= 2);
+        check(bounds.le(&(2_u16,)).unwrap(), order, &|k| k.0 <= 2);
+        check(bounds.between(&(1_u16,), &(3_u16,)).unwrap(), order, &|k| {
+            (1..=3).contains(&k.0)
+        });
+        let bounds = ScanBounds::new(order).fields(2);
+        check(bounds.prefix(&(4_u16, 255_u32)).unwrap(), order, &|k| {
+            (k.0, k.1) == (4, 255)
+        });
+        check(bounds.ge(&(1_u16, 255_u32)).unwrap(), order, &|k| {
+            (k.0, k.1) >= (1, 255)
+        });
+        check(bounds.le(&(1_u16, 255_u32)).unwrap(), order, &|k| {
+            (k.0, k.1) <= (1, 255)
+        });
+        check(
+            bounds.between(&(0_u16, 1_u32), &(3_u16, 0_u32)).unwrap(),
+            order,
+            &|k| (0, 1) <= (k.0, k.1) && (k.0, k.1) <= (3, 0),
+        );
+    }
+    // upper bound of the last possible prefix is open
+    let range = ScanBounds::new(Order::Ascending).ge(&(u16::MAX,)).unwrap();
+    assert_eq!(range.end, None);
+    let range = ScanBounds::new(Order::Descending).le(&(0_u16,)).unwrap();
+    assert_eq!(range.end, None);
+    assert!(matches!(
+        ScanBounds::new(Order::Ascending).prefix(&1_u8),
+        Err(Error::PrefixOfNonCompoundValue)
+    ));
+}
+
+#[test]
+fn test_flatten_not_supported() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Inner {
+        b: u32,
+        c: String,
+    }
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Outer {
+        a: u8,
+        #[serde(flatten)]
+        inner: Inner,
+    }
+    let v = Outer {
+        a: 1,
+        inner: Inner {
+            b: 2,
+            c: "c".into(),
+        },
+    };
+    assert!(matches!(calc_size_asc(&v), Err(Error::FlattenNotSupported)));
+    assert!(matches!(serialize_asc(&v), Err(Error::FlattenNotSupported)));
+    let mut buf = [0_u8; 64];
+    assert!(matches!(
+        ser_to_buf_ordered(&mut buf, &v, Order::Ascending),
+        Err(Error::FlattenNotSupported)
+    ));
+    // same fields without flattening can't be mistaken for flattened struct
+    let bytes = serialize_asc(&(1_u8, 2_u32, "c")).unwrap();
+    assert!(deserialize_asc::<Outer>(&bytes).is_err());
+}
+
+#[test]
+fn test_ser_de_desc() {
+    use ordcode::buf::{InvertWriter, IoWriter, VecWriter};
+    use serde::Deserialize;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Rec {
+        id: i32,
+        name: String,
+        data: Vec<u8>,
+        opt: Option<f64>,
+        tags: HashMap<String, u16>,
+    }
+    let mut tags = HashMap::new();
+    tags.insert("x".to_string(), 1_u16);
+    let recs = vec![
+        Rec {
+            id: -1,
+            name: "a".repeat(100),
+            data: (0..=255).collect(),
+            opt: Some(1.5),
+            tags,
+        },
+        Rec {
+            id: 0,
+            name: String::new(),
+            data: vec![],
+            opt: None,
+            tags: HashMap::new(),
+        },
+    ];
+    for rec in &recs {
+        let expected = serialize_desc(rec).unwrap();
+
+        // streamed descending output is the same as inverted ascending output
+        let mut writer = IoWriter::new(Vec::new());
+        rec.serialize(&mut new_ser_desc(&mut writer)).unwrap();
+        assert_eq!(writer.finalize().unwrap(), expected);
+
+        let mut writer = VecWriter::new();
+        rec.serialize(&mut new_ser_desc(&mut writer)).unwrap();
+        assert_eq!(writer.finalize(), expected);
+
+        let mut buf = vec![0_u8; expected.len()];
+        let mut writer = DeBytesWriter::new(&mut buf);
+        rec.serialize(&mut new_ser_desc(&mut writer)).unwrap();
+        writer.is_complete().unwrap();
+        assert_eq!(buf, expected);
+
+        let mut ser = new_ser_desc(VecWriter::new());
+        rec.serialize(&mut ser).unwrap();
+        let writer: InvertWriter<VecWriter> = ser.into_writer();
+        assert_eq!(writer.into_inner().finalize(), expected);
+
+        // reading without inverting input in place
+        let mut de = new_de_desc(DeBytesReader::new(&expected));
+        assert_eq!(&Rec::deserialize(&mut de).unwrap(), rec);
+        assert_eq!(de.into_reader().into_inner().remaining(), 0);
+    }
+}
+
+#[test]
+fn test_unknown_len_seq() {
+    use serde::ser::{SerializeSeq, Serializer};
+
+    // sequence which doesn't report its length, like one produced from an iterator
+    struct Evens(u32);
+    impl Serialize for Evens {
+        fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+            let mut seq = serializer.serialize_seq(None)?;
+            for v in (0..self.0).filter(|v| v % 2 == 0) {
+                seq.serialize_element(&v)?;
+            }
+            seq.end()
+        }
+    }
+    let v = (1_u8, Evens(10));
+    assert!(matches!(
+        calc_size_asc(&v),
+        Err(Error::SerializeSequenceMustHaveLength)
+    ));
+    assert!(matches!(
+        calc_size(&v, params::PortableBinary),
+        Err(Error::SerializeSequenceMustHaveLength)
+    ));
+    assert!(matches!(
+        serialize_asc(&v),
+        Err(Error::SerializeSequenceMustHaveLength)
+    ));
+    let mut buf = [0_u8; 64];
+    assert!(matches!(
+        ser_to_buf_ordered(&mut buf, &v, Order::Ascending),
+        Err(Error::SerializeSequenceMustHaveLength)
+    ));
+}
+
+#[test]
+fn test_enum_other_variant() {
+    // new variants are added after the catch-all variant of the old schema
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum New {
+        A,
+        B(String),
+        Reserved,
+        C { x: u32, y: Vec<u8> },
+        D,
+    }
+    #[derive(Deserialize, Debug, PartialEq)]
+    enum Old {
+        A,
+        B(String),
+        #[serde(other)]
+        Unknown,
+    }
+    #[derive(Deserialize, Debug, PartialEq)]
+    enum Strict {
+        A,
+        B(String),
+    }
+    fn roundtrip<T: DeserializeOwned, P: params::SerializerParams>(
+        v: &New,
+        params: P,
+    ) -> Result<T> {
+        let mut buf = vec![0_u8; calc_size(v, params).unwrap()];
+        let mut writer = DeBytesWriter::new(&mut buf);
+        v.serialize(&mut Serializer::new(&mut writer, params))
+            .unwrap();
+        writer.is_complete().unwrap();
+        let mut reader = DeBytesReader::new(&buf);
+        let rv = T::deserialize(&mut Deserializer::new(&mut reader, params))?;
+        assert_eq!(reader.remaining(), 0);
+        Ok(rv)
+    }
+    let b = New::B("b".into());
+    let c = New::C {
+        x: 1,
+        y: vec![1, 2],
+    };
+    assert_eq!(
+        roundtrip::<Old, _>(&New::A, params::AscendingOrder).unwrap(),
+        Old::A
+    );
+    assert_eq!(
+        roundtrip::<Old, _>(&b, params::AscendingOrder).unwrap(),
+        Old::B("b".into())
+    );
+    assert_eq!(
+        roundtrip::<Old, _>(&New::D, params::AscendingOrder).unwrap(),
+        Old::Unknown
+    );
+    assert_eq!(
+        roundtrip::<Old, _>(&New::D, params::PortableBinary).unwrap(),
+        Old::Unknown
+    );
+    // value of unknown variant can be skipped only with self-describing serialization
+    assert_eq!(
+        roundtrip::<Old, _>(&c, params::SelfDescribing).unwrap(),
+        Old::Unknown
+    );
+    assert_eq!(
+        roundtrip::<Old, _>(&New::D, params::SelfDescribing).unwrap(),
+        Old::Unknown
+    );
+
+    // without `#[serde(other)]` variant, unknown discriminant is an error
+    let err = roundtrip::<Strict, _>(&New::D, params::AscendingOrder).unwrap_err();
+    assert!(matches!(err, Error::InvalidVariantIndex(_)));
+    let err = roundtrip::<Strict, _>(&c, params::SelfDescribing).unwrap_err();
+    assert!(matches!(err, Error::InvalidVariantIndex(_)));
+}
+
+#[test]
+fn test_checksummed() {
+    // `[u8; N]` is serialized as is, so the trailer is CRC-32 check value for "123456789"
+    let buf = ser_to_vec_checksummed(b"123456789", Order::Ascending).unwrap();
+    assert_eq!(buf[9..], [0xcb, 0xf4, 0x39, 0x26]);
+
+    let v = (1_u32, "abc".to_string(), vec![Some(1.5_f64), None]);
+    for order in &[Order::Ascending, Order::Descending] {
+        let buf = ser_to_vec_checksummed(&v, *order).unwrap();
+        assert_eq!(
+            buf[..buf.len() - 4],
+            ser_to_vec_ordered(&v, *order).unwrap()[..]
+        );
+        let rt: (u32, String, Vec<Option<f64>>) =
+            de_from_bytes_checksummed(&mut buf.clone(), *order).unwrap();
+        assert_eq!(rt, v);
+
+        // single flipped bit in data or checksum is detected
+        for i in 0..buf.len() * 8 {
+            let mut corrupted = buf.clone();
+            corrupted[i / 8] ^= 1 << (i % 8);
+            let err = de_from_bytes_checksummed::<(u32, String, Vec<Option<f64>>)>(
+                &mut corrupted,
+                *order,
+            )
+            .unwrap_err();
+            assert!(matches!(err, Error::ChecksumMismatch));
+        }
+    }
+    assert!(matches!(
+        de_from_bytes_checksummed::<u8>(&mut [0, 0, 0], Order::Ascending),
+        Err(Error::PrematureEndOfInput)
+    ));
+}
+
+#[test]
+fn test_array_no_len() {
+    let arr = [1_u32, 2, 3, 4];
+    let vec = arr.to_vec();
+    let arr_buf = serialize_asc(&arr).unwrap();
+    let vec_buf = serialize_asc(&vec).unwrap();
+    // arrays are serialized as tuples, without length
+    assert_eq!(arr_buf.len(), 16);
+    assert_eq!(calc_size_asc(&arr).unwrap(), 16);
+    // sequences have length in the tail
+    assert_eq!(vec_buf.len(), 17);
+    assert_eq!(calc_size_asc(&vec).unwrap(), 17);
+    assert_eq!(vec_buf[..16], arr_buf[..]);
+    assert_eq!(vec_buf[16], 4 << 1 | 1);
+
+    let mut reader = DeBytesReader::new(&arr_buf);
+    let rt: [u32; 4] = serde::Deserialize::deserialize(&mut new_de_asc(&mut reader)).unwrap();
+    assert_eq!(rt, arr);
+    assert_eq!(reader.remaining(), 0);
+    assert_eq!(deserialize_asc::<Vec<u32>>(&vec_buf).unwrap(), vec);
+
+    // same with lengths in the head section
+    assert_eq!(calc_size(&arr, params::PortableBinary).unwrap(), 16);
+    assert!(calc_size(&vec, params::PortableBinary).unwrap() > 16);
+}
+
+#[test]
+fn test_buffer_error_sizes() {
+    let value = (1_u32, "abcd".to_string());
+    assert_eq!(calc_size_asc(&value).unwrap(), 9);
+
+    // string doesn't fit after `u32`, with its length in the tail
+    let mut buf = [0_u8; 6];
+    let err = ser_to_buf_ordered(&mut buf, &value, Order::Ascending).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::BufferOverflow {
+            needed: 9,
+            available: 6
+        }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "serialized data buffer overflow: needed 9 bytes, available 6"
+    );
+
+    // buffer larger than serialized data
+    let mut buf = [0_u8; 12];
+    let mut writer = DeBytesWriter::new(&mut buf);
+    value.serialize(&mut new_ser_asc(&mut writer)).unwrap();
+    assert!(matches!(
+        writer.is_complete(),
+        Err(Error::BufferUnderflow { remaining: 3 })
+    ));
+
+    // input not fully consumed
+    let buf = serialize_asc(&value).unwrap();
+    let mut reader = DeBytesReader::new(&buf);
+    let _: u32 = serde::Deserialize::deserialize(&mut new_de_asc(&mut reader)).unwrap();
+    assert!(matches!(
+        buf::ReadBytes::is_complete(&mut reader),
+        Err(Error::BufferUnderflow { remaining: 5 })
+    ));
+}
+
+#[derive(Copy, Clone, Default)]
+struct SortedMaps<const SELF_DESCRIBING: bool>;
+
+impl<const SELF_DESCRIBING: bool> params::EncodingParams for SortedMaps<SELF_DESCRIBING> {
+    const ORDER: Order = Order::Ascending;
+    const ENDIANNESS: params::Endianness = params::Endianness::Big;
+}
+
+impl<const SELF_DESCRIBING: bool> params::SerializerParams for SortedMaps<SELF_DESCRIBING> {
+    type SeqLenEncoder = varint::VarIntTailLenEncoder;
+    type DiscriminantEncoder = varint::VarIntDiscrEncoder;
+    const SELF_DESCRIBING: bool = SELF_DESCRIBING;
+    const SORT_MAP_ENTRIES: bool = true;
+}
+
+#[test]
+fn test_hashmap_deterministic() {
+    use std::collections::BTreeMap;
+
+    let entries: Vec<(String, Vec<u16>)> = (0..100_u16)
+        .map(|i| {
+            (
+                format!("key{}", u32::from(i) * 7919 % 100),
+                vec![i; usize::from(i % 5)],
+            )
+        })
+        .collect();
+    let btree: BTreeMap<_, _> = entries.iter().cloned().collect();
+    let expected = serialize_asc(&btree).unwrap();
+    // maps with different iteration order and capacity have the same encoding
+    let a: HashMap<_, _> = entries.iter().cloned().collect();
+    let mut b = HashMap::with_capacity(1000);
+    b.extend(entries.iter().rev().cloned());
+    for map in &[&a, &b] {
+        let bytes = ser_with(map, SortedMaps::<false>).unwrap();
+        assert_eq!(bytes, expected);
+        assert_eq!(calc_size(map, SortedMaps::<false>).unwrap(), expected.len());
+        let decoded: HashMap<String, Vec<u16>> = de_with(&bytes, SortedMaps::<false>).unwrap();
+        assert_eq!(&decoded, *map);
+        // without opt-in, entries are serialized in iteration order
+        let in_iter_order: Vec<_> = map.iter().collect();
+        assert_eq!(
+            serialize_asc(map).unwrap(),
+            serialize_asc(&in_iter_order).unwrap()
+        );
+    }
+
+    // same for self-describing data and nested maps
+    let nested: HashMap<u8, HashMap<String, Vec<u16>>> =
+        vec![(2, a.clone()), (1, b.clone())].into_iter().collect();
+    let nested_btree: BTreeMap<u8, &BTreeMap<_, _>> =
+        vec![(1, &btree), (2, &btree)].into_iter().collect();
+    assert_eq!(
+        ser_with(&nested, SortedMaps::<false>).unwrap(),
+        ser_with(&nested_btree, SortedMaps::<false>).unwrap()
+    );
+    assert_eq!(
+        ser_with(&nested, SortedMaps::<true>).unwrap(),
+        ser_with(&nested_btree, SortedMaps::<true>).unwrap()
+    );
+}
+
+#[derive(Copy, Clone, Default)]
+struct SkipIgnored;
+
+impl params::EncodingParams for SkipIgnored {
+    const ORDER: Order = Order::Ascending;
+    const ENDIANNESS: params::Endianness = params::Endianness::Big;
+}
+
+impl params::SerializerParams for SkipIgnored {
+    type SeqLenEncoder = varint::VarIntTailLenEncoder;
+    type DiscriminantEncoder = varint::VarIntDiscrEncoder;
+    const SKIP_IGNORED_AS_BYTES: bool = true;
+}
+
+#[test]
+fn test_ignored_trailing_str() {
+    #[derive(Serialize)]
+    struct New {
+        a: u16,
+        b: String,
+        c: String,
+    }
+    #[derive(Deserialize, Debug)]
+    struct Old {
+        a: u16,
+        b: String,
+        _c: serde::de::IgnoredAny,
+    }
+    let new = New {
+        a: 7,
+        b: "first".to_string(),
+        c: "trailing".to_string(),
+    };
+    let bytes = ser_with(&new, SkipIgnored).unwrap();
+    let old: Old = de_with(&bytes, SkipIgnored).unwrap();
+    assert_eq!(old.a, 7);
+    assert_eq!(old.b, "first");
+
+    // not opted in
+    assert!(matches!(
+        de_from_bytes_asc_exact::<Old>(&bytes),
+        Err(Error::DeserializeIgnoredAny)
+    ));
+
+    // ignored value must be a byte sequence: length of `c` is missing here
+    let bytes = ser_with(&(7u16, "first"), SkipIgnored).unwrap();
+    assert!(de_with::<Old, _>(&bytes, SkipIgnored).is_err());
+
+    // trailing scalar is rejected, not skipped as a byte sequence
+    let bytes = ser_with(&(7u16, "first", 0x0102_0304_u32), SkipIgnored).unwrap();
+    assert!(matches!(
+        de_with::<Old, _>(&bytes, SkipIgnored),
+        Err(Error::DeserializeIgnoredAny)
+    ));
+    let bytes = ser_with(&(7u16, "first", 3_u8), SkipIgnored).unwrap();
+    assert!(matches!(
+        de_with::<Old, _>(&bytes, SkipIgnored),
+        Err(Error::DeserializeIgnoredAny)
+    ));
+
+    // ignored value which is not the last one is rejected
+    #[derive(Deserialize, Debug)]
+    struct Middle {
+        _b: serde::de::IgnoredAny,
+        c: String,
+    }
+    let bytes = ser_with(&("first", "second"), SkipIgnored).unwrap();
+    assert!(matches!(
+        de_with::<Middle, _>(&bytes, SkipIgnored),
+        Err(Error::DeserializeIgnoredAny)
+    ));
+}
+
+#[test]
+fn test_calc_size_split() {
+    use ordcode::params::{LengthEncoder, PortableBinary};
+    use ordcode::varint::VarIntTailLenEncoder;
+
+    #[derive(Serialize)]
+    struct Rec {
+        id: u32,
+        name: String,
+        tags: Vec<u8>,
+    }
+    let rec = Rec {
+        id: 1,
+        name: "n".repeat(200),
+        tags: vec![1, 2, 3],
+    };
+    let (head, tail) = calc_size_split(&rec, params::AscendingOrder).unwrap();
+    assert_eq!(
+        tail,
+        VarIntTailLenEncoder::calc_size(200) + VarIntTailLenEncoder::calc_size(3)
+    );
+    assert_eq!(tail, 3);
+    assert_eq!(head, 4 + 200 + 3);
+    assert_eq!(head + tail, calc_size_asc(&rec).unwrap());
+    assert_eq!(head + tail, serialize_asc(&rec).unwrap().len());
+
+    // lengths are written to the head section
+    let (head, tail) = calc_size_split(&rec, PortableBinary).unwrap();
+    assert_eq!(tail, 0);
+    assert_eq!(head, calc_size(&rec, PortableBinary).unwrap());
+}
+
+#[test]
+fn test_size_calc_custom_serialize() {
+    use serde::ser::{SerializeStruct, Serializer};
+
+    // manual implementation, writes a few primitives of different kinds
+    struct Custom {
+        flags: u8,
+        id: u64,
+        score: f32,
+        name: &'static str,
+        parent: Option<u16>,
+    }
+    impl Serialize for Custom {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut st = serializer.serialize_struct("Custom", 5)?;
+            st.serialize_field("flags", &self.flags)?;
+            st.serialize_field("id", &self.id)?;
+            st.serialize_field("score", &self.score)?;
+            st.serialize_field("name", self.name)?;
+            st.serialize_field("parent", &self.parent)?;
+            st.end()
+        }
+    }
+    let v = Custom {
+        flags: 3,
+        id: 1 << 40,
+        score: 0.5,
+        name: "custom",
+        parent: Some(7),
+    };
+    let mut sc = SizeCalc::<params::AscendingOrder>::new();
+    v.serialize(&mut sc).unwrap();
+    assert_eq!(sc.head_size(), 1 + 8 + 4 + 6 + 1 + 2);
+    assert_eq!(sc.tail_size(), 1);
+    assert_eq!(sc.finish(), serialize_asc(&v).unwrap().len());
+
+    let mut sc = SizeCalc::<params::SelfDescribing>::new();
+    v.serialize(&mut sc).unwrap();
+    assert_eq!(sc.finish(), calc_size(&v, params::SelfDescribing).unwrap());
+}
+
+#[test]
+fn test_ser_to_buf_zeroed() {
+    let long = (1_u16, "a long string value".to_string());
+    let short = (2_u16, "ab".to_string());
+    for &order in &[Order::Ascending, Order::Descending] {
+        let expected = ser_to_vec_ordered(&short, order).unwrap();
+        // reuse buffer which holds previous, larger value
+        let mut buf = [0_u8; 64];
+        let gap = ser_to_buf_ordered_zeroed(&mut buf, &long, order).unwrap();
+        assert_eq!(gap.start, calc_size_asc(&long).unwrap());
+        let gap = ser_to_buf_ordered_zeroed(&mut buf, &short, order).unwrap();
+        assert_eq!(gap, expected.len()..64);
+        assert_eq!(&buf[..gap.start], &expected[..]);
+        assert!(buf[gap].iter().all(|&b| b == 0));
+
+        // without zeroing, bytes of the previous value remain in the gap
+        ser_to_buf_ordered(&mut buf, &long, order).unwrap();
+        let len = ser_to_buf_ordered(&mut buf, &short, order).unwrap();
+        assert_eq!(&buf[..len], &expected[..]);
+        assert!(buf[len..].iter().any(|&b| b != 0));
+    }
+
+    let mut buf = [0xaa_u8; 16];
+    let mut writer = DeBytesWriter::new(&mut buf);
+    "abc".serialize(&mut new_ser_asc(&mut writer)).unwrap();
+    assert_eq!(writer.finalize_zeroed().unwrap(), 4);
+    assert_eq!(buf[..4], [b'a', b'b', b'c', 7]);
+    assert_eq!(buf[4..], [0; 12]);
+}
+
+#[derive(Copy, Clone, Default)]
+struct PackedOptions<const NULLS_LAST: bool>;
+
+impl<const NULLS_LAST: bool> params::EncodingParams for PackedOptions<NULLS_LAST> {
+    const ORDER: Order = Order::Ascending;
+    const ENDIANNESS: params::Endianness = params::Endianness::Big;
+}
+
+impl<const NULLS_LAST: bool> params::SerializerParams for PackedOptions<NULLS_LAST> {
+    type SeqLenEncoder = varint::VarIntTailLenEncoder;
+    type DiscriminantEncoder = varint::VarIntDiscrEncoder;
+    const OPTION_NULLS_LAST: bool = NULLS_LAST;
+    const PACKED_OPTIONS: bool = true;
+}
+
+#[test]
+fn test_packed_options() {
+    fn ser<T: Serialize, P: params::SerializerParams + Copy>(v: &T, params: P) -> Vec<u8> {
+        ser_with(v, params).unwrap()
+    }
+    type O3 = Option<Option<Option<u8>>>;
+    let values: &[O3] = &[
+        None,
+        Some(None),
+        Some(Some(None)),
+        Some(Some(Some(0))),
+        Some(Some(Some(5))),
+    ];
+    let packed: Vec<_> = values
+        .iter()
+        .map(|v| ser(v, PackedOptions::<false>))
+        .collect();
+    assert_eq!(packed[0], [0]);
+    assert_eq!(packed[1], [1]);
+    assert_eq!(packed[2], [2]);
+    assert_eq!(packed[4], [3, 5]);
+    for (v, buf) in values.iter().zip(&packed) {
+        assert!(buf.len() < ser(v, params::AscendingOrder).len() || v.is_none());
+        assert_eq!(&de_with::<O3, _>(buf, PackedOptions::<false>).unwrap(), v);
+    }
+    for w in packed.windows(2) {
+        assert!(w[0] < w[1]);
+    }
+    let nulls_last: Vec<_> = values
+        .iter()
+        .map(|v| ser(v, PackedOptions::<true>))
+        .collect();
+    for (v, buf) in values.iter().zip(&nulls_last) {
+        assert_eq!(&de_with::<O3, _>(buf, PackedOptions::<true>).unwrap(), v);
+    }
+    // Some(Some(Some(0))) < Some(Some(Some(5))) < Some(Some(None)) < Some(None) < None
+    let nulls_last: Vec<_> = [3, 4, 2, 1, 0].iter().map(|&i| &nulls_last[i]).collect();
+    for w in nulls_last.windows(2) {
+        assert!(w[0] < w[1]);
+    }
+
+    // single option is encoded same as without packing
+    let v = (Some(1_u16), None::<u8>, Some("a".to_string()));
+    assert_eq!(
+        ser(&v, PackedOptions::<false>),
+        ser(&v, params::AscendingOrder)
+    );
+    // options nested in compound values and newtypes
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Wrap(Option<u8>);
+    let v = vec![(Some(Wrap(Some(1))), Some(Some(vec![Some(2_u8)])))];
+    let buf = ser(&v, PackedOptions::<false>);
+    let rt: Vec<(Option<Wrap>, Option<Option<Vec<Option<u8>>>>)> =
+        de_with(&buf, PackedOptions::<false>).unwrap();
+    assert_eq!(rt, v);
+
+    // discriminant deeper than the type
+    assert!(matches!(
+        de_with::<O3, _>(&[4, 5], PackedOptions::<false>).unwrap_err(),
+        Error::InvalidTagEncoding
+    ));
+}
+
+#[test]
+fn test_leading_fields() {
+    #[derive(Serialize)]
+    struct Key {
+        tenant: u32,
+        ts: u64,
+        name: String,
+    }
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct KeyHead {
+        tenant: u32,
+        ts: u64,
+    }
+    let key = Key {
+        tenant: 5,
+        ts: 1 << 40,
+        name: "name".to_string(),
+    };
+    let long = (3_u8, "abc".to_string(), "def".to_string());
+    for &order in &[Order::Ascending, Order::Descending] {
+        let buf = ser_to_vec_ordered(&(7_u32, 1_000_000_u64, "tail"), order).unwrap();
+        let head: (u32, u64) = de_from_bytes_ordered(&mut buf.clone(), order).unwrap();
+        assert_eq!(head, (7, 1_000_000));
+        assert!(matches!(
+            de_from_bytes_ordered_exact::<(u32, u64)>(&mut buf.clone(), order),
+            Err(Error::TrailingBytes)
+        ));
+
+        let buf = ser_to_vec_ordered(&key, order).unwrap();
+        let head: KeyHead = de_from_bytes_ordered(&mut buf.clone(), order).unwrap();
+        assert_eq!(
+            head,
+            KeyHead {
+                tenant: 5,
+                ts: 1 << 40
+            }
+        );
+        // variable-size leading field
+        let buf = ser_to_vec_ordered(&long, order).unwrap();
+        let head: (u8, String) = de_from_bytes_ordered(&mut buf.clone(), order).unwrap();
+        assert_eq!(head, (3, "abc".to_string()));
+    }
+}
+
+#[test]
+fn test_de_consumed() {
+    // adjacent fixed-size values
+    let mut buf = serialize_asc(&(1_u16, -5_i32)).unwrap();
+    buf.extend(serialize_asc(&(2_u16, 7_i32)).unwrap());
+    let (first, len) = de_from_bytes_asc_consumed::<(u16, i32)>(&buf).unwrap();
+    assert_eq!((first, len), ((1, -5), 6));
+    let (second, len2) = de_from_bytes_asc_consumed::<(u16, i32)>(&buf[len..]).unwrap();
+    assert_eq!((second, len + len2), ((2, 7), buf.len()));
+
+    // consumed count includes the tail section
+    let buf = serialize_asc(&(1_u8, "abc", vec![1_u16, 2])).unwrap();
+    let (_, len) = de_from_bytes_asc_consumed::<(u8, String, Vec<u16>)>(&buf).unwrap();
+    assert_eq!(len, buf.len());
+    assert_eq!(len, 1 + 3 + 4 + 2);
+
+    // adjacent variable-length values without tail section
+    let size = calc_size(&"first", params::PortableBinary).unwrap();
+    let mut buf = vec![0_u8; size + calc_size(&"second", params::PortableBinary).unwrap()];
+    let mut writer = DeBytesWriter::new(&mut buf);
+    "first"
+        .serialize(&mut Serializer::new(&mut writer, params::PortableBinary))
+        .unwrap();
+    "second"
+        .serialize(&mut Serializer::new(&mut writer, params::PortableBinary))
+        .unwrap();
+    writer.is_complete().unwrap();
+    let (first, len) = de_from_bytes_consumed::<&str, _>(&buf, params::PortableBinary).unwrap();
+    assert_eq!((first, len), ("first", size));
+    let (second, _) =
+        de_from_bytes_consumed::<&str, _>(&buf[len..], params::PortableBinary).unwrap();
+    assert_eq!(second, "second");
+}
+
+#[test]
+fn test_invalid_variant_index() {
+    use ordcode::params::LengthEncoder;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Three {
+        A,
+        B(u8),
+        C { x: u16 },
+    }
+    fn discr(v: u32) -> Vec<u8> {
+        let mut writer = buf::VecWriter::new();
+        varint::VarIntDiscrEncoder::write(&mut writer, v).unwrap();
+        let mut buf = writer.finalize();
+        buf.push(0);
+        buf
+    }
+    assert_eq!(deserialize_asc::<Three>(&discr(0)).unwrap(), Three::A);
+    assert_eq!(deserialize_asc::<Three>(&discr(1)).unwrap(), Three::B(0));
+    for &idx in &[3, 200, u32::MAX] {
+        let err = deserialize_asc::<Three>(&discr(idx)).unwrap_err();
+        assert!(
+            matches!(err, Error::InvalidVariantIndex(i) if i == idx),
+            "{:?}",
+            err
+        );
+        assert!(err.to_string().contains(&format!("index {}", idx)));
+    }
+}
+
+#[test]
+fn test_writer_flush() {
+    #[derive(Default)]
+    struct FlushCounter {
+        data: Vec<u8>,
+        flushed: Vec<usize>,
+    }
+    impl buf::WriteBytes for FlushCounter {
+        fn write(&mut self, value: &[u8]) -> Result {
+            self.data.extend_from_slice(value);
+            Ok(())
+        }
+        fn flush(&mut self) -> Result {
+            self.flushed.push(self.data.len());
+            Ok(())
+        }
+    }
+    impl buf::TailWriteBytes for FlushCounter {
+        fn write_tail(&mut self, value: &[u8]) -> Result {
+            self.data.extend_from_slice(value);
+            Ok(())
+        }
+    }
+    let mut writer = FlushCounter::default();
+    let mut ser = new_ser_asc(&mut writer);
+    1_u8.serialize(&mut ser).unwrap();
+    ser.finish().unwrap();
+    (2_u16, 3_u32).serialize(&mut ser).unwrap();
+    ser.finish().unwrap();
+    assert_eq!(writer.flushed, [1, 7]);
+
+    // forwarded by writer adapters
+    let mut writer = FlushCounter::default();
+    let mut ser = new_ser_desc(&mut writer);
+    "ab".serialize(&mut ser).unwrap();
+    ser.finish().unwrap();
+    assert_eq!(writer.flushed, [3]);
+
+    // stream serializer flushes after each value
+    let mut writer = FlushCounter::default();
+    let mut stream = StreamSerializer::new(&mut writer, Order::Ascending);
+    for v in &["a", "bc", "def"] {
+        stream.write_value(v).unwrap();
+    }
+    assert_eq!(writer.flushed, [2, 5, 9]);
+}
+
+#[test]
+fn test_frames() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+    struct Rec {
+        id: u32,
+        name: String,
+    }
+    let recs: Vec<Rec> = (0..3)
+        .map(|i| Rec {
+            id: i,
+            name: "r".repeat(i as usize * 100),
+        })
+        .collect();
+    for &order in &[Order::Ascending, Order::Descending] {
+        let mut log = Vec::new();
+        for rec in &recs {
+            let frame = frame_to_vec(rec, order).unwrap();
+            assert_eq!(
+                frame.len(),
+                varint::VarUInt::varu_encoded_len(&(calc_size_asc(rec).unwrap() as u64)) as usize
+                    + calc_size_asc(rec).unwrap()
+            );
+            log.extend(frame);
+        }
+        let mut rest = &log[..];
+        let mut decoded = Vec::new();
+        while !rest.is_empty() {
+            let (rec, tail): (Rec, _) = read_frame(rest, order).unwrap();
+            decoded.push(rec);
+            rest = tail;
+        }
+        assert_eq!(decoded, recs);
+    }
+    // borrowing from ascending frames
+    let log = frame_to_vec(&("key", 1_u8), Order::Ascending).unwrap();
+    let ((k, v), rest): ((&str, u8), _) = read_frame(&log, Order::Ascending).unwrap();
+    assert_eq!((k, v, rest.len()), ("key", 1, 0));
+
+    // truncated frame, and frame with unconsumed bytes
+    let log = frame_to_vec(&recs[1], Order::Ascending).unwrap();
+    assert!(matches!(
+        read_frame::<Rec>(&log[..log.len() - 1], Order::Ascending).unwrap_err(),
+        Error::PrematureEndOfInput
+    ));
+    assert!(matches!(
+        read_frame::<u32>(&log, Order::Ascending).unwrap_err(),
+        Error::TrailingBytes
+    ));
+}
+
+#[test]
+fn test_debug_clone() {
+    use ordcode::buf::{CountWriter, DeBytesReader};
+    use ordcode::params::AscendingOrder;
+
+    #[derive(Debug)]
+    struct KeyBuilder<'a> {
+        ser: Serializer<Vec<u8>, AscendingOrder>,
+        de: Deserializer<DeBytesReader<'a>, AscendingOrder>,
+    }
+    let mut ser = Serializer::new(Vec::new(), AscendingOrder);
+    1_u8.serialize(&mut ser).unwrap();
+    let mut fork = ser.clone();
+    2_u8.serialize(&mut ser).unwrap();
+    3_u8.serialize(&mut fork).unwrap();
+    assert_eq!(ser.clone().into_writer(), [1, 2]);
+    assert_eq!(fork.into_writer(), [1, 3]);
+
+    let buf = [1_u8, 2];
+    let builder = KeyBuilder {
+        ser,
+        de: Deserializer::new(DeBytesReader::new(&buf), AscendingOrder),
+    };
+    let s = format!("{:?}", builder);
+    assert!(s.contains("AscendingOrder"), "{}", s);
+    assert!(s.contains("DeBytesReader"), "{}", s);
+
+    let mut ser = builder.ser.clone();
+    4_u8.serialize(&mut ser).unwrap();
+    assert_eq!(ser.into_writer(), [1, 2, 4]);
+    assert_eq!(builder.ser.into_writer(), [1, 2]);
+
+    let mut de = builder.de.clone();
+    assert_eq!(<u8 as serde::Deserialize>::deserialize(&mut de).unwrap(), 1);
+    assert_eq!(
+        <(u8, u8) as serde::Deserialize>::deserialize(&mut builder.de.clone()).unwrap(),
+        (1, 2)
+    );
+
+    let counter = Serializer::new(CountWriter::new(), AscendingOrder);
+    assert!(format!("{:?}", counter.clone()).contains("CountWriter"));
+}
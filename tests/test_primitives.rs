@@ -256,6 +256,103 @@ fn test_cmp_f64_desc() {
     assert!(encode(f64::INFINITY) > encode(f64::NAN));
 }
 
+#[test]
+fn test_finite_floats() {
+    for v in &[f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        let mut s = vec![];
+        assert!(matches!(
+            v.to_writer(&mut s, FiniteFloats),
+            Err(Error::NonFiniteFloat)
+        ));
+        assert!(matches!(
+            (*v as f32).to_writer(&mut s, FiniteFloats),
+            Err(Error::NonFiniteFloat)
+        ));
+    }
+    // finite values are encoded as with `AscendingOrder`
+    for v in &[f64::MIN, -1.5, -0.0, 0.0, f64::MIN_POSITIVE, f64::MAX] {
+        let mut finite = vec![];
+        v.to_writer(&mut finite, FiniteFloats).unwrap();
+        let mut asc = vec![];
+        v.to_writer(&mut asc, AscendingOrder).unwrap();
+        assert_eq!(finite, asc);
+    }
+    let mut neg = vec![];
+    (-0.0f32).to_writer(&mut neg, FiniteFloats).unwrap();
+    let mut pos = vec![];
+    0.0f32.to_writer(&mut pos, FiniteFloats).unwrap();
+    assert!(neg < pos);
+}
+
+#[test]
+fn test_non_nan_f64() {
+    use ordcode::primitives::NonNanF64;
+    use std::convert::TryFrom;
+
+    assert!(matches!(
+        NonNanF64::try_from(f64::NAN),
+        Err(Error::NonFiniteFloat)
+    ));
+    assert!(matches!(
+        NonNanF64::try_from(-f64::NAN),
+        Err(Error::NonFiniteFloat)
+    ));
+    let vals = [
+        f64::NEG_INFINITY,
+        f64::MIN,
+        -1.5,
+        -f64::MIN_POSITIVE,
+        -0.0,
+        0.0,
+        f64::MIN_POSITIVE,
+        2.0,
+        f64::MAX,
+        f64::INFINITY,
+    ];
+    let enc = |v: NonNanF64| {
+        let mut buf = vec![];
+        v.to_writer(&mut buf, AscendingOrder).unwrap();
+        buf
+    };
+    for a in &vals {
+        let na = NonNanF64::try_from(*a).unwrap();
+        assert_eq!(na.get(), *a);
+        let buf = enc(na);
+        assert_eq!(
+            NonNanF64::from_reader(&mut DeBytesReader::new(&buf), AscendingOrder).unwrap(),
+            na
+        );
+        for b in &vals {
+            let nb = NonNanF64::try_from(*b).unwrap();
+            assert_eq!(na.partial_cmp(&nb), a.partial_cmp(b));
+            assert_eq!(na.cmp(&nb), enc(na).cmp(&enc(nb)));
+        }
+    }
+    // negative zero is stored as positive zero
+    assert_eq!(
+        NonNanF64::try_from(-0.0).unwrap().get().to_bits(),
+        0.0_f64.to_bits()
+    );
+
+    // deserializing NaN is rejected
+    let mut nan = vec![];
+    f64::NAN.to_writer(&mut nan, AscendingOrder).unwrap();
+    assert!(matches!(
+        NonNanF64::from_reader(&mut DeBytesReader::new(&nan), AscendingOrder),
+        Err(Error::NonFiniteFloat)
+    ));
+    let one = NonNanF64::try_from(1.0).unwrap();
+    let buf = ser_to_vec_ordered(&one, Order::Descending).unwrap();
+    assert_eq!(
+        de_from_bytes_ordered::<NonNanF64>(&mut buf.clone(), Order::Descending).unwrap(),
+        one
+    );
+    assert!(de_from_bytes_asc::<NonNanF64>(
+        &ser_to_vec_ordered(&f64::NAN, Order::Ascending).unwrap()
+    )
+    .is_err());
+}
+
 #[test]
 fn test_esc_enclen_asc() {
     let v = vec![0, 0, 0xF8, 3, 1, 0, 0xFF, 0xF8, 0xFE, 1, 2, 7, 0, 1, 0xFE];
@@ -306,3 +403,1396 @@ fn bytes_esc_nested_asc() {
 fn bytes_esc_nested_desc() {
     cmp_esc_bytes_nested(DescendingOrder);
 }
+
+#[derive(Copy, Clone, Default)]
+struct CanonicalAsc;
+
+impl EncodingParams for CanonicalAsc {
+    const ORDER: Order = Order::Ascending;
+    const ENDIANNESS: Endianness = Endianness::Big;
+    const CANONICAL_NAN: bool = true;
+}
+
+#[test]
+fn test_canonical_nan_f32() {
+    fn encode(v: f32) -> Vec<u8> {
+        let mut s = vec![];
+        v.to_writer(&mut s, CanonicalAsc).unwrap();
+        s
+    }
+    let nans = [
+        f32::NAN,
+        -f32::NAN,
+        f32::from_bits(0x7f80_0001), // signaling NaN
+        f32::from_bits(0x7fc0_1234),
+        f32::from_bits(0xffff_ffff),
+    ];
+    let enc = encode(f32::NAN);
+    for v in &nans {
+        assert_eq!(encode(*v), enc);
+    }
+    assert!(encode(f32::INFINITY) < enc);
+    assert_eq!(encode(-0.0), encode(0.0));
+    let d = f32::from_reader(DeBytesReader::new(&enc), CanonicalAsc).unwrap();
+    assert_eq!(d.to_bits(), 0x7fc0_0000);
+    let d = f32::from_reader(DeBytesReader::new(&encode(-0.0)), CanonicalAsc).unwrap();
+    assert_eq!(d.to_bits(), 0);
+}
+
+#[test]
+fn test_canonical_nan_f64() {
+    fn encode(v: f64) -> Vec<u8> {
+        let mut s = vec![];
+        v.to_writer(&mut s, CanonicalAsc).unwrap();
+        s
+    }
+    let nans = [
+        f64::NAN,
+        -f64::NAN,
+        f64::from_bits(0x7ff0_0000_0000_0001), // signaling NaN
+        f64::from_bits(0x7ff8_dead_beef_0000),
+        f64::from_bits(0xffff_ffff_ffff_ffff),
+    ];
+    let enc = encode(f64::NAN);
+    for v in &nans {
+        assert_eq!(encode(*v), enc);
+    }
+    assert!(encode(f64::INFINITY) < enc);
+    assert_eq!(encode(-0.0), encode(0.0));
+    let d = f64::from_reader(DeBytesReader::new(&enc), CanonicalAsc).unwrap();
+    assert_eq!(d.to_bits(), 0x7ff8_0000_0000_0000);
+    let d = f64::from_reader(DeBytesReader::new(&encode(-0.0)), CanonicalAsc).unwrap();
+    assert_eq!(d.to_bits(), 0);
+    // canonicalization does not affect ordinary values
+    assert_eq!(encode(-1.5), {
+        let mut s = vec![];
+        (-1.5f64).to_writer(&mut s, AscendingOrder).unwrap();
+        s
+    });
+}
+
+#[test]
+fn test_nonzero() {
+    use std::num::{NonZeroI32, NonZeroU64, NonZeroU8};
+    let mut s = vec![];
+    NonZeroU64::new(1234)
+        .unwrap()
+        .to_writer(&mut s, AscendingOrder)
+        .unwrap();
+    let mut s2 = vec![];
+    1234u64.to_writer(&mut s2, AscendingOrder).unwrap();
+    assert_eq!(s, s2);
+    let v = NonZeroU64::from_reader(DeBytesReader::new(&s), AscendingOrder).unwrap();
+    assert_eq!(v.get(), 1234);
+
+    let mut s = vec![];
+    NonZeroI32::new(-5)
+        .unwrap()
+        .to_writer(&mut s, DescendingOrder)
+        .unwrap();
+    let v = NonZeroI32::from_reader(DeBytesReader::new(&s), DescendingOrder).unwrap();
+    assert_eq!(v.get(), -5);
+
+    let mut s = vec![];
+    0u64.to_writer(&mut s, AscendingOrder).unwrap();
+    assert!(matches!(
+        NonZeroU64::from_reader(DeBytesReader::new(&s), AscendingOrder),
+        Err(Error::UnexpectedZero)
+    ));
+    let mut s = vec![];
+    0i32.to_writer(&mut s, DescendingOrder).unwrap();
+    assert!(matches!(
+        NonZeroI32::from_reader(DeBytesReader::new(&s), DescendingOrder),
+        Err(Error::UnexpectedZero)
+    ));
+    assert!(matches!(
+        NonZeroU8::from_reader(DeBytesReader::new(&[0]), AscendingOrder),
+        Err(Error::UnexpectedZero)
+    ));
+}
+
+#[test]
+fn test_successor_key() {
+    use ordcode::primitives::{successor_key, successor_key_in_place};
+    assert_eq!(successor_key(&[]), None);
+    assert_eq!(successor_key(&[0xFF, 0xFF, 0xFF]), None);
+    assert_eq!(successor_key(&[0]), Some(vec![1]));
+    assert_eq!(successor_key(&[5, 0xFF, 0xFF]), Some(vec![6]));
+    assert_eq!(successor_key(&[5, 0xFE, 0xFF]), Some(vec![5, 0xFF]));
+    let mut buf = [0xFF_u8, 7, 0xFF];
+    assert_eq!(successor_key_in_place(&mut buf), Some(2));
+    assert_eq!(buf, [0xFF, 8, 0xFF]);
+
+    // successor bounds all keys with given prefix, for both orders
+    for order in &[Order::Ascending, Order::Descending] {
+        let key = |a: u16, b: u32| -> Vec<u8> {
+            let mut s = vec![];
+            a.to_writer(&mut s, AscendingOrder).unwrap();
+            b.to_writer(&mut s, AscendingOrder).unwrap();
+            if matches!(order, Order::Descending) {
+                primitives::invert_buffer(&mut s);
+            }
+            s
+        };
+        let prefix = key(0x00FF, 0);
+        let prefix = &prefix[..2];
+        let upper = successor_key(prefix).unwrap();
+        for b in &[0, 1, u32::MAX] {
+            let k = key(0x00FF, *b);
+            assert!(k.as_slice() >= prefix && k < upper);
+        }
+        for a in &[0x00FE, 0x0100] {
+            let k = key(*a, 0);
+            assert!(k.as_slice() < prefix || k >= upper);
+        }
+    }
+}
+
+#[test]
+fn test_duration() {
+    use ordcode::primitives::{deserialize_duration, serialize_duration, DurationKey};
+    use std::time::Duration;
+
+    let durations = [
+        Duration::from_nanos(0),
+        Duration::from_nanos(1),
+        Duration::from_millis(999),
+        Duration::from_secs(1),
+        Duration::new(1, 1),
+        Duration::from_secs(u64::MAX),
+    ];
+    for order in &[Order::Ascending, Order::Descending] {
+        let mut prev: Option<Vec<u8>> = None;
+        for d in &durations {
+            let mut buf = vec![];
+            match order {
+                Order::Descending => serialize_duration(d, &mut buf, DescendingOrder),
+                _ => serialize_duration(d, &mut buf, AscendingOrder),
+            }
+            .unwrap();
+            assert_eq!(buf.len(), 12);
+            let d2 = match order {
+                Order::Descending => {
+                    deserialize_duration(DeBytesReader::new(&buf), DescendingOrder)
+                }
+                _ => deserialize_duration(DeBytesReader::new(&buf), AscendingOrder),
+            }
+            .unwrap();
+            assert_eq!(*d, d2);
+            // serde wrapper produces the same bytes
+            assert_eq!(ser_to_vec_ordered(&DurationKey(*d), *order).unwrap(), buf);
+            let mut b = buf.clone();
+            let k: DurationKey = de_from_bytes_ordered(&mut b, *order).unwrap();
+            assert_eq!(k.0, *d);
+            if let Some(p) = prev {
+                match order {
+                    Order::Descending => assert!(p > buf),
+                    _ => assert!(p < buf),
+                }
+            }
+            prev = Some(buf);
+        }
+    }
+    let mut bad = vec![];
+    1_u64.to_writer(&mut bad, AscendingOrder).unwrap();
+    1_000_000_000_u32
+        .to_writer(&mut bad, AscendingOrder)
+        .unwrap();
+    assert!(matches!(
+        deserialize_duration(DeBytesReader::new(&bad), AscendingOrder),
+        Err(Error::InvalidDuration)
+    ));
+}
+
+#[test]
+fn test_ip_addr() {
+    use ordcode::primitives::{
+        deserialize_ip_addr, serialize_ip_addr, IpAddrKey, Ipv4AddrKey, Ipv6AddrKey,
+    };
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    let addrs: Vec<IpAddr> = [
+        "0.0.0.0",
+        "10.0.0.1",
+        "10.0.0.2",
+        "255.255.255.255",
+        "::",
+        "::1",
+    ]
+    .iter()
+    .map(|s| s.parse().unwrap())
+    .collect();
+    for order in &[Order::Ascending, Order::Descending] {
+        let mut prev: Option<Vec<u8>> = None;
+        for a in &addrs {
+            let mut buf = vec![];
+            match order {
+                Order::Descending => serialize_ip_addr(a, &mut buf, DescendingOrder),
+                _ => serialize_ip_addr(a, &mut buf, AscendingOrder),
+            }
+            .unwrap();
+            assert_eq!(buf.len(), if a.is_ipv4() { 5 } else { 17 });
+            let a2 = match order {
+                Order::Descending => deserialize_ip_addr(DeBytesReader::new(&buf), DescendingOrder),
+                _ => deserialize_ip_addr(DeBytesReader::new(&buf), AscendingOrder),
+            }
+            .unwrap();
+            assert_eq!(*a, a2);
+            // serde wrappers produce the same bytes
+            assert_eq!(ser_to_vec_ordered(&IpAddrKey(*a), *order).unwrap(), buf);
+            let mut b = buf.clone();
+            let k: IpAddrKey = de_from_bytes_ordered(&mut b, *order).unwrap();
+            assert_eq!(k.0, *a);
+            match a {
+                IpAddr::V4(v4) => {
+                    let mut b = ser_to_vec_ordered(&Ipv4AddrKey(*v4), *order).unwrap();
+                    assert_eq!(b, buf[1..]);
+                    let k: Ipv4AddrKey = de_from_bytes_ordered(&mut b, *order).unwrap();
+                    assert_eq!(k.0, *v4);
+                }
+                IpAddr::V6(v6) => {
+                    let mut b = ser_to_vec_ordered(&Ipv6AddrKey(*v6), *order).unwrap();
+                    assert_eq!(b, buf[1..]);
+                    let k: Ipv6AddrKey = de_from_bytes_ordered(&mut b, *order).unwrap();
+                    assert_eq!(k.0, *v6);
+                }
+            }
+            if let Some(p) = prev {
+                match order {
+                    Order::Descending => assert!(p > buf),
+                    _ => assert!(p < buf),
+                }
+            }
+            prev = Some(buf);
+        }
+    }
+    let mut buf = vec![];
+    Ipv4Addr::new(10, 0, 0, 1)
+        .to_writer(&mut buf, AscendingOrder)
+        .unwrap();
+    assert_eq!(buf, [10, 0, 0, 1]);
+    assert_eq!(
+        Ipv6Addr::from_reader(DeBytesReader::new(&[0xFF; 16]), DescendingOrder).unwrap(),
+        Ipv6Addr::UNSPECIFIED
+    );
+    assert!(matches!(
+        deserialize_ip_addr(DeBytesReader::new(&[2, 0, 0, 0, 0]), AscendingOrder),
+        Err(Error::InvalidTagEncoding)
+    ));
+}
+
+#[test]
+fn test_compare_serialized() {
+    use ordcode::primitives::compare_serialized;
+
+    let ints = [(0_u32, -1_i16), (0, 1), (1, i16::MIN), (u32::MAX, 0)];
+    let strs = ["", "a", "ab", "b", "ba"];
+    let long = "x".repeat(100);
+    let long2 = long.clone() + "y";
+    for order in &[Order::Ascending, Order::Descending] {
+        for a in &ints {
+            for b in &ints {
+                let expected = ser_to_vec_ordered(a, *order)
+                    .unwrap()
+                    .cmp(&ser_to_vec_ordered(b, *order).unwrap());
+                assert_eq!(compare_serialized(a, b, *order).unwrap(), expected);
+            }
+        }
+        for a in strs.iter().chain(&[long.as_str(), long2.as_str()]) {
+            for b in strs.iter().chain(&[long.as_str(), long2.as_str()]) {
+                let a = (1_u8, *a);
+                let b = (1_u8, *b);
+                let expected = ser_to_vec_ordered(&a, *order)
+                    .unwrap()
+                    .cmp(&ser_to_vec_ordered(&b, *order).unwrap());
+                assert_eq!(compare_serialized(&a, &b, *order).unwrap(), expected);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_invert_buffer() {
+    // simple LCG to fill buffer with pseudo-random data
+    let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+    let orig: Vec<u8> = (0..65_536 + 13)
+        .map(|_| {
+            seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+            (seed >> 56) as u8
+        })
+        .collect();
+    for len in &[0, 1, 7, 8, 9, 16, 65_536, orig.len()] {
+        let mut buf = orig[..*len].to_vec();
+        primitives::invert_buffer(&mut buf);
+        assert!(buf.iter().zip(&orig).all(|(a, b)| *a == !*b));
+        primitives::invert_buffer(&mut buf);
+        assert_eq!(buf, orig[..*len]);
+    }
+}
+
+#[test]
+fn test_bytes_desc_large() {
+    let orig: Vec<u8> = (0..1_048_576_u32).map(|v| (v % 251) as u8).collect();
+    let complement: Vec<u8> = orig.iter().map(|b| !*b).collect();
+
+    let mut buf = vec![];
+    bytes_esc::serialize_bytes_noesc(&mut buf, &orig, DescendingOrder).unwrap();
+    assert_eq!(buf, complement);
+    let mut out = vec![];
+    bytes_esc::deserialize_bytes_noesc_to_writer(
+        DeBytesReader::new(&buf),
+        &mut out,
+        DescendingOrder,
+    )
+    .unwrap();
+    assert_eq!(out, orig);
+
+    // escaped encoding: descending is exact complement of ascending
+    let mut asc = vec![];
+    bytes_esc::serialize_bytes(&mut asc, &orig, AscendingOrder).unwrap();
+    let mut desc = vec![];
+    bytes_esc::serialize_bytes(&mut desc, &orig, DescendingOrder).unwrap();
+    assert!(asc.len() > orig.len());
+    assert!(asc.iter().zip(&desc).all(|(a, d)| *a == !*d));
+    let v =
+        bytes_esc::deserialize_bytes_to_vec(DeBytesReader::new(&desc), DescendingOrder).unwrap();
+    assert_eq!(v, orig);
+    let v = bytes_esc::deserialize_bytes_to_vec(DeBytesReader::new(&asc), AscendingOrder).unwrap();
+    assert_eq!(v, orig);
+}
+
+#[test]
+fn test_usize_isize() {
+    use ordcode::primitives::{
+        deserialize_isize, deserialize_usize, serialize_isize, serialize_usize,
+    };
+
+    for v in &[0_usize, 1, 0xFFFF, u32::MAX as usize, usize::MAX] {
+        let mut buf = vec![];
+        serialize_usize(*v, &mut buf, AscendingOrder).unwrap();
+        // always 8 bytes, same as u64, regardless of pointer width
+        let mut buf64 = vec![];
+        (*v as u64).to_writer(&mut buf64, AscendingOrder).unwrap();
+        assert_eq!(buf, buf64);
+        assert_eq!(
+            deserialize_usize(DeBytesReader::new(&buf), AscendingOrder).unwrap(),
+            *v
+        );
+    }
+    for v in &[isize::MIN, -1, 0, 1, isize::MAX] {
+        let mut buf = vec![];
+        serialize_isize(*v, &mut buf, DescendingOrder).unwrap();
+        let mut buf64 = vec![];
+        (*v as i64).to_writer(&mut buf64, DescendingOrder).unwrap();
+        assert_eq!(buf, buf64);
+        assert_eq!(
+            deserialize_isize(DeBytesReader::new(&buf), DescendingOrder).unwrap(),
+            *v
+        );
+    }
+    // values written on a 64-bit target which don't fit into 32-bit usize/isize
+    let mut big = vec![];
+    (u64::from(u32::MAX) + 1)
+        .to_writer(&mut big, AscendingOrder)
+        .unwrap();
+    let mut small = vec![];
+    (i64::from(i32::MIN) - 1)
+        .to_writer(&mut small, AscendingOrder)
+        .unwrap();
+    let u = deserialize_usize(DeBytesReader::new(&big), AscendingOrder);
+    let i = deserialize_isize(DeBytesReader::new(&small), AscendingOrder);
+    if cfg!(target_pointer_width = "64") {
+        assert_eq!(u.unwrap(), u32::MAX as usize + 1);
+        assert_eq!(i.unwrap() as i64, i64::from(i32::MIN) - 1);
+    } else {
+        assert!(matches!(u, Err(Error::IntegerOverflow)));
+        assert!(matches!(i, Err(Error::IntegerOverflow)));
+    }
+}
+
+#[test]
+fn test_char() {
+    for c in &['\0', 'a', '\u{D7FF}', '\u{E000}', char::MAX] {
+        let mut buf = vec![];
+        c.to_writer(&mut buf, DescendingOrder).unwrap();
+        assert_eq!(
+            char::from_reader(DeBytesReader::new(&buf), DescendingOrder).unwrap(),
+            *c
+        );
+    }
+    for v in &[0xD800_u32, 0xDFFF, 0x11_0000, u32::MAX] {
+        let mut buf = vec![];
+        v.to_writer(&mut buf, AscendingOrder).unwrap();
+        let r = char::from_reader(DeBytesReader::new(&buf), AscendingOrder);
+        assert!(matches!(r, Err(Error::InvalidCharCodePoint(c)) if c == *v));
+    }
+}
+
+#[test]
+fn test_fixed_bytes() {
+    use ordcode::primitives::{deserialize_fixed_bytes, serialize_fixed_bytes};
+
+    let uuid: [u8; 16] = [
+        0x67, 0xe5, 0x50, 0x44, 0x10, 0xb1, 0x42, 0x6f, 0x92, 0x47, 0xbb, 0x68, 0x0e, 0x5f, 0xe0,
+        0xc8,
+    ];
+    let mut buf = vec![];
+    serialize_fixed_bytes(&uuid, &mut buf, AscendingOrder).unwrap();
+    assert_eq!(buf, uuid);
+    let v: [u8; 16] = deserialize_fixed_bytes(DeBytesReader::new(&buf), AscendingOrder).unwrap();
+    assert_eq!(v, uuid);
+
+    let mut buf = vec![];
+    serialize_fixed_bytes(&uuid, &mut buf, DescendingOrder).unwrap();
+    assert_eq!(buf.len(), 16);
+    let v: [u8; 16] = deserialize_fixed_bytes(DeBytesReader::new(&buf), DescendingOrder).unwrap();
+    assert_eq!(v, uuid);
+
+    // serde encodes byte arrays the same way, with no length overhead
+    assert_eq!(calc_size_asc(&uuid).unwrap(), 16);
+    assert_eq!(ser_to_vec_ordered(&uuid, Order::Ascending).unwrap(), uuid);
+    let mut d = ser_to_vec_ordered(&uuid, Order::Descending).unwrap();
+    assert_eq!(d, buf);
+    assert_eq!(
+        de_from_bytes_ordered::<[u8; 16]>(&mut d, Order::Descending).unwrap(),
+        uuid
+    );
+
+    assert!(matches!(
+        deserialize_fixed_bytes::<_, 16>(DeBytesReader::new(&uuid[..15]), AscendingOrder),
+        Err(Error::PrematureEndOfInput)
+    ));
+}
+
+#[derive(Copy, Clone, Default)]
+struct LittleAsc;
+
+impl EncodingParams for LittleAsc {
+    const ORDER: Order = Order::Ascending;
+    const ENDIANNESS: Endianness = Endianness::Little;
+}
+
+#[test]
+fn test_128_words() {
+    use ordcode::primitives::{
+        deserialize_i128_words, deserialize_u128_words, serialize_i128_words, serialize_u128_words,
+    };
+
+    fn check<P: EncodingParams>(params: P) {
+        let unsigned = [
+            0_u128,
+            1,
+            u128::from(u64::MAX),
+            u128::from(u64::MAX) + 1,
+            u128::MAX,
+        ];
+        for v in &unsigned {
+            let (hi, lo) = ((v >> 64) as u64, *v as u64);
+            let mut native = vec![];
+            v.to_writer(&mut native, params).unwrap();
+            let mut words = vec![];
+            serialize_u128_words(hi, lo, &mut words, params).unwrap();
+            assert_eq!(native, words);
+            assert_eq!(
+                deserialize_u128_words(DeBytesReader::new(&native), params).unwrap(),
+                (hi, lo)
+            );
+        }
+        let signed = [i128::MIN, -(1 << 64), -1, 0, 1, 1 << 64, i128::MAX];
+        for v in &signed {
+            let (hi, lo) = ((v >> 64) as i64, *v as u64);
+            let mut native = vec![];
+            v.to_writer(&mut native, params).unwrap();
+            let mut words = vec![];
+            serialize_i128_words(hi, lo, &mut words, params).unwrap();
+            assert_eq!(native, words);
+            assert_eq!(
+                deserialize_i128_words(DeBytesReader::new(&native), params).unwrap(),
+                (hi, lo)
+            );
+        }
+    }
+    check(AscendingOrder);
+    check(DescendingOrder);
+    check(LittleAsc);
+    check(params::NativeBinary);
+}
+
+#[test]
+fn test_encoded_bounds() {
+    use ordcode::primitives::{max_encoded, min_encoded, EncodedBounds};
+
+    fn check<T: EncodedBounds + Copy, P: EncodingParams>(values: &[T], params: P) {
+        let mut min = vec![];
+        min_encoded::<T, _>(&mut min, params).unwrap();
+        let mut max = vec![];
+        max_encoded::<T, _>(&mut max, params).unwrap();
+        assert!(min < max);
+        for v in values {
+            let mut buf = vec![];
+            v.to_writer(&mut buf, params).unwrap();
+            assert!(min <= buf && buf <= max);
+        }
+    }
+    // simple LCG for pseudo-random values
+    let mut seed = 0x9e37_79b9_7f4a_7c15_u64;
+    let mut next = move || {
+        seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+        seed
+    };
+    let rnd: Vec<u64> = (0..1000).map(|_| next()).collect();
+    let u32s: Vec<u32> = rnd
+        .iter()
+        .map(|v| *v as u32)
+        .chain(vec![0, u32::MAX])
+        .collect();
+    let i64s: Vec<i64> = rnd
+        .iter()
+        .map(|v| *v as i64)
+        .chain(vec![i64::MIN, i64::MAX])
+        .collect();
+    let f64s: Vec<f64> = rnd
+        .iter()
+        .map(|v| f64::from_bits(*v))
+        .chain(vec![
+            f64::NEG_INFINITY,
+            f64::INFINITY,
+            f64::NAN,
+            -f64::NAN,
+            -0.0,
+            f64::MIN_POSITIVE,
+        ])
+        .collect();
+    let f32s: Vec<f32> = rnd.iter().map(|v| f32::from_bits(*v as u32)).collect();
+    check(&u32s, AscendingOrder);
+    check(&u32s, DescendingOrder);
+    check(&i64s, AscendingOrder);
+    check(&i64s, DescendingOrder);
+    check(&f64s, AscendingOrder);
+    check(&f64s, DescendingOrder);
+    check(&f64s, CanonicalAsc);
+    check(&f32s, AscendingOrder);
+    check(&f32s, DescendingOrder);
+    check(&[false, true], AscendingOrder);
+    check(&['a', '\u{10ffff}', '\0', '\u{d7ff}'], DescendingOrder);
+
+    let mut min = vec![];
+    min_encoded::<f64, _>(&mut min, AscendingOrder).unwrap();
+    assert_eq!(min, [0; 8]);
+    let mut max = vec![];
+    max_encoded::<f64, _>(&mut max, DescendingOrder).unwrap();
+    assert_eq!(max, [0xFF; 8]);
+}
+
+#[test]
+fn test_float_cross_endian() {
+    const F64S: &[f64] = &[
+        f64::NEG_INFINITY,
+        f64::MIN,
+        -1.5,
+        -f64::MIN_POSITIVE,
+        -4.9e-324, // negative subnormal
+        -0.0,
+        0.0,
+        4.9e-324, // positive subnormal
+        f64::MIN_POSITIVE,
+        1.5,
+        f64::MAX,
+        f64::INFINITY,
+    ];
+    const F32S: &[f32] = &[
+        f32::NEG_INFINITY,
+        f32::MIN,
+        -1.5,
+        -f32::MIN_POSITIVE,
+        -1.0e-45,
+        -0.0,
+        0.0,
+        1.0e-45,
+        f32::MIN_POSITIVE,
+        1.5,
+        f32::MAX,
+        f32::INFINITY,
+    ];
+    fn encode<T: SerializableValue, P: EncodingParams>(v: &T, params: P) -> Vec<u8> {
+        let mut s = vec![];
+        v.to_writer(&mut s, params).unwrap();
+        s
+    }
+    fn roundtrip<T: SerializableValue, P: EncodingParams>(v: &T, params: P) -> T {
+        T::from_reader(DeBytesReader::new(&encode(v, params)), params).unwrap()
+    }
+    // big-endian encodings are sorted, portable encoding is the same as ascending order encoding
+    for w in F64S.windows(2) {
+        assert!(encode(&w[0], PortableBinary) < encode(&w[1], PortableBinary));
+        assert!(encode(&w[0], DescendingOrder) > encode(&w[1], DescendingOrder));
+    }
+    for w in F32S.windows(2) {
+        assert!(encode(&w[0], PortableBinary) < encode(&w[1], PortableBinary));
+        assert!(encode(&w[0], DescendingOrder) > encode(&w[1], DescendingOrder));
+    }
+    for v in F64S {
+        assert_eq!(encode(v, PortableBinary), encode(v, AscendingOrder));
+        assert_eq!(roundtrip(v, PortableBinary).to_bits(), v.to_bits());
+        assert_eq!(roundtrip(v, DescendingOrder).to_bits(), v.to_bits());
+        assert_eq!(roundtrip(v, LittleAsc).to_bits(), v.to_bits());
+        assert_eq!(roundtrip(v, NativeBinary).to_bits(), v.to_bits());
+        // little-endian and native encodings are raw bits, which are not ordered
+        assert_eq!(encode(v, LittleAsc), v.to_bits().to_le_bytes());
+        assert_eq!(encode(v, NativeBinary), v.to_bits().to_ne_bytes());
+    }
+    for v in F32S {
+        assert_eq!(encode(v, PortableBinary), encode(v, AscendingOrder));
+        assert_eq!(roundtrip(v, PortableBinary).to_bits(), v.to_bits());
+        assert_eq!(roundtrip(v, DescendingOrder).to_bits(), v.to_bits());
+        assert_eq!(roundtrip(v, LittleAsc).to_bits(), v.to_bits());
+        assert_eq!(roundtrip(v, NativeBinary).to_bits(), v.to_bits());
+        assert_eq!(encode(v, LittleAsc), v.to_bits().to_le_bytes());
+        assert_eq!(encode(v, NativeBinary), v.to_bits().to_ne_bytes());
+    }
+    // portable encoding does not depend on host endianness
+    assert_eq!(
+        encode(&-1.5f64, PortableBinary),
+        [0x40, 0x07, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]
+    );
+    assert_eq!(
+        encode(&1.5f64, PortableBinary),
+        [0xbf, 0xf8, 0, 0, 0, 0, 0, 0]
+    );
+    assert_eq!(encode(&-0.0f32, PortableBinary), [0x7f, 0xff, 0xff, 0xff]);
+    assert_eq!(encode(&0.0f32, PortableBinary), [0x80, 0, 0, 0]);
+    assert_eq!(encode(&f32::INFINITY, PortableBinary), [0xff, 0x80, 0, 0]);
+}
+
+#[test]
+fn test_hex() {
+    use ordcode::primitives::{from_hex, to_hex, HexKey};
+
+    let mut key = vec![];
+    1234_u64.to_writer(&mut key, DescendingOrder).unwrap();
+    key.extend_from_slice(&[0, 0x7f, 0x80]);
+    for v in [&key[..], &[0xff], &(0..=255).collect::<Vec<u8>>()] {
+        assert_eq!(from_hex(&to_hex(v)).unwrap(), v);
+        assert_eq!(from_hex(&HexKey(v).to_string()).unwrap(), v);
+        assert_eq!(from_hex(&to_hex(v).to_uppercase()).unwrap(), v);
+    }
+    assert_eq!(to_hex(&key), "fffffffffffffb2d007f80");
+    assert_eq!(HexKey(&key).to_string(), "ff ff ff ff ff ff fb 2d 00 7f 80");
+    assert_eq!(format!("{:?}", HexKey(&[1, 2])), "01 02");
+
+    // empty slice
+    assert_eq!(to_hex(&[]), "");
+    assert_eq!(HexKey(&[]).to_string(), "");
+    assert!(from_hex("").unwrap().is_empty());
+    assert!(from_hex("  ").unwrap().is_empty());
+
+    assert!(matches!(from_hex("abc"), Err(Error::InvalidHexEncoding)));
+    assert!(matches!(from_hex("0g"), Err(Error::InvalidHexEncoding)));
+}
+
+#[test]
+fn test_socket_addr() {
+    use ordcode::primitives::{deserialize_socket_addr, serialize_socket_addr};
+    use std::net::SocketAddr;
+
+    fn encode<P: EncodingParams>(a: &SocketAddr, params: P) -> Vec<u8> {
+        let mut buf = vec![];
+        serialize_socket_addr(a, &mut buf, params).unwrap();
+        buf
+    }
+    let addrs: Vec<SocketAddr> = [
+        "1.2.3.4:80",
+        "1.2.3.4:443",
+        "1.2.3.5:1",
+        "255.255.255.255:0",
+        "[::1]:80",
+        "[::1]:65535",
+        "[::2]:1",
+    ]
+    .iter()
+    .map(|s| s.parse().unwrap())
+    .collect();
+    for w in addrs.windows(2) {
+        assert!(encode(&w[0], AscendingOrder) < encode(&w[1], AscendingOrder));
+        assert!(encode(&w[0], DescendingOrder) > encode(&w[1], DescendingOrder));
+    }
+    for a in &addrs {
+        let buf = encode(a, AscendingOrder);
+        assert_eq!(buf.len(), if a.is_ipv4() { 7 } else { 19 });
+        assert_eq!(
+            deserialize_socket_addr(DeBytesReader::new(&buf), AscendingOrder).unwrap(),
+            *a
+        );
+        let buf = encode(a, DescendingOrder);
+        assert_eq!(
+            deserialize_socket_addr(DeBytesReader::new(&buf), DescendingOrder).unwrap(),
+            *a
+        );
+        // port is in network byte order regardless of endianness
+        assert_eq!(
+            &encode(a, LittleAsc)[buf.len() - 2..],
+            a.port().to_be_bytes()
+        );
+    }
+    let err = deserialize_socket_addr(DeBytesReader::new(&[0, 1, 2, 3, 4, 0]), AscendingOrder)
+        .unwrap_err();
+    assert!(matches!(err, Error::PrematureEndOfInput));
+}
+
+#[test]
+fn test_f32_as_f64() {
+    use ordcode::primitives::{
+        deserialize_f32_as_f64, deserialize_f32_as_f64_exact, serialize_f32_as_f64,
+    };
+
+    fn encode_f32<P: EncodingParams>(v: f32, params: P) -> Vec<u8> {
+        let mut buf = vec![];
+        serialize_f32_as_f64(v, &mut buf, params).unwrap();
+        buf
+    }
+    fn encode_f64<P: EncodingParams>(v: f64, params: P) -> Vec<u8> {
+        let mut buf = vec![];
+        v.to_writer(&mut buf, params).unwrap();
+        buf
+    }
+    assert_eq!(
+        encode_f32(1.5, AscendingOrder),
+        encode_f64(1.5, AscendingOrder)
+    );
+    assert_eq!(
+        encode_f32(1.5, DescendingOrder),
+        encode_f64(1.5, DescendingOrder)
+    );
+    assert_eq!(encode_f32(1.5, AscendingOrder).len(), 8);
+
+    // mixed keys sort together
+    assert!(encode_f32(1.5, AscendingOrder) < encode_f64(1.500_001, AscendingOrder));
+    assert!(encode_f64(-2.0, AscendingOrder) < encode_f32(-1.5, AscendingOrder));
+    assert!(encode_f32(f32::MAX, AscendingOrder) < encode_f64(f64::MAX, AscendingOrder));
+
+    for v in &[
+        f32::NEG_INFINITY,
+        -1.5,
+        -0.0,
+        0.0,
+        1.0e-45,
+        1.5,
+        f32::MAX,
+        f32::INFINITY,
+    ] {
+        let buf = encode_f32(*v, AscendingOrder);
+        let r = deserialize_f32_as_f64(DeBytesReader::new(&buf), AscendingOrder).unwrap();
+        assert_eq!(r.to_bits(), v.to_bits());
+        let r = deserialize_f32_as_f64_exact(DeBytesReader::new(&buf), AscendingOrder).unwrap();
+        assert_eq!(r.to_bits(), v.to_bits());
+    }
+    let buf = encode_f32(f32::NAN, DescendingOrder);
+    assert!(
+        deserialize_f32_as_f64_exact(DeBytesReader::new(&buf), DescendingOrder)
+            .unwrap()
+            .is_nan()
+    );
+
+    // precision loss
+    let buf = encode_f64(0.1, AscendingOrder);
+    let r = deserialize_f32_as_f64(DeBytesReader::new(&buf), AscendingOrder).unwrap();
+    assert_eq!(r, 0.1_f32);
+    let err = deserialize_f32_as_f64_exact(DeBytesReader::new(&buf), AscendingOrder).unwrap_err();
+    assert!(matches!(err, Error::PrecisionLoss));
+    let buf = encode_f64(f64::MAX, AscendingOrder);
+    let err = deserialize_f32_as_f64_exact(DeBytesReader::new(&buf), AscendingOrder).unwrap_err();
+    assert!(matches!(err, Error::PrecisionLoss));
+}
+
+#[test]
+fn test_escaped_order() {
+    use bytes_esc::{deserialize_escaped, escaped_length, serialize_escaped};
+
+    let values: Vec<Vec<u8>> = vec![
+        vec![],
+        vec![0],
+        vec![0, 0],
+        vec![0, 1],
+        vec![0, 0xFF],
+        vec![1],
+        vec![1, 0],
+        vec![0xF8],
+        vec![0xFE, 0],
+        vec![0xFF],
+        vec![0xFF, 0],
+        vec![0xFF, 0xFF],
+        b"a".to_vec(),
+        b"a\0".to_vec(),
+        b"ab".to_vec(),
+    ];
+    fn encode<P: EncodingParams>(parts: &[&[u8]], param: P) -> Vec<u8> {
+        let mut buf = vec![];
+        for p in parts {
+            serialize_escaped(&mut buf, p, param).unwrap();
+        }
+        buf
+    }
+    // concatenated escaped values compare in the same order as original pairs
+    for a1 in &values {
+        for b1 in &values {
+            for a2 in &values {
+                for b2 in &values {
+                    let expected = (a1, b1).cmp(&(a2, b2));
+                    let asc1 = encode(&[a1, b1], AscendingOrder);
+                    let asc2 = encode(&[a2, b2], AscendingOrder);
+                    assert_eq!(asc1.cmp(&asc2), expected);
+                    let desc1 = encode(&[a1, b1], DescendingOrder);
+                    let desc2 = encode(&[a2, b2], DescendingOrder);
+                    assert_eq!(desc1.cmp(&desc2), expected.reverse());
+                }
+            }
+        }
+    }
+    for a in &values {
+        for b in &values {
+            let buf = encode(&[a, b], AscendingOrder);
+            let mut r = DeBytesReader::new(&buf);
+            assert_eq!(escaped_length(&mut r, AscendingOrder).unwrap(), a.len());
+            assert_eq!(&deserialize_escaped(&mut r, AscendingOrder).unwrap(), a);
+            assert_eq!(&deserialize_escaped(&mut r, AscendingOrder).unwrap(), b);
+            assert_eq!(r.remaining(), 0);
+
+            let buf = encode(&[a, b], DescendingOrder);
+            let mut r = DeBytesReader::new(&buf);
+            assert_eq!(&deserialize_escaped(&mut r, DescendingOrder).unwrap(), a);
+            assert_eq!(&deserialize_escaped(&mut r, DescendingOrder).unwrap(), b);
+            assert_eq!(r.remaining(), 0);
+        }
+    }
+    let err = deserialize_escaped(DeBytesReader::new(&[1, 0, 2]), AscendingOrder).unwrap_err();
+    assert!(matches!(err, Error::InvalidByteSequenceEscape));
+    let err = deserialize_escaped(DeBytesReader::new(&[1, 2]), AscendingOrder).unwrap_err();
+    assert!(matches!(err, Error::PrematureEndOfInput));
+}
+
+#[test]
+fn test_escaped_compare() {
+    use bytes_esc::{compare, serialize_escaped};
+    use std::cmp::Ordering;
+
+    // simple LCG for pseudo-random values
+    let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = move || {
+        seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+        (seed >> 33) as u32
+    };
+    // short sequences from a small alphabet with zero and escape bytes, so that prefixes,
+    // equal values and embedded zeros are common
+    let mut random_bytes = || -> Vec<u8> {
+        let len = next() % 6;
+        (0..len)
+            .map(|_| [0x00, 0x00, 0x01, 0x7F, 0xF8, 0xFF][(next() % 6) as usize])
+            .collect()
+    };
+    let escaped = |v: &[u8], trailing: &[u8]| {
+        let mut buf = vec![];
+        serialize_escaped(&mut buf, v, AscendingOrder).unwrap();
+        buf.extend_from_slice(trailing);
+        buf
+    };
+    let mut counts = [0_usize; 3];
+    for _ in 0..20_000 {
+        let (a, b) = (random_bytes(), random_bytes());
+        let expected = a.cmp(&b);
+        counts[(expected as i8 + 1) as usize] += 1;
+        let (ea, eb) = (escaped(&a, &[]), escaped(&b, &[]));
+        assert_eq!(compare(&ea, &eb), expected, "{:?} {:?}", a, b);
+        // trailing data after terminator is ignored
+        let (ta, tb) = (random_bytes(), random_bytes());
+        assert_eq!(compare(&escaped(&a, &ta), &escaped(&b, &tb)), expected);
+    }
+    // all outcomes are covered
+    assert!(counts.iter().all(|c| *c > 500), "{:?}", counts);
+
+    assert_eq!(compare(&[], &[]), Ordering::Equal);
+    assert_eq!(
+        compare(&escaped(b"", &[]), &escaped(b"\0", &[])),
+        Ordering::Less
+    );
+}
+
+#[test]
+fn test_bitflags() {
+    use ordcode::primitives::{deserialize_bitflags, serialize_bitflags, BitFlags8};
+
+    fn encode<P: EncodingParams, const N: usize>(flags: &[bool; N], params: P) -> Vec<u8> {
+        let mut buf = vec![];
+        serialize_bitflags(flags, &mut buf, params).unwrap();
+        buf
+    }
+    let a = [true, false, false, false, false, false, false, false];
+    let b = [false, true, true, true, true, true, true, true];
+    assert_eq!(encode(&a, AscendingOrder), [0x80]);
+    assert_eq!(encode(&b, AscendingOrder), [0x7f]);
+    assert!(encode(&a, AscendingOrder) > encode(&b, AscendingOrder));
+    assert!(encode(&a, DescendingOrder) < encode(&b, DescendingOrder));
+
+    // ordering is the same as for arrays of flags
+    let all: Vec<[bool; 3]> = (0..8_u8)
+        .map(|i| [i & 4 != 0, i & 2 != 0, i & 1 != 0])
+        .collect();
+    for w in all.windows(2) {
+        assert!(w[0] < w[1]);
+        assert!(encode(&w[0], AscendingOrder) < encode(&w[1], AscendingOrder));
+        assert!(encode(&w[0], DescendingOrder) > encode(&w[1], DescendingOrder));
+    }
+    for flags in &all {
+        let buf = encode(flags, DescendingOrder);
+        let rt: [bool; 3] =
+            deserialize_bitflags(DeBytesReader::new(&buf), DescendingOrder).unwrap();
+        assert_eq!(&rt, flags);
+    }
+    let mut buf = vec![];
+    assert!(matches!(
+        serialize_bitflags(&[false; 9], &mut buf, AscendingOrder),
+        Err(Error::SequenceTooLong)
+    ));
+
+    let flags = BitFlags8(a);
+    let buf = ser_to_vec_ordered(&flags, Order::Ascending).unwrap();
+    assert_eq!(buf, encode(&a, AscendingOrder));
+    assert_eq!(de_from_bytes_asc::<BitFlags8>(&buf).unwrap(), flags);
+    assert_eq!(BitFlags8::from_bits(0x7f), BitFlags8(b));
+    assert!(BitFlags8(a) > BitFlags8(b));
+}
+
+#[test]
+fn test_native_raw_ints() {
+    use ordcode::primitives::{deserialize_i128_words, serialize_i128_words};
+
+    fn check<T>(values: &[T], to_ne: fn(&T) -> Vec<u8>)
+    where
+        T: SerializableValue + PartialEq + std::fmt::Debug,
+    {
+        for v in values {
+            let mut buf = Vec::new();
+            v.to_writer(&mut buf, NativeBinaryRaw).unwrap();
+            // written as is, without transformation
+            assert_eq!(buf, to_ne(v));
+            assert_eq!(
+                &T::from_reader(DeBytesReader::new(&buf), NativeBinaryRaw).unwrap(),
+                v
+            );
+        }
+    }
+    check(&[i8::MIN, -1, 0, 1, i8::MAX], |v| v.to_ne_bytes().to_vec());
+    check(&[i16::MIN, -1, 0, 1, i16::MAX], |v| {
+        v.to_ne_bytes().to_vec()
+    });
+    check(&[i32::MIN, -1, 0, 1, i32::MAX], |v| {
+        v.to_ne_bytes().to_vec()
+    });
+    check(&[i64::MIN, -1, 0, 1, i64::MAX], |v| {
+        v.to_ne_bytes().to_vec()
+    });
+    check(&[i128::MIN, -1, 0, 1, i128::MAX], |v| {
+        v.to_ne_bytes().to_vec()
+    });
+    check(&[0_u32, 1, u32::MAX], |v| v.to_ne_bytes().to_vec());
+    check(&[-1.5_f64, 0.0, f64::MAX], |v| v.to_ne_bytes().to_vec());
+
+    // signed integers are still offset with other parameters, including `NativeBinary`
+    let mut buf = Vec::new();
+    (-1_i32).to_writer(&mut buf, PortableBinary).unwrap();
+    assert_eq!(buf, [0x7f, 0xff, 0xff, 0xff]);
+    let mut buf = Vec::new();
+    (-1_i32).to_writer(&mut buf, NativeBinary).unwrap();
+    assert_eq!(buf, i32::MAX.to_ne_bytes());
+
+    // 128-bit words encoding is the same as native
+    for &v in &[i128::MIN, -1, 0, 1, i128::MAX] {
+        let mut buf = Vec::new();
+        serialize_i128_words((v >> 64) as i64, v as u64, &mut buf, NativeBinaryRaw).unwrap();
+        assert_eq!(buf, v.to_ne_bytes());
+        let (hi, lo) = deserialize_i128_words(DeBytesReader::new(&buf), NativeBinaryRaw).unwrap();
+        assert_eq!((i128::from(hi) << 64) | i128::from(lo), v);
+    }
+
+    // serde round trip
+    let value = (-5_i16, vec![i64::MIN, -1, 3], Some(-7_i8));
+    let mut buf = vec![0_u8; calc_size(&value, NativeBinaryRaw).unwrap()];
+    let mut writer = DeBytesWriter::new(&mut buf);
+    serde::Serialize::serialize(&value, &mut Serializer::new(&mut writer, NativeBinaryRaw))
+        .unwrap();
+    writer.is_complete().unwrap();
+    let mut reader = DeBytesReader::new(&buf);
+    let rt: (i16, Vec<i64>, Option<i8>) =
+        serde::Deserialize::deserialize(&mut Deserializer::new(&mut reader, NativeBinaryRaw))
+            .unwrap();
+    assert_eq!(rt, value);
+}
+
+#[test]
+fn test_decimal() {
+    use ordcode::primitives::{deserialize_decimal, serialize_decimal};
+
+    // prices in 1/10000 units, given with various scales
+    fn encode<P: EncodingParams>(mantissa: i128, scale: u8, params: P) -> Vec<u8> {
+        let mut buf = vec![];
+        serialize_decimal(mantissa, scale, 4, &mut buf, params).unwrap();
+        buf
+    }
+    let amounts: &[(i128, u8)] = &[
+        (-1_000_000, 2), // -10000.00
+        (-1999, 2),      // -19.99
+        (-5, 1),         // -0.5
+        (0, 0),
+        (1, 4),      // 0.0001
+        (25, 2),     // 0.25
+        (19990, 3),  // 19.990
+        (2, 0),      // 20
+        (100005, 4), // 10.0005
+    ];
+    let mut sorted = amounts.to_vec();
+    sorted.sort_by_key(|&(m, s)| m * 10_i128.pow(u32::from(4 - s)));
+    for w in sorted.windows(2) {
+        let (a, b) = (w[0], w[1]);
+        assert!(encode(a.0, a.1, AscendingOrder) < encode(b.0, b.1, AscendingOrder));
+        assert!(encode(a.0, a.1, DescendingOrder) > encode(b.0, b.1, DescendingOrder));
+    }
+    for &(m, s) in amounts {
+        let buf = encode(m, s, AscendingOrder);
+        let (rm, rs) = deserialize_decimal(DeBytesReader::new(&buf), 4, AscendingOrder).unwrap();
+        assert_eq!(rs, 4);
+        assert_eq!(rm, m * 10_i128.pow(u32::from(4 - s)));
+    }
+    // same encoding as scaled `i128`
+    assert_eq!(
+        encode(1999, 2, AscendingOrder),
+        ser_to_vec_ordered(&199_900_i128, Order::Ascending).unwrap()
+    );
+
+    let mut buf = vec![];
+    assert!(matches!(
+        serialize_decimal(12345, 5, 4, &mut buf, AscendingOrder),
+        Err(Error::PrecisionLoss)
+    ));
+    assert!(matches!(
+        serialize_decimal(i128::MAX, 0, 4, &mut buf, AscendingOrder),
+        Err(Error::IntegerOverflow)
+    ));
+    serialize_decimal(12340, 5, 4, &mut buf, AscendingOrder).unwrap();
+    serialize_decimal(0, 200, 4, &mut buf, AscendingOrder).unwrap();
+}
+
+#[test]
+fn test_read_slice() {
+    let buf = [1_u8, 2, 3, 4, 5, 6];
+    let (a, b) = {
+        let mut reader = DeBytesReader::new(&buf);
+        let a = reader.read_slice(2).unwrap();
+        let b = reader.read_slice(3).unwrap();
+        assert_eq!(reader.head_pos(), 5);
+        assert!(matches!(
+            reader.read_slice(2).unwrap_err(),
+            Error::PrematureEndOfInput
+        ));
+        assert_eq!(reader.read_slice(1).unwrap(), [6]);
+        (a, b)
+    };
+    // slices outlive the reader and point into the buffer, one after another
+    assert_eq!(a, [1, 2]);
+    assert_eq!(b, [3, 4, 5]);
+    assert_eq!(a.as_ptr(), buf.as_ptr());
+    assert_eq!(b.as_ptr(), a.as_ptr_range().end);
+}
+
+#[test]
+fn test_tribool() {
+    use ordcode::primitives::TriBool;
+
+    let values = [TriBool(Some(false)), TriBool(Some(true)), TriBool(None)];
+    for (code, v) in values.iter().enumerate() {
+        let buf = ser_to_vec_ordered(v, Order::Ascending).unwrap();
+        assert_eq!(buf, [code as u8]);
+        assert_eq!(calc_size_asc(v).unwrap(), 1);
+        assert_eq!(de_from_bytes_asc::<TriBool>(&buf).unwrap(), *v);
+        let mut buf = ser_to_vec_ordered(v, Order::Descending).unwrap();
+        assert_eq!(
+            de_from_bytes_ordered::<TriBool>(&mut buf, Order::Descending).unwrap(),
+            *v
+        );
+    }
+    // false < true < null, for values and for encodings
+    for w in values.windows(2) {
+        assert!(w[0] < w[1]);
+        assert!(
+            ser_to_vec_ordered(&w[0], Order::Ascending).unwrap()
+                < ser_to_vec_ordered(&w[1], Order::Ascending).unwrap()
+        );
+    }
+    let nulls_first = [
+        TriBool::<true>(None),
+        TriBool(Some(false)),
+        TriBool(Some(true)),
+    ];
+    for (code, v) in nulls_first.iter().enumerate() {
+        let buf = ser_to_vec_ordered(v, Order::Ascending).unwrap();
+        assert_eq!(buf, [code as u8]);
+        assert_eq!(de_from_bytes_asc::<TriBool<true>>(&buf).unwrap(), *v);
+    }
+    assert!(nulls_first.windows(2).all(|w| w[0] < w[1]));
+
+    assert!(de_from_bytes_asc::<TriBool>(&[3]).is_err());
+}
+
+// compile-time checks of maximum encoded sizes
+const _: () = {
+    use ordcode::{primitives::MaxEncodedLen, varint::VarUInt};
+
+    const _: [(); 1] = [(); u8::MAX_ENCODED_LEN];
+    const _: [(); 2] = [(); i16::MAX_ENCODED_LEN];
+    const _: [(); 4] = [(); u32::MAX_ENCODED_LEN];
+    const _: [(); 8] = [(); i64::MAX_ENCODED_LEN];
+    const _: [(); 16] = [(); u128::MAX_ENCODED_LEN];
+    const _: [(); 4] = [(); f32::MAX_ENCODED_LEN];
+    const _: [(); 8] = [(); f64::MAX_ENCODED_LEN];
+    const _: [(); 1] = [(); bool::MAX_ENCODED_LEN];
+    const _: [(); 4] = [(); char::MAX_ENCODED_LEN];
+    const _: [(); 8] = [(); std::num::NonZeroU64::MAX_ENCODED_LEN];
+    const _: [(); 16] = [(); std::net::Ipv6Addr::MAX_ENCODED_LEN];
+    const _: [(); 5] = [(); <u32 as VarUInt>::VARU_MAX_LEN];
+    const _: [(); 9] = [(); <u64 as VarUInt>::VARU_MAX_LEN];
+};
+
+#[test]
+fn test_max_encoded_len() {
+    use ordcode::{primitives::MaxEncodedLen, varint::VarUInt};
+
+    let mut buf = [0_u8; u64::MAX_ENCODED_LEN];
+    u64::MAX
+        .to_writer(DeBytesWriter::new(&mut buf), AscendingOrder)
+        .unwrap();
+    let mut buf = [0_u8; char::MAX_ENCODED_LEN];
+    char::MAX
+        .to_writer(DeBytesWriter::new(&mut buf), DescendingOrder)
+        .unwrap();
+    let mut buf = [0_u8; <u64 as VarUInt>::VARU_MAX_LEN];
+    assert_eq!(
+        usize::from(u64::MAX.varu_to_slice(&mut buf)),
+        <u64 as VarUInt>::VARU_MAX_LEN
+    );
+    let mut buf = [0_u8; <u32 as VarUInt>::VARU_MAX_LEN];
+    assert_eq!(
+        usize::from(u32::MAX.varu_to_slice(&mut buf)),
+        <u32 as VarUInt>::VARU_MAX_LEN
+    );
+}
+
+#[test]
+fn test_invert_range() {
+    let orig: Vec<u8> = (0..40).collect();
+    let mut buf = orig.clone();
+    primitives::invert_range(&mut buf, 5..30);
+    assert_eq!(buf[..5], orig[..5]);
+    assert_eq!(buf[30..], orig[30..]);
+    assert!(buf[5..30].iter().zip(&orig[5..30]).all(|(a, b)| *a == !*b));
+    primitives::invert_range(&mut buf, 5..30);
+    assert_eq!(buf, orig);
+    // empty range is a no-op
+    primitives::invert_range(&mut buf, 7..7);
+    assert_eq!(buf, orig);
+}
+
+#[test]
+fn test_head_range() {
+    use ordcode::buf::{TailWriteBytes, WriteBytes};
+
+    let mut buf = [0_u8; 8];
+    let mut writer = DeBytesWriter::new(&mut buf);
+    writer.write(&[1, 2]).unwrap();
+    let range = writer
+        .head_range(|w| {
+            w.write(&[3, 4, 5])?;
+            w.write_tail(&[9])
+        })
+        .unwrap();
+    assert_eq!(range, 2..5);
+    writer.write(&[6]).unwrap();
+    assert_eq!(writer.finalize().unwrap(), 7);
+    primitives::invert_range(&mut buf, range);
+    assert_eq!(buf[..7], [1, 2, !3, !4, !5, 6, 9]);
+}
+
+#[test]
+fn test_write_pair() {
+    use ordcode::buf::{TailWriteBytes, WriteBytes};
+
+    let mut separate = [0_u8; 8];
+    let mut writer = DeBytesWriter::new(&mut separate);
+    writer.write(b"ab").unwrap();
+    writer.write_tail(&[2]).unwrap();
+    writer.write(b"cde").unwrap();
+    writer.write_tail(&[3]).unwrap();
+    assert_eq!(writer.finalize().unwrap(), 7);
+
+    let mut fused = [0_u8; 8];
+    let mut writer = DeBytesWriter::new(&mut fused);
+    writer.write_pair(b"ab", &[2]).unwrap();
+    (&mut writer).write_pair(b"cde", &[3]).unwrap();
+    assert_eq!(writer.finalize().unwrap(), 7);
+    assert_eq!(fused, separate);
+
+    // default implementation
+    let mut vec = Vec::new();
+    vec.write_pair(b"ab", &[2]).unwrap();
+    assert_eq!(vec, b"ab\x02");
+
+    // nothing is written on overflow
+    let mut buf = [0_u8; 4];
+    let mut writer = DeBytesWriter::new(&mut buf);
+    writer.write(b"a").unwrap();
+    assert!(matches!(
+        writer.write_pair(b"bc", &[1, 2]),
+        Err(Error::BufferOverflow {
+            needed: 5,
+            available: 4
+        })
+    ));
+    assert_eq!(writer.remaining(), 3);
+}
+
+#[derive(Copy, Clone)]
+struct UnorderedLittle;
+
+impl EncodingParams for UnorderedLittle {
+    const ORDER: Order = Order::Unordered;
+    const ENDIANNESS: Endianness = Endianness::Little;
+    const RAW_SIGNED_INTS: bool = true;
+}
+
+#[derive(Copy, Clone)]
+struct UnorderedBig;
+
+impl EncodingParams for UnorderedBig {
+    const ORDER: Order = Order::Unordered;
+    const ENDIANNESS: Endianness = Endianness::Big;
+    const RAW_SIGNED_INTS: bool = true;
+}
+
+#[derive(Copy, Clone)]
+struct UnorderedComplemented;
+
+impl EncodingParams for UnorderedComplemented {
+    const ORDER: Order = Order::Unordered;
+    const ENDIANNESS: Endianness = Endianness::Big;
+}
+
+#[test]
+fn test_unordered_raw_integers() {
+    fn encode<T: SerializableValue, P: EncodingParams>(v: T, params: P) -> Vec<u8> {
+        let mut s = vec![];
+        v.to_writer(&mut s, params).unwrap();
+        s
+    }
+    // signed integers are written in two's complement, without min-value complement
+    assert_eq!(encode(-1_i8, UnorderedLittle), (-1_i8).to_le_bytes());
+    assert_eq!(encode(-2_i16, UnorderedLittle), (-2_i16).to_le_bytes());
+    assert_eq!(encode(-3_i32, UnorderedBig), (-3_i32).to_be_bytes());
+    assert_eq!(encode(i64::MIN, UnorderedBig), i64::MIN.to_be_bytes());
+    assert_eq!(
+        encode(i128::MIN + 1, UnorderedLittle),
+        (i128::MIN + 1).to_le_bytes()
+    );
+    assert_eq!(encode(5_u16, UnorderedBig), 5_u16.to_be_bytes());
+    // ordered encodings still apply min-value complement
+    assert_eq!(encode(-1_i32, AscendingOrder), [0x7f, 0xff, 0xff, 0xff]);
+    // not opted in
+    assert_eq!(
+        encode(-1_i32, UnorderedComplemented),
+        [0x7f, 0xff, 0xff, 0xff]
+    );
+
+    for v in V_I64 {
+        let buf = encode(*v, UnorderedLittle);
+        assert_eq!(buf, v.to_le_bytes());
+        let mut r = DeBytesReader::new(&buf);
+        assert_eq!(i64::from_reader(&mut r, UnorderedLittle).unwrap(), *v);
+        let mut buf = vec![];
+        primitives::serialize_i128_words(*v >> 63, *v as u64, &mut buf, UnorderedBig).unwrap();
+        assert_eq!(buf, i128::from(*v).to_be_bytes());
+        let mut r = DeBytesReader::new(&buf);
+        assert_eq!(
+            primitives::deserialize_i128_words(&mut r, UnorderedBig).unwrap(),
+            (*v >> 63, *v as u64)
+        );
+    }
+}
+
+#[test]
+fn test_timestamp() {
+    fn encode(secs: u32, nanos: u32, order: Order) -> Vec<u8> {
+        let mut buf = vec![];
+        match order {
+            Order::Descending => {
+                primitives::serialize_timestamp(secs, nanos, &mut buf, DescendingOrder)
+            }
+            _ => primitives::serialize_timestamp(secs, nanos, &mut buf, AscendingOrder),
+        }
+        .unwrap();
+        buf
+    }
+    // ordered by instant, across second boundary
+    let instants = [
+        (0, 0),
+        (0, 1),
+        (9, 999_999_998),
+        (9, 999_999_999),
+        (10, 0),
+        (10, 1),
+        (u32::MAX, 999_999_999),
+    ];
+    for w in instants.windows(2) {
+        assert!(
+            encode(w[0].0, w[0].1, Order::Ascending) < encode(w[1].0, w[1].1, Order::Ascending)
+        );
+        assert!(
+            encode(w[0].0, w[0].1, Order::Descending) > encode(w[1].0, w[1].1, Order::Descending)
+        );
+    }
+    for (secs, nanos) in &instants {
+        let buf = encode(*secs, *nanos, Order::Ascending);
+        assert_eq!(buf.len(), 8);
+        let mut r = DeBytesReader::new(&buf);
+        assert_eq!(
+            primitives::deserialize_timestamp(&mut r, AscendingOrder).unwrap(),
+            (*secs, *nanos)
+        );
+    }
+    assert!(matches!(
+        primitives::serialize_timestamp(1, 1_000_000_000, &mut vec![], AscendingOrder),
+        Err(Error::InvalidDuration)
+    ));
+    let buf = [0, 0, 0, 1, 0xff, 0xff, 0xff, 0xff];
+    assert!(matches!(
+        primitives::deserialize_timestamp(&mut DeBytesReader::new(&buf), AscendingOrder),
+        Err(Error::InvalidDuration)
+    ));
+}
+
+#[test]
+fn test_descending_preset_inverts() {
+    fn check<T: SerializableValue + Copy>(values: &[T]) {
+        for v in values {
+            let mut asc = vec![];
+            v.to_writer(&mut asc, AscendingOrder).unwrap();
+            let mut desc = vec![];
+            v.to_writer(&mut desc, DescendingOrder).unwrap();
+            primitives::invert_buffer(&mut asc);
+            assert_eq!(desc, asc);
+        }
+    }
+    check(V_U16);
+    check(V_U64);
+    check(V_I8);
+    check(V_I32);
+    check(V_F32);
+    check(V_F64);
+    check(&[false, true]);
+    check(&['a', char::MAX]);
+}
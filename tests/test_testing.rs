@@ -0,0 +1,99 @@
+#![cfg(feature = "testing")]
+
+extern crate ordcode;
+#[macro_use]
+extern crate serde_derive;
+
+use ordcode::params::{AscendingOrder, EncodingParams, Endianness, Order, SerializerParams};
+use ordcode::testing::{assert_order_preserving, check_ordering, roundtrip};
+use ordcode::varint;
+
+// Integers in little-endian byte order do not preserve ordering
+#[derive(Copy, Clone, Default)]
+struct BrokenParams;
+
+impl EncodingParams for BrokenParams {
+    const ORDER: Order = Order::Ascending;
+    const ENDIANNESS: Endianness = Endianness::Little;
+}
+
+impl SerializerParams for BrokenParams {
+    type SeqLenEncoder = varint::VarIntTailLenEncoder;
+    type DiscriminantEncoder = varint::VarIntDiscrEncoder;
+}
+
+#[test]
+fn test_order_preserving() {
+    assert_order_preserving::<_, AscendingOrder>(&[0_u16, 1, 255, 256, u16::MAX]);
+    assert_order_preserving::<_, AscendingOrder>(&[-1.5_f64, -0.0, 1.0, 2.0, f64::NAN]);
+    assert_order_preserving::<_, AscendingOrder>(&["", "a", "aa", "ab", "b"]);
+    assert_order_preserving::<_, AscendingOrder>(&[(2_u8, "a"), (1, "b"), (1, "a")]);
+    assert_order_preserving::<_, BrokenParams>(&[0_u8, 1, 255]);
+}
+
+#[test]
+#[should_panic(expected = "ordering not preserved")]
+fn test_order_not_preserved() {
+    assert_order_preserving::<_, BrokenParams>(&[1_u16, 256]);
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, PartialOrd)]
+struct Key {
+    tenant: u32,
+    name: String,
+    version: Option<i64>,
+    kind: Kind,
+    tags: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, PartialOrd)]
+enum Kind {
+    File,
+    Link(String),
+    Dir { entries: u16 },
+}
+
+fn key(tenant: u32, name: &str, version: Option<i64>, kind: Kind) -> Key {
+    Key {
+        tenant,
+        name: name.to_string(),
+        version,
+        kind,
+        tags: name.bytes().collect(),
+    }
+}
+
+#[test]
+fn test_roundtrip() {
+    roundtrip(&key(1, "a", Some(-1), Kind::File));
+    roundtrip(&key(2, "", None, Kind::Link("target".to_string())));
+    roundtrip(&key(
+        u32::MAX,
+        "dir",
+        Some(i64::MIN),
+        Kind::Dir { entries: 3 },
+    ));
+    roundtrip(&vec![(1_u8, -1.5_f64), (2, 0.0)]);
+    roundtrip(&"x".repeat(300));
+}
+
+#[test]
+fn test_check_ordering() {
+    check_ordering(&[
+        key(1, "a", None, Kind::File),
+        key(1, "a", Some(-5), Kind::File),
+        key(1, "a", Some(0), Kind::File),
+        key(1, "a", Some(0), Kind::Link("b".to_string())),
+        key(1, "a", Some(0), Kind::Dir { entries: 0 }),
+        key(1, "a", Some(0), Kind::Dir { entries: 0 }),
+        key(1, "b", None, Kind::File),
+        key(2, "", None, Kind::File),
+    ]);
+    check_ordering(&[-1_i32, 0, 0, 1, i32::MAX]);
+}
+
+#[test]
+#[should_panic(expected = "values are not sorted")]
+fn test_check_ordering_unsorted() {
+    check_ordering(&[2_u8, 1]);
+}
@@ -1,4 +1,8 @@
-use ordcode::{varint::*, *};
+use ordcode::{
+    buf::{ReadBytes, WriteBytes},
+    varint::*,
+    *,
+};
 
 // Varint tests are adopted and modified from VInt implementation, github.com/iqlusioninc/veriform
 // Original Copyright © 2017-2020 Tony Arcieri
@@ -128,3 +132,296 @@ fn with_buffer() {
     assert_eq!(<u64>::varu_from_reader(ReadFromTail(&mut r)).unwrap(), 12);
     assert_eq!(<u64>::varu_from_reader(&mut r).unwrap(), 11);
 }
+
+#[test]
+fn zigzag_i64_roundtrip() {
+    for v in &[0_i64, -1, 1, 63, -64, 64, i64::MIN, i64::MAX] {
+        let mut s = Vec::<u8>::new();
+        encode_zigzag_i64(*v, &mut s).unwrap();
+        let mut r = DeBytesReader::new(&s);
+        assert_eq!(decode_zigzag_i64(&mut r).unwrap(), *v);
+        r.is_complete().unwrap();
+    }
+    let mut s = Vec::<u8>::new();
+    encode_zigzag_i64(-1, &mut s).unwrap();
+    assert_eq!(s, encode64(1).as_ref());
+    s.clear();
+    encode_zigzag_i64(i64::MIN, &mut s).unwrap();
+    assert_eq!(s, encode64(u64::MAX).as_ref());
+}
+
+#[test]
+fn zigzag_i32_roundtrip() {
+    for v in &[0_i32, -1, 1, 63, -64, 64, i32::MIN, i32::MAX] {
+        let mut s = Vec::<u8>::new();
+        encode_zigzag_i32(*v, &mut s).unwrap();
+        let mut r = DeBytesReader::new(&s);
+        assert_eq!(decode_zigzag_i32(&mut r).unwrap(), *v);
+        r.is_complete().unwrap();
+    }
+    let mut s = Vec::<u8>::new();
+    encode_zigzag_i32(-64, &mut s).unwrap();
+    assert_eq!(s.len(), 1);
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn peek_seq_len() {
+    use serde::Deserialize;
+
+    let v: Vec<u32> = (0..300).collect();
+    let buf = ser_to_vec_ordered(&(v.clone(), 7_u8), Order::Ascending).unwrap();
+    let mut reader = DeBytesReader::new(&buf);
+    let len = peek_len(&reader).unwrap();
+    assert_eq!(len, 300);
+    assert_eq!(peek_len(&reader).unwrap(), 300);
+    let mut de = new_de_asc(&mut reader);
+    let (v2, b) = <(Vec<u32>, u8)>::deserialize(&mut de).unwrap();
+    assert_eq!(v2, v);
+    assert_eq!(b, 7);
+    assert_eq!(reader.remaining(), 0);
+}
+
+#[test]
+fn tail_cursor() {
+    use ordcode::{buf::TailCursor, params::LengthEncoder};
+
+    let lens = [
+        0_usize,
+        1,
+        127,
+        128,
+        300,
+        16_383,
+        16_384,
+        1 << 30,
+        usize::MAX,
+    ];
+    let mut buf = [0_u8; 64];
+    let mut cursor = TailCursor::new(&mut buf[..]);
+    for &len in &lens {
+        cursor.push_varint_len(len).unwrap();
+    }
+    let tail_len = cursor.tail_len();
+    assert_eq!(
+        tail_len,
+        lens.iter()
+            .map(|&len| (len as u64).varu_encoded_len() as usize)
+            .sum::<usize>()
+    );
+    // tail section is read from the end of the buffer, so lengths are popped in push order
+    for &len in &lens {
+        assert_eq!(cursor.pop_varint_len().unwrap(), len);
+    }
+
+    // same layout as written by `DeBytesWriter` with varint tail length encoder
+    let mut buf2 = [0_u8; 64];
+    let mut writer = DeBytesWriter::new(&mut buf2);
+    for &len in &lens {
+        VarIntTailLenEncoder::write(&mut writer, len).unwrap();
+    }
+    assert_eq!(buf[64 - tail_len..], buf2[64 - tail_len..]);
+
+    let mut cursor = TailCursor::new(&mut buf[..2]);
+    cursor.push_varint_len(1).unwrap();
+    assert!(matches!(
+        cursor.push_varint_len(300),
+        Err(Error::BufferOverflow {
+            needed: 3,
+            available: 2
+        })
+    ));
+    assert_eq!(cursor.pop_varint_len().unwrap(), 1);
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn tail_cursor_serialized() {
+    use ordcode::buf::TailCursor;
+
+    // lengths of variable-size fields are read in order of fields
+    let buf = ser_to_vec_ordered(
+        &(vec![1_u8; 300], 7_u8, "abc", vec![(); 5]),
+        Order::Ascending,
+    )
+    .unwrap();
+    let mut cursor = TailCursor::new(&buf[..]);
+    assert_eq!(cursor.pop_varint_len().unwrap(), 300);
+    assert_eq!(cursor.pop_varint_len().unwrap(), 3);
+    assert_eq!(cursor.pop_varint_len().unwrap(), 5);
+    assert_eq!(cursor.tail_len(), 0);
+}
+
+#[test]
+fn encoded_len_boundaries() {
+    // each length adds 7 bits of payload, up to 8 bytes; 9-byte encoding holds full `u64`
+    for len in 1..=8_u32 {
+        let max = (1_u64 << (7 * len)) - 1;
+        assert_eq!(encoded_len(max), len as usize);
+        assert_eq!(encoded_len(max + 1), len as usize + 1);
+        assert_eq!(encode64(max).len(), encoded_len(max));
+        assert_eq!(encode64(max + 1).len(), encoded_len(max + 1));
+    }
+    assert_eq!(encoded_len(0), 1);
+    assert_eq!(encoded_len(127), 1);
+    assert_eq!(encoded_len(128), 2);
+    assert_eq!(encoded_len(16_383), 2);
+    assert_eq!(encoded_len(16_384), 3);
+    assert_eq!(encoded_len(u64::MAX), 9);
+    assert_eq!(encoded_len(u64::MAX), <u64 as VarUInt>::VARU_MAX_LEN);
+
+    assert_eq!(primitives::encoded_len_char(), 4);
+    assert_eq!(primitives::encoded_len_bool(), 1);
+    assert_eq!(primitives::encoded_len_f32(), 4);
+    assert_eq!(primitives::encoded_len_f64(), 8);
+}
+
+#[test]
+fn u128_boundaries() {
+    fn encode(value: u128) -> Vec<u8> {
+        let mut s = Vec::new();
+        encode_u128(value, &mut s).unwrap();
+        s
+    }
+    let cases: &[(u128, usize)] = &[
+        (0, 1),
+        (127, 1),
+        (128, 2),
+        ((1 << 56) - 1, 8),
+        (1 << 56, 10),
+        (u128::from(u64::MAX), 10),
+        (u128::from(u64::MAX) + 1, 11),
+        (1 << 120, 18),
+        (u128::MAX, 18),
+    ];
+    for &(value, len) in cases {
+        let buf = encode(value);
+        assert_eq!(buf.len(), len, "{value}");
+        let mut reader = DeBytesReader::new(&buf);
+        assert_eq!(decode_u128(&mut reader).unwrap(), value);
+        assert!(reader.is_complete().is_ok());
+    }
+    // small values are compatible with `u64` varints
+    assert_eq!(encode(300), encode64(300).as_ref());
+    assert_eq!(
+        decode_u128(DeBytesReader::new(&encode64(300))).unwrap(),
+        300
+    );
+
+    assert!(matches!(
+        decode_u128(DeBytesReader::new(&[0_u8, 17])),
+        Err(Error::InvalidVarintEncoding)
+    ));
+    assert!(decode_u128(DeBytesReader::new(&encode(u128::MAX)[..17])).is_err());
+}
+
+#[test]
+fn strict_rejects_overlong() {
+    // 5 encoded with 2 bytes instead of 1
+    let overlong = [(5 << 1 | 1) << 1, 0];
+    // lenient decoding accepts it, except in debug builds
+    #[cfg(not(debug_assertions))]
+    {
+        assert_eq!(
+            u64::varu_from_reader(DeBytesReader::new(&overlong)).unwrap(),
+            5
+        );
+        assert_eq!(
+            u32::varu_from_reader(DeBytesReader::new(&overlong)).unwrap(),
+            5
+        );
+    }
+    assert!(matches!(
+        u64::varu_from_reader_strict(DeBytesReader::new(&overlong)).unwrap_err(),
+        Error::InvalidVarintEncoding
+    ));
+    assert!(matches!(
+        u32::varu_from_reader_strict(DeBytesReader::new(&overlong)).unwrap_err(),
+        Error::InvalidVarintEncoding
+    ));
+    // 9-byte encoding of a value which fits into 8 bytes
+    let mut overlong = [0_u8; 9];
+    overlong[1] = 1;
+    #[cfg(not(debug_assertions))]
+    assert_eq!(
+        u64::varu_from_reader(DeBytesReader::new(&overlong)).unwrap(),
+        1
+    );
+    assert!(u64::varu_from_reader_strict(DeBytesReader::new(&overlong)).is_err());
+    // 5-byte `u32` encoding with unused bits of the first byte cleared
+    let mut buf = [0xf0_u8, 0, 0, 0, 0x80];
+    assert_eq!(
+        u32::varu_from_reader_strict(DeBytesReader::new(&buf)).unwrap(),
+        0x8000_0000
+    );
+    buf[0] = 0x10;
+    assert!(u32::varu_from_reader_strict(DeBytesReader::new(&buf)).is_err());
+    assert_eq!(
+        u32::varu_from_reader(DeBytesReader::new(&buf)).unwrap(),
+        0x8000_0000
+    );
+
+    // canonical encodings are accepted at all boundaries
+    for &v in &[
+        0,
+        127,
+        128,
+        (1 << 14) - 1,
+        1 << 14,
+        (1 << 56) - 1,
+        1 << 56,
+        u64::MAX,
+    ] {
+        let buf = encode64(v);
+        assert_eq!(
+            u64::varu_from_reader_strict(DeBytesReader::new(&buf)).unwrap(),
+            v
+        );
+    }
+    for &v in &[0, 127, 128, (1 << 28) - 1, 1 << 28, u32::MAX] {
+        let mut buf = Vec::new();
+        v.varu_to_writer(&mut buf).unwrap();
+        assert_eq!(
+            u32::varu_from_reader_strict(DeBytesReader::new(&buf)).unwrap(),
+            v
+        );
+    }
+}
+
+#[test]
+fn strict_default_impl() {
+    // implementation which relies on default `varu_from_reader_strict()`
+    struct V(u64);
+    impl VarUInt for V {
+        const VARU_MAX_LEN: usize = u64::VARU_MAX_LEN;
+        fn varu_encoded_len(&self) -> u8 {
+            self.0.varu_encoded_len()
+        }
+        fn varu_decoded_len(first_byte: u8) -> u8 {
+            u64::varu_decoded_len(first_byte)
+        }
+        fn varu_to_writer(&self, writer: impl WriteBytes) -> Result {
+            self.0.varu_to_writer(writer)
+        }
+        fn varu_from_reader(reader: impl ReadBytes) -> Result<Self> {
+            u64::varu_from_reader(reader).map(V)
+        }
+        fn varu_from_slice(bytes: &[u8]) -> Result<(Self, u8)> {
+            u64::varu_from_slice(bytes).map(|(v, len)| (V(v), len))
+        }
+        fn varu_to_slice(&self, bytes: &mut [u8]) -> u8 {
+            self.0.varu_to_slice(bytes)
+        }
+    }
+    for &v in &[0, 127, 128, 1 << 56, u64::MAX] {
+        let buf = encode64(v);
+        let mut r = DeBytesReader::new(&buf);
+        assert_eq!(V::varu_from_reader_strict(&mut r).unwrap().0, v);
+        r.is_complete().unwrap();
+    }
+    let overlong = [(5 << 1 | 1) << 1, 0];
+    assert!(matches!(
+        V::varu_from_reader_strict(DeBytesReader::new(&overlong)),
+        Err(Error::InvalidVarintEncoding)
+    ));
+}